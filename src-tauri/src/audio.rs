@@ -0,0 +1,447 @@
+use crate::rarity::RarityTier;
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{Manager, State};
+
+const AUDIO_SETTINGS: &str = "audio";
+
+/// Extensions the decoder understands.
+const SUPPORTED_EXTENSIONS: [&str; 5] = ["mp3", "ogg", "wav", "flac", "oga"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub enabled: bool,
+    /// Playback volume from 0.0 to 1.0.
+    pub volume: f32,
+    /// Name of a folder under `UnlockIt/sounds` whose `pack.json` provides the sounds.
+    pub sound_pack: Option<String>,
+    /// Sound per rarity tier, overriding the pack and the sound picked in the profile.
+    pub rarity_sounds: BTreeMap<RarityTier, String>,
+    /// Sound per appid, overriding everything else.
+    pub game_sounds: BTreeMap<String, String>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 0.8,
+            sound_pack: None,
+            rarity_sounds: BTreeMap::new(),
+            game_sounds: BTreeMap::new(),
+        }
+    }
+}
+
+/// `sounds/<pack>/pack.json`; file names are relative to the pack folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundPackManifest {
+    pub name: Option<String>,
+    pub default: Option<String>,
+    pub rarity: BTreeMap<RarityTier, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SoundPack {
+    pub id: String,
+    pub name: String,
+    pub sounds: Vec<String>,
+}
+
+pub trait AudioOutput: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Starts playing `path` without waiting for it to finish.
+    fn play(&self, path: &Path, volume: f32) -> Result<(), String>;
+}
+
+/// Decodes MP3/OGG/WAV/FLAC with rodio and plays on the default output device.
+pub struct RodioOutput;
+
+impl AudioOutput for RodioOutput {
+    fn name(&self) -> &'static str {
+        "rodio"
+    }
+
+    fn play(&self, path: &Path, volume: f32) -> Result<(), String> {
+        let file = fs::File::open(path)
+            .map_err(|e| format!("Failed to open sound file {:?}: {}", path, e))?;
+        let decoder = rodio::Decoder::new(std::io::BufReader::new(file))
+            .map_err(|e| format!("Failed to decode sound file {:?}: {}", path, e))?;
+
+        // The output stream is not Send, so it lives on the thread that plays the sound.
+        std::thread::spawn(move || {
+            let (_stream, handle) = match rodio::OutputStream::try_default() {
+                Ok(output) => output,
+                Err(e) => {
+                    println!("No audio output device available: {}", e);
+                    return;
+                }
+            };
+            match rodio::Sink::try_new(&handle) {
+                Ok(sink) => {
+                    sink.set_volume(volume);
+                    sink.append(decoder);
+                    sink.sleep_until_end();
+                }
+                Err(e) => println!("Failed to create audio sink: {}", e),
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Plays nothing and remembers what it was asked to play. Used headless and in tests.
+#[derive(Default)]
+pub struct NullOutput {
+    played: Mutex<Vec<(PathBuf, f32)>>,
+}
+
+impl NullOutput {
+    pub fn played(&self) -> Vec<(PathBuf, f32)> {
+        self.played.lock().unwrap().clone()
+    }
+}
+
+impl AudioOutput for NullOutput {
+    fn name(&self) -> &'static str {
+        "null"
+    }
+
+    fn play(&self, path: &Path, volume: f32) -> Result<(), String> {
+        self.played
+            .lock()
+            .unwrap()
+            .push((path.to_path_buf(), volume));
+        Ok(())
+    }
+}
+
+/// Managed state that picks and plays unlock sounds.
+pub struct AudioPlayer {
+    output: Box<dyn AudioOutput>,
+    settings: Mutex<AudioSettings>,
+    /// Where bundled sounds (`ps5.mp3`, `xbox-360.mp3`, ...) can be found.
+    bundled_dirs: Vec<PathBuf>,
+    packs_dir: PathBuf,
+}
+
+impl AudioPlayer {
+    pub fn new(
+        output: Box<dyn AudioOutput>,
+        settings: AudioSettings,
+        bundled_dirs: Vec<PathBuf>,
+        packs_dir: PathBuf,
+    ) -> Self {
+        Self {
+            output,
+            settings: Mutex::new(settings),
+            bundled_dirs,
+            packs_dir,
+        }
+    }
+
+    /// Uses rodio unless `UNLOCKIT_AUDIO=null` asks for a silent output.
+    pub fn for_platform(app_handle: &tauri::AppHandle, storage: &Storage) -> Self {
+        let output: Box<dyn AudioOutput> = match std::env::var("UNLOCKIT_AUDIO").as_deref() {
+            Ok("null") => Box::new(NullOutput::default()),
+            _ => Box::new(RodioOutput),
+        };
+        println!("Using '{}' audio output", output.name());
+
+        // `../public/*.mp3` is bundled under `_up_/public` in the resource dir.
+        let mut bundled_dirs = Vec::new();
+        if let Ok(resource_dir) = app_handle.path().resource_dir() {
+            bundled_dirs.push(resource_dir.join("_up_").join("public"));
+            bundled_dirs.push(resource_dir);
+        }
+
+        Self::new(
+            output,
+            storage.load_settings(AUDIO_SETTINGS),
+            bundled_dirs,
+            storage.sounds_dir(),
+        )
+    }
+
+    pub fn settings(&self) -> AudioSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: AudioSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    /// Picks the sound for an unlock: per-game, then per-rarity, then the active
+    /// sound pack, then `requested` (the sound chosen in the profile).
+    pub fn resolve_sound(
+        &self,
+        app_id: Option<&str>,
+        rarity: RarityTier,
+        requested: Option<&str>,
+    ) -> Option<PathBuf> {
+        let settings = self.settings();
+        if !settings.enabled {
+            return None;
+        }
+
+        let pack_sound = settings.sound_pack.as_deref().and_then(|pack| {
+            let manifest = self.load_pack_manifest(pack);
+            manifest
+                .rarity
+                .get(&rarity)
+                .or(manifest.default.as_ref())
+                .map(|file| format!("{}/{}", pack, file))
+        });
+
+        let candidates = [
+            app_id.and_then(|id| settings.game_sounds.get(id).cloned()),
+            settings.rarity_sounds.get(&rarity).cloned(),
+            pack_sound,
+            requested.filter(|r| !r.is_empty()).map(String::from),
+        ];
+
+        candidates.into_iter().flatten().find_map(|sound| {
+            let path = self.locate(&sound);
+            if path.is_none() {
+                println!("Sound not found: {}", sound);
+            }
+            path
+        })
+    }
+
+    /// Resolves a sound reference: an absolute path, a bundled file name, or
+    /// `<pack>/<file>` inside the sound packs folder.
+    pub fn locate(&self, sound: &str) -> Option<PathBuf> {
+        let direct = PathBuf::from(sound);
+        if direct.is_absolute() {
+            return direct.is_file().then_some(direct);
+        }
+
+        self.bundled_dirs
+            .iter()
+            .chain(std::iter::once(&self.packs_dir))
+            .map(|dir| dir.join(sound))
+            .find(|path| path.is_file())
+    }
+
+    pub fn play(&self, path: &Path) {
+        let volume = self.settings().volume.clamp(0.0, 1.0);
+        match self.output.play(path, volume) {
+            Ok(()) => println!("Playing sound {:?} at volume {:.2}", path, volume),
+            Err(e) => println!("Failed to play sound: {}", e),
+        }
+    }
+
+    fn load_pack_manifest(&self, pack: &str) -> SoundPackManifest {
+        let path = self.packs_dir.join(pack).join("pack.json");
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn list_packs(&self) -> Vec<SoundPack> {
+        let mut packs = Vec::new();
+        let Ok(entries) = fs::read_dir(&self.packs_dir) else {
+            return packs;
+        };
+
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            let id = entry.file_name().to_string_lossy().to_string();
+            let mut sounds: Vec<String> = fs::read_dir(&dir)
+                .map(|files| {
+                    files
+                        .flatten()
+                        .map(|file| file.path())
+                        .filter(|path| is_supported(path))
+                        .filter_map(|path| {
+                            path.file_name()
+                                .map(|name| format!("{}/{}", id, name.to_string_lossy()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            sounds.sort();
+
+            let name = self
+                .load_pack_manifest(&id)
+                .name
+                .unwrap_or_else(|| id.clone());
+            packs.push(SoundPack { id, name, sounds });
+        }
+
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        packs
+    }
+}
+
+fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+pub fn get_audio_settings(audio: State<'_, AudioPlayer>) -> AudioSettings {
+    audio.settings()
+}
+
+#[tauri::command]
+pub fn set_audio_settings(
+    audio: State<'_, AudioPlayer>,
+    storage: State<'_, Storage>,
+    settings: AudioSettings,
+) -> Result<(), String> {
+    storage.save_settings(AUDIO_SETTINGS, &settings)?;
+    audio.set_settings(settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_sound_packs(audio: State<'_, AudioPlayer>) -> Vec<SoundPack> {
+    audio.list_packs()
+}
+
+/// Plays a sound reference at the configured volume, for the settings screen.
+#[tauri::command]
+pub fn preview_sound(audio: State<'_, AudioPlayer>, sound: String) -> Result<(), String> {
+    let path = audio
+        .locate(&sound)
+        .ok_or_else(|| format!("Sound not found: {}", sound))?;
+    audio.play(&path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Lets the test keep a handle on the output the player owns.
+    struct SharedOutput(Arc<NullOutput>);
+
+    impl AudioOutput for SharedOutput {
+        fn name(&self) -> &'static str {
+            self.0.name()
+        }
+
+        fn play(&self, path: &Path, volume: f32) -> Result<(), String> {
+            self.0.play(path, volume)
+        }
+    }
+
+    fn player(dir: &Path, settings: AudioSettings) -> (AudioPlayer, Arc<NullOutput>) {
+        let output = Arc::new(NullOutput::default());
+        let player = AudioPlayer::new(
+            Box::new(SharedOutput(output.clone())),
+            settings,
+            vec![dir.join("bundled")],
+            dir.join("sounds"),
+        );
+        (player, output)
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"sound").unwrap();
+    }
+
+    #[test]
+    fn resolve_sound_prefers_game_then_rarity_then_pack_then_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundled = dir.path().join("bundled");
+        let pack = dir.path().join("sounds").join("retro");
+        for file in ["game.mp3", "rare.mp3", "profile.mp3"] {
+            touch(&bundled.join(file));
+        }
+        touch(&pack.join("epic.ogg"));
+        touch(&pack.join("default.ogg"));
+        fs::write(
+            pack.join("pack.json"),
+            r#"{ "default": "default.ogg", "rarity": { "Epic": "epic.ogg" } }"#,
+        )
+        .unwrap();
+
+        let mut settings = AudioSettings {
+            sound_pack: Some("retro".to_string()),
+            ..AudioSettings::default()
+        };
+        settings
+            .rarity_sounds
+            .insert(RarityTier::Rare, "rare.mp3".to_string());
+        settings
+            .game_sounds
+            .insert("480".to_string(), "game.mp3".to_string());
+        let (player, _) = player(dir.path(), settings.clone());
+        let resolve = |app_id, rarity| player.resolve_sound(app_id, rarity, Some("profile.mp3"));
+
+        assert_eq!(
+            resolve(Some("480"), RarityTier::Rare),
+            Some(bundled.join("game.mp3"))
+        );
+        assert_eq!(
+            resolve(Some("220"), RarityTier::Rare),
+            Some(bundled.join("rare.mp3"))
+        );
+        assert_eq!(resolve(None, RarityTier::Epic), Some(pack.join("epic.ogg")));
+        assert_eq!(
+            resolve(None, RarityTier::Common),
+            Some(pack.join("default.ogg"))
+        );
+
+        // A sound that cannot be found falls through to the next candidate.
+        settings
+            .game_sounds
+            .insert("480".to_string(), "missing.mp3".to_string());
+        settings.sound_pack = None;
+        player.set_settings(settings.clone());
+        assert_eq!(
+            resolve(Some("480"), RarityTier::Rare),
+            Some(bundled.join("rare.mp3"))
+        );
+        assert_eq!(
+            resolve(None, RarityTier::Common),
+            Some(bundled.join("profile.mp3"))
+        );
+        assert_eq!(
+            player.resolve_sound(None, RarityTier::Common, Some("")),
+            None
+        );
+
+        settings.enabled = false;
+        player.set_settings(settings);
+        assert_eq!(resolve(Some("480"), RarityTier::Rare), None);
+    }
+
+    #[test]
+    fn play_clamps_the_configured_volume() {
+        let dir = tempfile::tempdir().unwrap();
+        let sound = dir.path().join("bundled").join("ps5.mp3");
+        touch(&sound);
+        let (player, output) = player(dir.path(), AudioSettings::default());
+
+        for volume in [0.5, 1.5, -0.2] {
+            player.set_settings(AudioSettings {
+                volume,
+                ..AudioSettings::default()
+            });
+            player.play(&sound);
+        }
+
+        let volumes: Vec<f32> = output.played().into_iter().map(|(_, v)| v).collect();
+        assert_eq!(volumes, vec![0.5, 1.0, 0.0]);
+        assert!(output.played().iter().all(|(path, _)| path == &sound));
+    }
+}
@@ -0,0 +1,552 @@
+//! Whole-library backup to a single zip archive, and restore from it.
+//!
+//! Archive layout (`BACKUP_VERSION` 1):
+//! - `manifest.json` — [`BackupManifest`]
+//! - `library.db` — snapshot of the library database (games, unlocks, playtime, sessions)
+//! - `stores/<name>.json` — the frontend's `tauri-plugin-store` files
+//! - `settings/<name>.json` — backend settings
+//! - `images/<appid>/...` — custom covers and wallpapers
+//! - `local/assets/...`, `local/achievements/...` — art and schemas cached by the frontend
+
+use crate::db::{LibraryDb, RestoreMode};
+use crate::gc::{remove_empty_dirs, walk_files};
+use crate::storage::{rewrite_path_prefix, write_atomic, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use tauri_plugin_store::StoreExt;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const BACKUP_FORMAT: &str = "unlockit-backup";
+pub const BACKUP_VERSION: u32 = 1;
+
+/// `tauri-plugin-store` files the frontend keeps in the app data dir.
+const STORE_FILES: [&str; 5] = [
+    "my-games.json",
+    "achievements.json",
+    "howlongtobeat.json",
+    "profile.json",
+    "trackedAchievementsFiles.json",
+];
+
+/// Directories under the app local data dir that are worth carrying over.
+const LOCAL_DIRS: [&str; 2] = ["assets", "achievements"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    pub app_version: String,
+    pub platform: String,
+    /// Directories of the machine the backup was made on, used to rewrite
+    /// absolute paths on restore.
+    pub app_data_dir: String,
+    pub app_local_data_dir: String,
+    pub games: Vec<BackupGame>,
+    pub entries: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupGame {
+    pub app_id: String,
+    pub exe_path: Option<String>,
+}
+
+/// Prefix replacement for executable paths, e.g. `D:\Games` → `E:\Games`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathMapping {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// The game exists locally with a different executable; the local one was kept.
+    GameExists,
+    /// A store entry exists on both sides with different content; the local one was kept.
+    EntryExists,
+    /// After remapping, the executable does not exist on this machine.
+    ExeNotFound,
+    /// A file exists locally with different content; the local one was kept.
+    FileExists,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupConflict {
+    pub kind: ConflictKind,
+    pub app_id: Option<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemappedPath {
+    pub app_id: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub games: usize,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub mode: RestoreMode,
+    pub manifest: BackupManifest,
+    pub files_restored: usize,
+    pub files_skipped: usize,
+    /// Local stores and images the backup does not have, removed in replace mode.
+    pub files_removed: usize,
+    pub remapped: Vec<RemappedPath>,
+    pub conflicts: Vec<BackupConflict>,
+    /// The frontend keeps its own copy of the stores and must reload to see restored data.
+    pub restart_required: bool,
+}
+
+#[tauri::command]
+pub fn export_backup(
+    storage: State<'_, Storage>,
+    db: State<'_, LibraryDb>,
+    path: String,
+) -> Result<ExportReport, String> {
+    let target = PathBuf::from(&path);
+    let partial = target.with_extension("zip.partial");
+
+    let snapshot = storage.temp_dir().join("unlockit-backup-library.db");
+    db.snapshot_to(&snapshot)?;
+    let games = db
+        .game_exe_paths()?
+        .into_iter()
+        .map(|(app_id, exe_path)| BackupGame {
+            app_id,
+            exe_path: Some(exe_path),
+        })
+        .collect::<Vec<_>>();
+
+    let mut files: Vec<(String, PathBuf)> = vec![("library.db".to_string(), snapshot.clone())];
+    for store in STORE_FILES {
+        let source = storage.app_data_dir().join(store);
+        if source.exists() {
+            files.push((format!("stores/{}", store), source));
+        }
+    }
+    files.extend(tree_entries(&storage.root().join("settings"), "settings"));
+    files.extend(tree_entries(&storage.images_root(), "images"));
+    for dir in LOCAL_DIRS {
+        files.extend(tree_entries(
+            &storage.app_local_data_dir().join(dir),
+            &format!("local/{}", dir),
+        ));
+    }
+
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: chrono::Utc::now().timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: std::env::consts::OS.to_string(),
+        app_data_dir: storage.app_data_dir().to_string_lossy().to_string(),
+        app_local_data_dir: storage.app_local_data_dir().to_string_lossy().to_string(),
+        games,
+        entries: files.iter().map(|(name, _)| name.clone()).collect(),
+    };
+
+    let result = write_archive(&partial, &manifest, &files);
+    let _ = fs::remove_file(&snapshot);
+    let bytes = result?;
+    fs::rename(&partial, &target).map_err(|e| format!("Failed to finalize backup: {}", e))?;
+
+    println!(
+        "Exported backup with {} files ({} bytes) to {:?}",
+        files.len(),
+        bytes,
+        target
+    );
+    Ok(ExportReport {
+        path,
+        games: manifest.games.len(),
+        files: files.len(),
+        bytes,
+    })
+}
+
+fn tree_entries(root: &Path, prefix: &str) -> Vec<(String, PathBuf)> {
+    walk_files(root)
+        .into_iter()
+        .filter_map(|(path, _)| {
+            let relative = path.strip_prefix(root).ok()?;
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some((format!("{}/{}", prefix, name), path))
+        })
+        .collect()
+}
+
+fn write_archive(
+    target: &Path,
+    manifest: &BackupManifest,
+    files: &[(String, PathBuf)],
+) -> Result<u64, String> {
+    let file =
+        fs::File::create(target).map_err(|e| format!("Failed to create backup file: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize backup manifest: {}", e))?;
+    zip.start_file("manifest.json", options)
+        .map_err(|e| format!("Failed to write backup manifest: {}", e))?;
+    zip.write_all(&manifest_json)
+        .map_err(|e| format!("Failed to write backup manifest: {}", e))?;
+
+    for (name, source) in files {
+        let mut input =
+            fs::File::open(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+        std::io::copy(&mut input, &mut zip)
+            .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+    }
+
+    let file = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish backup archive: {}", e))?;
+    file.metadata()
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read backup size: {}", e))
+}
+
+#[tauri::command]
+pub fn import_backup(
+    app_handle: tauri::AppHandle,
+    storage: State<'_, Storage>,
+    db: State<'_, LibraryDb>,
+    path: String,
+    mode: RestoreMode,
+    path_mappings: Option<Vec<PathMapping>>,
+) -> Result<ImportReport, String> {
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("Failed to read backup archive: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    let path_mappings = path_mappings.unwrap_or_default();
+
+    let mut report = ImportReport {
+        mode,
+        manifest: manifest.clone(),
+        files_restored: 0,
+        files_skipped: 0,
+        files_removed: 0,
+        remapped: Vec::new(),
+        conflicts: Vec::new(),
+        restart_required: true,
+    };
+
+    // Library database: restore a migrated, remapped copy of the snapshot.
+    let snapshot = storage.temp_dir().join("unlockit-restore-library.db");
+    extract_entry(&mut archive, "library.db", &snapshot)?;
+    let restore_result = (|| {
+        let restored = LibraryDb::open(&snapshot)?;
+        for mapping in &path_mappings {
+            for (app_id, from, to) in restored.remap_game_paths(&mapping.from, &mapping.to)? {
+                report.remapped.push(RemappedPath { app_id, from, to });
+            }
+        }
+        for (app_id, exe_path) in restored.game_exe_paths()? {
+            if !Path::new(&exe_path).exists() {
+                report.conflicts.push(BackupConflict {
+                    kind: ConflictKind::ExeNotFound,
+                    app_id: Some(app_id),
+                    detail: exe_path,
+                });
+            }
+        }
+        drop(restored);
+
+        for app_id in db.restore_from(&snapshot, mode)? {
+            report.conflicts.push(BackupConflict {
+                kind: ConflictKind::GameExists,
+                app_id: Some(app_id),
+                detail: "Kept the local executable path".to_string(),
+            });
+        }
+        Ok::<(), String>(())
+    })();
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{}", snapshot.to_string_lossy(), suffix));
+    }
+    restore_result?;
+
+    // Paths recorded in the stores point at the old machine's directories.
+    let mut prefixes = vec![
+        (
+            manifest.app_local_data_dir.clone(),
+            storage.app_local_data_dir().to_string_lossy().to_string(),
+        ),
+        (
+            manifest.app_data_dir.clone(),
+            storage.app_data_dir().to_string_lossy().to_string(),
+        ),
+    ];
+    prefixes.extend(
+        path_mappings
+            .iter()
+            .map(|mapping| (mapping.from.clone(), mapping.to.clone())),
+    );
+
+    let mut restored_stores = HashSet::new();
+    let mut restored_images = HashSet::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry
+            .enclosed_name()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+        else {
+            println!("Skipping unsafe backup entry: {}", entry.name());
+            continue;
+        };
+
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {} from backup: {}", name, e))?;
+
+        if let Some(store) = name.strip_prefix("stores/") {
+            restore_store(&app_handle, store, &contents, mode, &prefixes, &mut report)?;
+            restored_stores.insert(store.to_string());
+        } else if let Some(target) = file_target(&storage, &name) {
+            restore_file(&target, &name, &contents, mode, &mut report)?;
+            if name.starts_with("images/") {
+                restored_images.insert(target);
+            }
+        }
+    }
+
+    if mode == RestoreMode::Replace {
+        remove_unrestored(
+            &app_handle,
+            &storage,
+            &restored_stores,
+            &restored_images,
+            &mut report,
+        )?;
+    }
+
+    println!(
+        "Imported backup {:?} ({:?}): {} files restored, {} skipped, {} removed, {} conflicts",
+        path,
+        mode,
+        report.files_restored,
+        report.files_skipped,
+        report.files_removed,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+fn read_manifest(archive: &mut ZipArchive<fs::File>) -> Result<BackupManifest, String> {
+    let mut entry = archive
+        .by_name("manifest.json")
+        .map_err(|_| "Not an UnlockIt backup: manifest.json is missing".to_string())?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read backup manifest: {}", e))?;
+    let manifest: BackupManifest =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid backup manifest: {}", e))?;
+
+    if manifest.format != BACKUP_FORMAT {
+        return Err(format!(
+            "Not an UnlockIt backup (format '{}')",
+            manifest.format
+        ));
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} is newer than supported version {}; update UnlockIt first",
+            manifest.version, BACKUP_VERSION
+        ));
+    }
+    Ok(manifest)
+}
+
+fn extract_entry(
+    archive: &mut ZipArchive<fs::File>,
+    name: &str,
+    target: &Path,
+) -> Result<(), String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("Backup is missing {}", name))?;
+    let mut output =
+        fs::File::create(target).map_err(|e| format!("Failed to extract {}: {}", name, e))?;
+    std::io::copy(&mut entry, &mut output)
+        .map_err(|e| format!("Failed to extract {}: {}", name, e))?;
+    Ok(())
+}
+
+/// Where a non-store archive entry lives on this machine.
+fn file_target(storage: &Storage, name: &str) -> Option<PathBuf> {
+    let (root, relative) = if let Some(rest) = name.strip_prefix("images/") {
+        (storage.images_root(), rest)
+    } else if let Some(rest) = name.strip_prefix("settings/") {
+        (storage.root().join("settings"), rest)
+    } else if let Some(rest) = name.strip_prefix("local/") {
+        let (dir, rest) = rest.split_once('/')?;
+        if !LOCAL_DIRS.contains(&dir) {
+            return None;
+        }
+        (storage.app_local_data_dir().join(dir), rest)
+    } else {
+        return None;
+    };
+    Some(root.join(relative))
+}
+
+fn restore_file(
+    target: &Path,
+    name: &str,
+    contents: &[u8],
+    mode: RestoreMode,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    if mode == RestoreMode::Merge && target.exists() {
+        let same = fs::read(target)
+            .map(|local| local == contents)
+            .unwrap_or(false);
+        if !same {
+            report.conflicts.push(BackupConflict {
+                kind: ConflictKind::FileExists,
+                app_id: None,
+                detail: name.to_string(),
+            });
+        }
+        report.files_skipped += 1;
+        return Ok(());
+    }
+    write_atomic(target, contents)?;
+    report.files_restored += 1;
+    Ok(())
+}
+
+/// Restores a store through `tauri-plugin-store` rather than its file: the plugin
+/// keeps loaded stores in memory and writes them back on exit, which would undo
+/// a restore made behind its back.
+fn restore_store(
+    app_handle: &tauri::AppHandle,
+    store: &str,
+    contents: &[u8],
+    mode: RestoreMode,
+    prefixes: &[(String, String)],
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    if !STORE_FILES.contains(&store) {
+        report.files_skipped += 1;
+        return Ok(());
+    }
+    let mut incoming: serde_json::Value = serde_json::from_slice(contents)
+        .map_err(|e| format!("Invalid {} in backup: {}", store, e))?;
+    for (from, to) in prefixes
+        .iter()
+        .filter(|(from, to)| !from.is_empty() && from != to)
+    {
+        rewrite_path_prefix(&mut incoming, from, to);
+    }
+
+    let Some(incoming) = incoming.as_object() else {
+        return Err(format!("Invalid {} in backup: not an object", store));
+    };
+
+    let handle = app_handle
+        .store(store)
+        .map_err(|e| format!("Failed to open {}: {}", store, e))?;
+    if mode == RestoreMode::Replace {
+        handle.clear();
+    }
+    let local: HashMap<String, serde_json::Value> = handle.entries().into_iter().collect();
+    for (key, value) in incoming {
+        match local.get(key) {
+            Some(existing) if existing != value => {
+                report.conflicts.push(BackupConflict {
+                    kind: ConflictKind::EntryExists,
+                    app_id: store_app_id(key),
+                    detail: format!("{}: {}", store, key),
+                });
+            }
+            Some(_) => {}
+            None => handle.set(key.clone(), value.clone()),
+        }
+    }
+    handle
+        .save()
+        .map_err(|e| format!("Failed to save {}: {}", store, e))?;
+    report.files_restored += 1;
+    Ok(())
+}
+
+/// Replace mode: empties the stores and deletes the images the backup does not have.
+fn remove_unrestored(
+    app_handle: &tauri::AppHandle,
+    storage: &Storage,
+    restored_stores: &HashSet<String>,
+    restored_images: &HashSet<PathBuf>,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    for store in STORE_FILES
+        .iter()
+        .filter(|store| !restored_stores.contains(**store))
+    {
+        if !storage.app_data_dir().join(store).exists() {
+            continue;
+        }
+        let handle = app_handle
+            .store(store)
+            .map_err(|e| format!("Failed to open {}: {}", store, e))?;
+        handle.clear();
+        handle
+            .save()
+            .map_err(|e| format!("Failed to save {}: {}", store, e))?;
+        report.files_removed += 1;
+    }
+
+    let images_root = storage.images_root();
+    for (path, _) in walk_files(&images_root) {
+        if restored_images.contains(&path) {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => report.files_removed += 1,
+            Err(e) => println!("Failed to remove {:?}: {}", path, e),
+        }
+    }
+    remove_empty_dirs(&images_root);
+    Ok(())
+}
+
+/// Appid encoded in a store key (`game_<id>`, `achievements_<id>`, `<id>_beatTime`).
+fn store_app_id(key: &str) -> Option<String> {
+    key.strip_prefix("game_")
+        .or_else(|| key.strip_prefix("achievements_"))
+        .or_else(|| key.strip_suffix("_beatTime"))
+        .map(String::from)
+}
@@ -0,0 +1,276 @@
+//! Database side of `export_backup` / `import_backup`.
+
+use super::{db_err, LibraryDb};
+use std::path::Path;
+
+/// Tables copied between a backup and the live database, in dependency order.
+const TABLES: [&str; 5] = ["games", "achievements", "unlocks", "sessions", "metadata"];
+
+/// How [`LibraryDb::restore_from`] combines the backup with local data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// Keep local data and add what only the backup has; unlocks and playtime are combined.
+    Merge,
+    /// Drop local data and take the backup as-is.
+    Replace,
+}
+
+impl LibraryDb {
+    /// Writes a consistent copy of the database to `target`, even while it is in use.
+    pub fn snapshot_to(&self, target: &Path) -> Result<(), String> {
+        if target.exists() {
+            std::fs::remove_file(target)
+                .map_err(|e| format!("Failed to remove old snapshot: {}", e))?;
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute("VACUUM INTO ?1", [target.to_string_lossy()])
+            .map_err(db_err("snapshot library database"))?;
+        Ok(())
+    }
+
+    /// Rewrites `exe_path` and `install_dir` prefixes, used on a restored copy
+    /// before it is merged. Returns `(app_id, old, new)` for every changed exe path.
+    pub fn remap_game_paths(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<(String, String, String)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_id, exe_path FROM games WHERE substr(exe_path, 1, length(?1)) = ?1",
+            )
+            .map_err(db_err("find game paths"))?;
+        let changed = stmt
+            .query_map([from], |row| {
+                let app_id: String = row.get(0)?;
+                let old: String = row.get(1)?;
+                let new = format!("{}{}", to, &old[from.len()..]);
+                Ok((app_id, old, new))
+            })
+            .map_err(db_err("find game paths"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err("read game paths"))?;
+
+        for column in ["exe_path", "install_dir"] {
+            conn.execute(
+                &format!(
+                    "UPDATE games SET {0} = ?2 || substr({0}, length(?1) + 1)
+                     WHERE substr({0}, 1, length(?1)) = ?1",
+                    column
+                ),
+                [from, to],
+            )
+            .map_err(db_err("remap game paths"))?;
+        }
+        Ok(changed)
+    }
+
+    /// `(app_id, exe_path)` for every game with an executable.
+    pub fn game_exe_paths(&self) -> Result<Vec<(String, String)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_id, exe_path FROM games WHERE exe_path IS NOT NULL AND exe_path != ''",
+            )
+            .map_err(db_err("list game paths"))?;
+        let paths = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(db_err("list game paths"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err("read game paths"))?;
+        Ok(paths)
+    }
+
+    /// Copies the contents of the database at `backup` into this one. Returns the
+    /// appids present on both sides with a different executable, which were kept
+    /// as they are locally.
+    pub fn restore_from(&self, backup: &Path, mode: RestoreMode) -> Result<Vec<String>, String> {
+        let mut conn = self.conn.lock().unwrap();
+        conn.execute("ATTACH DATABASE ?1 AS backup", [backup.to_string_lossy()])
+            .map_err(db_err("open backup database"))?;
+
+        let result = (|| {
+            let tx = conn.transaction().map_err(db_err("start restore"))?;
+
+            let conflicts = if mode == RestoreMode::Merge {
+                let mut stmt = tx
+                    .prepare(
+                        "SELECT b.app_id FROM backup.games b
+                         JOIN main.games g ON g.app_id = b.app_id
+                         WHERE IFNULL(g.exe_path, '') != IFNULL(b.exe_path, '')
+                           AND IFNULL(g.exe_path, '') != ''",
+                    )
+                    .map_err(db_err("compare games"))?;
+                let conflicts = stmt
+                    .query_map([], |row| row.get(0))
+                    .map_err(db_err("compare games"))?
+                    .collect::<Result<Vec<String>, _>>()
+                    .map_err(db_err("compare games"))?;
+                conflicts
+            } else {
+                for table in TABLES {
+                    tx.execute(&format!("DELETE FROM main.{}", table), [])
+                        .map_err(db_err("clear library database"))?;
+                }
+                Vec::new()
+            };
+
+            let statements: [&str; 6] = match mode {
+                RestoreMode::Replace => [
+                    "INSERT INTO main.games SELECT * FROM backup.games",
+                    "INSERT INTO main.achievements SELECT * FROM backup.achievements",
+                    "INSERT INTO main.unlocks SELECT * FROM backup.unlocks",
+                    "INSERT INTO main.sessions (app_id, started_at, ended_at, duration_seconds)
+                     SELECT app_id, started_at, ended_at, duration_seconds FROM backup.sessions",
+                    "INSERT INTO main.metadata SELECT * FROM backup.metadata",
+                    "INSERT OR IGNORE INTO main.legacy_imports SELECT * FROM backup.legacy_imports",
+                ],
+                RestoreMode::Merge => [
+                    "INSERT INTO main.games SELECT * FROM backup.games WHERE true
+                     ON CONFLICT (app_id) DO UPDATE SET
+                        playtime_seconds = MAX(playtime_seconds, excluded.playtime_seconds)",
+                    "INSERT INTO main.achievements SELECT * FROM backup.achievements WHERE true
+                     ON CONFLICT (app_id, api_name) DO UPDATE SET
+                        global_percent = COALESCE(global_percent, excluded.global_percent)",
+                    "INSERT INTO main.unlocks SELECT * FROM backup.unlocks WHERE true
+                     ON CONFLICT (app_id, api_name) DO UPDATE SET
+                        unlocked = MAX(unlocked, excluded.unlocked),
+                        unlock_time = CASE
+                            WHEN unlock_time IS NULL THEN excluded.unlock_time
+                            WHEN excluded.unlock_time IS NULL THEN unlock_time
+                            ELSE MIN(unlock_time, excluded.unlock_time) END,
+                        progress = MAX(IFNULL(progress, 0), IFNULL(excluded.progress, 0)),
+                        source = COALESCE(source, excluded.source),
+                        updated_at = MAX(updated_at, excluded.updated_at)",
+                    "INSERT INTO main.sessions (app_id, started_at, ended_at, duration_seconds)
+                     SELECT b.app_id, b.started_at, b.ended_at, b.duration_seconds
+                     FROM backup.sessions b
+                     WHERE NOT EXISTS (SELECT 1 FROM main.sessions s
+                                       WHERE s.app_id = b.app_id AND s.started_at = b.started_at)",
+                    "INSERT OR IGNORE INTO main.metadata SELECT * FROM backup.metadata",
+                    "INSERT OR IGNORE INTO main.legacy_imports SELECT * FROM backup.legacy_imports",
+                ],
+            };
+            for statement in statements {
+                tx.execute(statement, [])
+                    .map_err(db_err("restore library data"))?;
+            }
+
+            tx.commit().map_err(db_err("commit restore"))?;
+            Ok(conflicts)
+        })();
+
+        if let Err(e) = conn.execute("DETACH DATABASE backup", []) {
+            println!("Failed to detach backup database: {}", e);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{AchievementUpdate, GameRecord};
+
+    fn add_game(db: &LibraryDb, app_id: &str, exe_path: &str, playtime: u64) {
+        db.upsert_game(&GameRecord {
+            app_id: app_id.to_string(),
+            name: format!("Game {}", app_id),
+            exe_path: Some(exe_path.to_string()),
+            ..GameRecord::default()
+        })
+        .unwrap();
+        db.record_playtime(app_id, playtime, 0).unwrap();
+    }
+
+    fn unlock(db: &LibraryDb, app_id: &str, api_name: &str, unlock_time: i64) {
+        db.upsert_unlocks(
+            app_id,
+            &[AchievementUpdate {
+                api_name: api_name.to_string(),
+                unlocked: Some(true),
+                unlock_time: Some(unlock_time),
+                ..AchievementUpdate::default()
+            }],
+        )
+        .unwrap();
+    }
+
+    /// A local library and a snapshot of a backup that overlaps it on game 480.
+    fn libraries(dir: &Path) -> (LibraryDb, std::path::PathBuf) {
+        let backup = LibraryDb::open(&dir.join("backup.db")).unwrap();
+        add_game(&backup, "480", "D:\\Games\\Spacewar.exe", 100);
+        add_game(&backup, "220", "D:\\Games\\hl2.exe", 50);
+        unlock(&backup, "480", "ACH_A", 100);
+        unlock(&backup, "480", "ACH_B", 150);
+        backup.record_playtime("220", 50, 50).unwrap();
+        let snapshot = dir.join("snapshot.db");
+        backup.snapshot_to(&snapshot).unwrap();
+
+        let local = LibraryDb::open(&dir.join("library.db")).unwrap();
+        add_game(&local, "480", "E:\\Games\\Spacewar.exe", 300);
+        add_game(&local, "10", "E:\\Games\\hl.exe", 10);
+        unlock(&local, "480", "ACH_A", 200);
+        (local, snapshot)
+    }
+
+    fn app_ids(db: &LibraryDb) -> Vec<String> {
+        let mut ids: Vec<String> = db
+            .list_games()
+            .unwrap()
+            .into_iter()
+            .map(|g| g.app_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn merge_keeps_local_games_and_combines_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let (local, snapshot) = libraries(dir.path());
+
+        let conflicts = local.restore_from(&snapshot, RestoreMode::Merge).unwrap();
+        assert_eq!(conflicts, ["480"]);
+        assert_eq!(app_ids(&local), ["10", "220", "480"]);
+
+        let spacewar = local.get_game("480").unwrap().unwrap();
+        assert_eq!(
+            spacewar.exe_path.as_deref(),
+            Some("E:\\Games\\Spacewar.exe")
+        );
+        assert_eq!(spacewar.playtime_seconds, 300);
+
+        let unlocks = local.achievement_state("480").unwrap().achievements;
+        assert_eq!(unlocks["ACH_A"].unlock_time, Some(100));
+        assert_eq!(unlocks["ACH_B"].unlock_time, Some(150));
+
+        // Merging the same backup twice does not duplicate sessions.
+        local.restore_from(&snapshot, RestoreMode::Merge).unwrap();
+        assert_eq!(local.list_sessions("220").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn replace_takes_the_backup_as_is() {
+        let dir = tempfile::tempdir().unwrap();
+        let (local, snapshot) = libraries(dir.path());
+
+        let conflicts = local.restore_from(&snapshot, RestoreMode::Replace).unwrap();
+        assert!(conflicts.is_empty());
+        assert_eq!(app_ids(&local), ["220", "480"]);
+
+        let spacewar = local.get_game("480").unwrap().unwrap();
+        assert_eq!(
+            spacewar.exe_path.as_deref(),
+            Some("D:\\Games\\Spacewar.exe")
+        );
+        assert_eq!(spacewar.playtime_seconds, 100);
+        assert_eq!(
+            local.achievement_state("480").unwrap().achievements["ACH_A"].unlock_time,
+            Some(100)
+        );
+        assert_eq!(local.list_sessions("220").unwrap().len(), 1);
+    }
+}
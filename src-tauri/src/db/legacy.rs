@@ -0,0 +1,383 @@
+//! One-time import of the JSON files that predate the library database.
+//!
+//! Each source is imported in its own transaction and recorded in
+//! `legacy_imports`, so a failure in one file does not block the others and a
+//! successful import never runs twice. The files themselves are left in place.
+
+use super::{now, LibraryDb};
+use crate::storage::Storage;
+use rusqlite::{params, OptionalExtension, Transaction};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+type Importer = fn(&Transaction, &Storage) -> Result<usize, String>;
+
+/// Order matters: games first, so the other sources can attach to them.
+const SOURCES: &[(&str, Importer)] = &[
+    ("my-games.json", import_games),
+    ("achievements.json", import_achievements),
+    ("howlongtobeat.json", import_how_long_to_beat),
+    ("playtimes.json", import_playtimes),
+    ("data.ini", import_data_ini),
+];
+
+pub fn import_all(db: &LibraryDb, storage: &Storage) {
+    for (source, importer) in SOURCES {
+        let result = db.with_transaction(|tx| {
+            let done: Option<i64> = tx
+                .query_row(
+                    "SELECT rows FROM legacy_imports WHERE source = ?1",
+                    [source],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to check legacy import: {}", e))?;
+            if done.is_some() {
+                return Ok(None);
+            }
+
+            let rows = importer(tx, storage)?;
+            tx.execute(
+                "INSERT INTO legacy_imports (source, imported_at, rows) VALUES (?1, ?2, ?3)",
+                params![source, now(), rows as i64],
+            )
+            .map_err(|e| format!("Failed to record legacy import: {}", e))?;
+            Ok(Some(rows))
+        });
+
+        match result {
+            Ok(Some(rows)) => println!("Imported {} rows from legacy {}", rows, source),
+            Ok(None) => {}
+            Err(e) => println!("Failed to import legacy {}: {}", source, e),
+        }
+    }
+}
+
+fn read_json(path: &Path) -> Result<Option<Value>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// `my-games.json`: `game_<appid>` → `GameStoreData`.
+fn import_games(tx: &Transaction, storage: &Storage) -> Result<usize, String> {
+    let Some(store) = read_json(&storage.app_data_dir().join("my-games.json"))? else {
+        return Ok(0);
+    };
+    let now = now();
+    let mut rows = 0;
+
+    for (key, game) in store.as_object().into_iter().flatten() {
+        let Some(app_id) = key.strip_prefix("game_") else {
+            continue;
+        };
+        let cover = str_field(game, "library_cover").or_else(|| str_field(game, "igdb_cover"));
+        tx.execute(
+            "INSERT INTO games (app_id, name, exe_path, install_dir, header_image, cover, status,
+                                playtime_seconds, data, added_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)
+             ON CONFLICT (app_id) DO NOTHING",
+            params![
+                app_id,
+                str_field(game, "name").unwrap_or_default(),
+                str_field(game, "exePath"),
+                str_field(game, "dir"),
+                str_field(game, "header_image"),
+                cover,
+                str_field(game, "status"),
+                game.get("playtime").and_then(|p| p.as_i64()).unwrap_or(0),
+                game.to_string(),
+                now
+            ],
+        )
+        .map_err(|e| format!("Failed to import game {}: {}", app_id, e))?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+/// `achievements.json`: `achievements_<appid>` → Steam schema with `achievedAt` per achievement.
+fn import_achievements(tx: &Transaction, storage: &Storage) -> Result<usize, String> {
+    let Some(store) = read_json(&storage.app_data_dir().join("achievements.json"))? else {
+        return Ok(0);
+    };
+    let now = now();
+    let mut rows = 0;
+
+    for (key, schema) in store.as_object().into_iter().flatten() {
+        let Some(app_id) = key.strip_prefix("achievements_") else {
+            continue;
+        };
+        let achievements = schema
+            .pointer("/game/availableGameStats/achievements")
+            .and_then(|a| a.as_array());
+
+        for achievement in achievements.into_iter().flatten() {
+            let Some(api_name) = str_field(achievement, "name") else {
+                continue;
+            };
+            // Percentages arrive as strings from the Steam API, numbers elsewhere.
+            let percent = achievement.get("percent").and_then(|p| {
+                p.as_f64()
+                    .or_else(|| p.as_str().and_then(|s| s.parse::<f64>().ok()))
+            });
+            tx.execute(
+                "INSERT INTO achievements (app_id, api_name, display_name, description, icon,
+                                           icon_gray, hidden, global_percent)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT (app_id, api_name) DO NOTHING",
+                params![
+                    app_id,
+                    api_name,
+                    str_field(achievement, "displayName"),
+                    str_field(achievement, "description"),
+                    str_field(achievement, "icon"),
+                    str_field(achievement, "icongray"),
+                    achievement
+                        .get("hidden")
+                        .and_then(|h| h.as_i64())
+                        .unwrap_or(0)
+                        != 0,
+                    percent
+                ],
+            )
+            .map_err(|e| format!("Failed to import achievement {}: {}", api_name, e))?;
+            rows += 1;
+
+            let achieved_at = achievement.get("achievedAt").and_then(|a| {
+                a.as_i64()
+                    .or_else(|| a.as_str().and_then(|s| s.trim().parse::<i64>().ok()))
+            });
+            if let Some(unlock_time) = achieved_at.filter(|t| *t > 0) {
+                tx.execute(
+                    "INSERT INTO unlocks (app_id, api_name, unlocked, unlock_time, source, updated_at)
+                     VALUES (?1, ?2, 1, ?3, 'legacy', ?4)
+                     ON CONFLICT (app_id, api_name) DO NOTHING",
+                    params![app_id, api_name, unlock_time, now],
+                )
+                .map_err(|e| format!("Failed to import unlock {}: {}", api_name, e))?;
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// `howlongtobeat.json`: `<appid>_beatTime` → HowLongToBeat search result.
+fn import_how_long_to_beat(tx: &Transaction, storage: &Storage) -> Result<usize, String> {
+    let Some(store) = read_json(&storage.app_data_dir().join("howlongtobeat.json"))? else {
+        return Ok(0);
+    };
+    let now = now();
+    let mut rows = 0;
+
+    for (key, value) in store.as_object().into_iter().flatten() {
+        let Some(app_id) = key.strip_suffix("_beatTime") else {
+            continue;
+        };
+        tx.execute(
+            "INSERT INTO metadata (app_id, key, value, updated_at) VALUES (?1, 'hltb', ?2, ?3)
+             ON CONFLICT (app_id, key) DO NOTHING",
+            params![app_id, value.to_string(), now],
+        )
+        .map_err(|e| format!("Failed to import HowLongToBeat data for {}: {}", app_id, e))?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+/// `UnlockIt/playtimes.json`: `{ "<appid>": seconds }`.
+fn import_playtimes(tx: &Transaction, storage: &Storage) -> Result<usize, String> {
+    let Some(playtimes) = read_json(&storage.playtimes_file())? else {
+        return Ok(0);
+    };
+    let now = now();
+    let mut rows = 0;
+
+    for (app_id, seconds) in playtimes.as_object().into_iter().flatten() {
+        let Some(seconds) = seconds.as_i64() else {
+            continue;
+        };
+        // Playtime of games that are no longer in the library is dropped.
+        rows += tx
+            .execute(
+                "UPDATE games SET playtime_seconds = MAX(playtime_seconds, ?2), updated_at = ?3
+                 WHERE app_id = ?1",
+                params![app_id, seconds, now],
+            )
+            .map_err(|e| format!("Failed to import playtime for {}: {}", app_id, e))?;
+    }
+    Ok(rows)
+}
+
+/// `UnlockIt/Achievements/<appid>/data.ini`, appended to by `store_achievements_by_appid`
+/// before the library database existed: a `[meta]` section and an `[achievements]`
+/// section of `<api name>=<JSON>` lines. Later lines win, since the file was never
+/// rewritten. Rows already imported from `achievements.json` are kept.
+fn import_data_ini(tx: &Transaction, storage: &Storage) -> Result<usize, String> {
+    let Ok(entries) = fs::read_dir(storage.achievements_root()) else {
+        return Ok(0);
+    };
+    let now = now();
+    let mut rows = 0;
+
+    for entry in entries.flatten() {
+        let app_id = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path().join("data.ini");
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let mut achievements: Vec<(String, Value)> = Vec::new();
+        let mut in_achievements = false;
+        for line in content.lines().map(str::trim) {
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_achievements = section.eq_ignore_ascii_case("achievements");
+                continue;
+            }
+            let Some((api_name, json)) = line.split_once('=').filter(|_| in_achievements) else {
+                continue;
+            };
+            let Ok(value) = serde_json::from_str::<Value>(json.trim()) else {
+                continue;
+            };
+            let api_name = api_name.trim().to_string();
+            achievements.retain(|(name, _)| *name != api_name);
+            achievements.push((api_name, value));
+        }
+
+        for (api_name, achievement) in &achievements {
+            tx.execute(
+                "INSERT INTO achievements (app_id, api_name, display_name, description, icon,
+                                           icon_gray, hidden)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (app_id, api_name) DO NOTHING",
+                params![
+                    app_id,
+                    api_name,
+                    str_field(achievement, "displayName"),
+                    str_field(achievement, "description"),
+                    str_field(achievement, "icon"),
+                    str_field(achievement, "icongray"),
+                    achievement
+                        .get("hidden")
+                        .and_then(|h| h.as_i64())
+                        .unwrap_or(0)
+                        != 0,
+                ],
+            )
+            .map_err(|e| format!("Failed to import achievement {}: {}", api_name, e))?;
+            rows += 1;
+
+            let unlocked = achievement
+                .get("unlocked")
+                .is_some_and(|u| u.as_bool().unwrap_or_else(|| u.as_i64().unwrap_or(0) != 0));
+            if unlocked {
+                tx.execute(
+                    "INSERT INTO unlocks (app_id, api_name, unlocked, source, updated_at)
+                     VALUES (?1, ?2, 1, 'legacy', ?3)
+                     ON CONFLICT (app_id, api_name) DO NOTHING",
+                    params![app_id, api_name, now],
+                )
+                .map_err(|e| format!("Failed to import unlock {}: {}", api_name, e))?;
+            }
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn import_all_reads_every_source_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::in_dir(dir.path());
+        let data_dir = storage.app_data_dir();
+        write(
+            &data_dir.join("my-games.json"),
+            r#"{
+                "game_480": { "name": "Spacewar", "exePath": "C:\\Games\\Spacewar.exe",
+                              "igdb_cover": "cover.jpg", "playtime": 60 },
+                "settings": { "name": "not a game" }
+            }"#,
+        );
+        write(
+            &data_dir.join("achievements.json"),
+            r#"{
+                "achievements_480": { "game": { "availableGameStats": { "achievements": [
+                    { "name": "ACH_A", "displayName": "First", "percent": "12.5", "achievedAt": "100" },
+                    { "name": "ACH_B", "hidden": 1, "percent": 80, "achievedAt": 0 }
+                ] } } }
+            }"#,
+        );
+        write(
+            &data_dir.join("howlongtobeat.json"),
+            r#"{ "480_beatTime": { "main": 5 } }"#,
+        );
+        write(&storage.playtimes_file(), r#"{ "480": 3600, "999": 10 }"#);
+        write(
+            &storage.achievements_root().join("480").join("data.ini"),
+            "[meta]\nname=Spacewar\n[achievements]\n\
+             ACH_A={\"unlocked\":true}\n\
+             ACH_B={\"unlocked\":false}\n\
+             ACH_B={\"unlocked\":1}\n\
+             ACH_C={\"displayName\":\"Third\",\"unlocked\":true}\n",
+        );
+
+        let db = LibraryDb::open(&storage.database_file()).unwrap();
+        import_all(&db, &storage);
+
+        let game = db.get_game("480").unwrap().unwrap();
+        assert_eq!(game.name, "Spacewar");
+        assert_eq!(game.exe_path.as_deref(), Some("C:\\Games\\Spacewar.exe"));
+        assert_eq!(game.cover.as_deref(), Some("cover.jpg"));
+        assert_eq!(game.playtime_seconds, 3600);
+        assert_eq!(db.list_games().unwrap().len(), 1);
+
+        let achievements = db.list_achievements("480").unwrap();
+        let names: Vec<&str> = achievements.iter().map(|a| a.api_name.as_str()).collect();
+        assert_eq!(names, ["ACH_A", "ACH_B", "ACH_C"]);
+        assert_eq!(achievements[0].display_name.as_deref(), Some("First"));
+        assert_eq!(achievements[0].global_percent, Some(12.5));
+        assert!(achievements[1].hidden);
+        assert_eq!(achievements[2].display_name.as_deref(), Some("Third"));
+
+        let unlocks = db.achievement_state("480").unwrap().achievements;
+        assert_eq!(unlocks["ACH_A"].unlock_time, Some(100));
+        assert!(unlocks["ACH_B"].unlocked);
+        assert!(unlocks["ACH_C"].unlocked);
+        assert!(unlocks
+            .values()
+            .all(|u| u.source.as_deref() == Some("legacy")));
+
+        assert_eq!(
+            db.get_metadata("480", "hltb").unwrap(),
+            Some(serde_json::json!({ "main": 5 }))
+        );
+
+        // A second launch leaves edited rows alone.
+        db.record_playtime("480", 10, 0).unwrap();
+        import_all(&db, &storage);
+        assert_eq!(db.playtime("480").unwrap(), 10);
+    }
+}
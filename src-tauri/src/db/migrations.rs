@@ -0,0 +1,135 @@
+use rusqlite::Connection;
+
+/// Schema migrations, applied in order. `PRAGMA user_version` records how many
+/// have run, so a migration must never be edited once released — add a new one.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    r#"
+    CREATE TABLE games (
+        app_id TEXT PRIMARY KEY,
+        name TEXT NOT NULL DEFAULT '',
+        exe_path TEXT,
+        install_dir TEXT,
+        header_image TEXT,
+        cover TEXT,
+        status TEXT,
+        playtime_seconds INTEGER NOT NULL DEFAULT 0,
+        -- Full frontend record (GameStoreData) for fields without a column.
+        data TEXT,
+        added_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+
+    CREATE TABLE achievements (
+        app_id TEXT NOT NULL,
+        api_name TEXT NOT NULL,
+        display_name TEXT,
+        description TEXT,
+        icon TEXT,
+        icon_gray TEXT,
+        hidden INTEGER NOT NULL DEFAULT 0,
+        global_percent REAL,
+        PRIMARY KEY (app_id, api_name)
+    );
+
+    CREATE TABLE unlocks (
+        app_id TEXT NOT NULL,
+        api_name TEXT NOT NULL,
+        unlocked INTEGER NOT NULL DEFAULT 0,
+        unlock_time INTEGER,
+        progress INTEGER,
+        max_progress INTEGER,
+        source TEXT,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (app_id, api_name)
+    );
+    CREATE INDEX unlocks_by_time ON unlocks (unlock_time) WHERE unlocked = 1;
+
+    CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        app_id TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER NOT NULL,
+        duration_seconds INTEGER NOT NULL
+    );
+    CREATE INDEX sessions_by_game ON sessions (app_id, started_at);
+
+    -- Free-form per-game data such as HowLongToBeat results, stored as JSON.
+    CREATE TABLE metadata (
+        app_id TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (app_id, key)
+    );
+
+    -- Legacy files already imported, so the first-launch migration runs once per source.
+    CREATE TABLE legacy_imports (
+        source TEXT PRIMARY KEY,
+        imported_at INTEGER NOT NULL,
+        rows INTEGER NOT NULL
+    );
+    "#,
+];
+
+pub fn run(conn: &mut Connection) -> Result<(), String> {
+    let current: usize =
+        conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+            .map_err(|e| format!("Failed to read schema version: {}", e))? as usize;
+
+    if current > MIGRATIONS.len() {
+        return Err(format!(
+            "Library database has schema version {}, newer than supported {}",
+            current,
+            MIGRATIONS.len()
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+        let version = index + 1;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start migration {}: {}", version, e))?;
+        tx.execute_batch(migration)
+            .map_err(|e| format!("Failed to apply migration {}: {}", version, e))?;
+        tx.pragma_update(None, "user_version", version as i64)
+            .map_err(|e| format!("Failed to record migration {}: {}", version, e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit migration {}: {}", version, e))?;
+        println!("Applied library database migration {}", version);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_version(conn: &Connection) -> i64 {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrations_run_once_and_reject_newer_schemas() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len() as i64);
+
+        // Running again must not try to recreate the tables.
+        conn.execute(
+            "INSERT INTO legacy_imports (source, imported_at, rows) VALUES ('test', 0, 0)",
+            [],
+        )
+        .unwrap();
+        run(&mut conn).unwrap();
+        let imports: i64 = conn
+            .query_row("SELECT COUNT(*) FROM legacy_imports", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(imports, 1);
+
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() as i64 + 1)
+            .unwrap();
+        assert!(run(&mut conn).unwrap_err().contains("newer than supported"));
+    }
+}
@@ -0,0 +1,736 @@
+//! Rust-owned library database (SQLite, `UnlockIt/library.db`).
+//!
+//! Holds games, achievement schemas, unlock state, play sessions and free-form
+//! per-game metadata. The schema is versioned through [`migrations`], and data
+//! from the older JSON files is imported once on first launch by [`legacy`].
+
+mod backup;
+mod legacy;
+mod migrations;
+mod state;
+
+pub use backup::RestoreMode;
+pub use state::{AchievementState, AchievementUpdate, GameAchievementState};
+
+use crate::rarity::RarityTier;
+use crate::storage::Storage;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::State;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameRecord {
+    pub app_id: String,
+    pub name: String,
+    pub exe_path: Option<String>,
+    pub install_dir: Option<String>,
+    pub header_image: Option<String>,
+    pub cover: Option<String>,
+    pub status: Option<String>,
+    pub playtime_seconds: u64,
+    /// The frontend's full game record, kept verbatim.
+    pub data: Option<serde_json::Value>,
+    pub added_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AchievementRecord {
+    pub api_name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub icon_gray: Option<String>,
+    pub hidden: bool,
+    /// Percentage of Steam players that unlocked it.
+    pub global_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRecord {
+    pub id: i64,
+    pub app_id: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub duration_seconds: u64,
+}
+
+/// One unlocked achievement joined with its game and schema.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnlockRecord {
+    pub app_id: String,
+    pub game_name: Option<String>,
+    pub api_name: String,
+    pub display_name: Option<String>,
+    pub icon: Option<String>,
+    pub unlock_time: Option<i64>,
+    pub global_percent: Option<f64>,
+    pub rarity: Option<RarityTier>,
+    pub source: Option<String>,
+}
+
+/// Filter for unlock queries; times are Unix seconds, `until` is exclusive.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UnlockQuery {
+    pub app_id: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+pub struct LibraryDb {
+    conn: Mutex<Connection>,
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn db_err(action: &str) -> impl Fn(rusqlite::Error) -> String + '_ {
+    move |e| format!("Failed to {}: {}", action, e)
+}
+
+impl LibraryDb {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create database directory: {}", e))?;
+        }
+        let mut conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open library database {:?}: {}", path, e))?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+            .map_err(db_err("configure library database"))?;
+        migrations::run(&mut conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Opens `UnlockIt/library.db` and imports any legacy files not imported yet.
+    pub fn open_for_app(storage: &Storage) -> Result<Self, String> {
+        let db = Self::open(&storage.database_file())?;
+        legacy::import_all(&db, storage);
+        Ok(db)
+    }
+
+    // Games
+
+    pub fn list_games(&self) -> Result<Vec<GameRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT * FROM games ORDER BY name COLLATE NOCASE")
+            .map_err(db_err("list games"))?;
+        let games = stmt
+            .query_map([], game_from_row)
+            .map_err(db_err("list games"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err("read games"))?;
+        Ok(games)
+    }
+
+    pub fn get_game(&self, app_id: &str) -> Result<Option<GameRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT * FROM games WHERE app_id = ?1",
+            [app_id],
+            game_from_row,
+        )
+        .optional()
+        .map_err(db_err("load game"))
+    }
+
+    /// Inserts or replaces a game; `playtime_seconds` and `added_at` of an
+    /// existing row are kept since the backend owns them.
+    pub fn upsert_game(&self, game: &GameRecord) -> Result<(), String> {
+        let data = game.data.as_ref().map(|data| data.to_string());
+        let now = now();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO games (app_id, name, exe_path, install_dir, header_image, cover, status,
+                                playtime_seconds, data, added_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)
+             ON CONFLICT (app_id) DO UPDATE SET
+                name = excluded.name,
+                exe_path = excluded.exe_path,
+                install_dir = excluded.install_dir,
+                header_image = excluded.header_image,
+                cover = excluded.cover,
+                status = excluded.status,
+                data = excluded.data,
+                updated_at = excluded.updated_at",
+            params![
+                game.app_id,
+                game.name,
+                game.exe_path,
+                game.install_dir,
+                game.header_image,
+                game.cover,
+                game.status,
+                game.playtime_seconds as i64,
+                data,
+                now
+            ],
+        )
+        .map_err(db_err("save game"))?;
+        Ok(())
+    }
+
+    /// Removes a game together with its achievements, unlocks, sessions and metadata.
+    pub fn delete_game(&self, app_id: &str) -> Result<bool, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(db_err("delete game"))?;
+        for table in ["achievements", "unlocks", "sessions", "metadata"] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE app_id = ?1", table),
+                [app_id],
+            )
+            .map_err(db_err("delete game data"))?;
+        }
+        let deleted = tx
+            .execute("DELETE FROM games WHERE app_id = ?1", [app_id])
+            .map_err(db_err("delete game"))?;
+        tx.commit().map_err(db_err("delete game"))?;
+        Ok(deleted > 0)
+    }
+
+    // Achievements
+
+    pub fn list_achievements(&self, app_id: &str) -> Result<Vec<AchievementRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT api_name, display_name, description, icon, icon_gray, hidden, global_percent
+                 FROM achievements WHERE app_id = ?1 ORDER BY api_name",
+            )
+            .map_err(db_err("list achievements"))?;
+        let achievements = stmt
+            .query_map([app_id], |row| {
+                Ok(AchievementRecord {
+                    api_name: row.get(0)?,
+                    display_name: row.get(1)?,
+                    description: row.get(2)?,
+                    icon: row.get(3)?,
+                    icon_gray: row.get(4)?,
+                    hidden: row.get(5)?,
+                    global_percent: row.get(6)?,
+                })
+            })
+            .map_err(db_err("list achievements"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err("read achievements"))?;
+        Ok(achievements)
+    }
+
+    pub fn upsert_achievements(
+        &self,
+        app_id: &str,
+        achievements: &[AchievementRecord],
+    ) -> Result<usize, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(db_err("save achievements"))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO achievements (app_id, api_name, display_name, description, icon,
+                                               icon_gray, hidden, global_percent)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                     ON CONFLICT (app_id, api_name) DO UPDATE SET
+                        display_name = excluded.display_name,
+                        description = excluded.description,
+                        icon = excluded.icon,
+                        icon_gray = excluded.icon_gray,
+                        hidden = excluded.hidden,
+                        global_percent = COALESCE(excluded.global_percent, global_percent)",
+                )
+                .map_err(db_err("save achievements"))?;
+            for achievement in achievements.iter().filter(|a| !a.api_name.is_empty()) {
+                stmt.execute(params![
+                    app_id,
+                    achievement.api_name,
+                    achievement.display_name,
+                    achievement.description,
+                    achievement.icon,
+                    achievement.icon_gray,
+                    achievement.hidden,
+                    achievement.global_percent
+                ])
+                .map_err(db_err("save achievement"))?;
+            }
+        }
+        tx.commit().map_err(db_err("save achievements"))?;
+        Ok(achievements.len())
+    }
+
+    // Unlocks
+
+    pub fn achievement_state(&self, app_id: &str) -> Result<GameAchievementState, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut state = GameAchievementState::empty(app_id);
+        state.achievements = read_unlocks(&conn, app_id)?;
+        Ok(state)
+    }
+
+    /// Inserts or updates unlock state and returns the entries that changed.
+    pub fn upsert_unlocks(
+        &self,
+        app_id: &str,
+        updates: &[AchievementUpdate],
+    ) -> Result<BTreeMap<String, AchievementState>, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(db_err("save unlocks"))?;
+        let mut current = read_unlocks(&tx, app_id)?;
+        let now = now();
+
+        let mut changed = BTreeMap::new();
+        for update in updates.iter().filter(|u| !u.api_name.is_empty()) {
+            let entry = current.entry(update.api_name.clone()).or_default();
+            if entry.apply(update, now) {
+                tx.execute(
+                    "INSERT INTO unlocks (app_id, api_name, unlocked, unlock_time, progress,
+                                          max_progress, source, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                     ON CONFLICT (app_id, api_name) DO UPDATE SET
+                        unlocked = excluded.unlocked,
+                        unlock_time = excluded.unlock_time,
+                        progress = excluded.progress,
+                        max_progress = excluded.max_progress,
+                        source = excluded.source,
+                        updated_at = excluded.updated_at",
+                    params![
+                        app_id,
+                        update.api_name,
+                        entry.unlocked,
+                        entry.unlock_time,
+                        entry.progress,
+                        entry.max_progress,
+                        entry.source,
+                        entry.updated_at
+                    ],
+                )
+                .map_err(db_err("save unlock"))?;
+                changed.insert(update.api_name.clone(), entry.clone());
+            }
+        }
+        tx.commit().map_err(db_err("save unlocks"))?;
+        Ok(changed)
+    }
+
+    /// Unlocked achievements matching `query`, rarest (lowest global percentage) first.
+    pub fn rarest_unlocks(&self, query: &UnlockQuery) -> Result<Vec<UnlockRecord>, String> {
+        self.query_unlocks(
+            query,
+            "a.global_percent IS NULL, a.global_percent ASC, u.unlock_time DESC",
+        )
+    }
+
+    /// Unlocked achievements matching `query`, oldest first.
+    pub fn unlock_timeline(&self, query: &UnlockQuery) -> Result<Vec<UnlockRecord>, String> {
+        self.query_unlocks(query, "u.unlock_time ASC, u.app_id, u.api_name")
+    }
+
+    /// Unlocked achievements matching `query`, newest first.
+    pub fn recent_unlocks(&self, query: &UnlockQuery) -> Result<Vec<UnlockRecord>, String> {
+        self.query_unlocks(query, "u.unlock_time DESC")
+    }
+
+    fn query_unlocks(&self, query: &UnlockQuery, order: &str) -> Result<Vec<UnlockRecord>, String> {
+        let sql = format!(
+            "SELECT u.app_id, g.name, u.api_name, a.display_name, a.icon, u.unlock_time,
+                    a.global_percent, u.source
+             FROM unlocks u
+             LEFT JOIN achievements a ON a.app_id = u.app_id AND a.api_name = u.api_name
+             LEFT JOIN games g ON g.app_id = u.app_id
+             WHERE u.unlocked = 1
+               AND (?1 IS NULL OR u.app_id = ?1)
+               AND (?2 IS NULL OR u.unlock_time >= ?2)
+               AND (?3 IS NULL OR u.unlock_time < ?3)
+             ORDER BY {}
+             LIMIT ?4",
+            order
+        );
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&sql).map_err(db_err("query unlocks"))?;
+        let unlocks = stmt
+            .query_map(
+                params![
+                    query.app_id,
+                    query.since,
+                    query.until,
+                    query.limit.map_or(-1, i64::from)
+                ],
+                |row| {
+                    let global_percent: Option<f64> = row.get(6)?;
+                    Ok(UnlockRecord {
+                        app_id: row.get(0)?,
+                        game_name: row.get(1)?,
+                        api_name: row.get(2)?,
+                        display_name: row.get(3)?,
+                        icon: row.get(4)?,
+                        unlock_time: row.get(5)?,
+                        global_percent,
+                        rarity: global_percent.map(RarityTier::from_percentage),
+                        source: row.get(7)?,
+                    })
+                },
+            )
+            .map_err(db_err("query unlocks"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err("read unlocks"))?;
+        Ok(unlocks)
+    }
+
+    // Playtime and sessions
+
+    pub fn playtime(&self, app_id: &str) -> Result<u64, String> {
+        let conn = self.conn.lock().unwrap();
+        let playtime: Option<i64> = conn
+            .query_row(
+                "SELECT playtime_seconds FROM games WHERE app_id = ?1",
+                [app_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err("load playtime"))?;
+        Ok(playtime.unwrap_or(0).max(0) as u64)
+    }
+
+    /// Stores the accumulated playtime and, when `session_seconds` is non-zero,
+    /// records a session that ended now. Games not in the library yet get a row
+    /// without a name, filled in by the next `upsert_game`.
+    pub fn record_playtime(
+        &self,
+        app_id: &str,
+        total_seconds: u64,
+        session_seconds: u64,
+    ) -> Result<(), String> {
+        let now = now();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(db_err("save playtime"))?;
+        tx.execute(
+            "INSERT INTO games (app_id, playtime_seconds, added_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)
+             ON CONFLICT (app_id) DO UPDATE SET
+                playtime_seconds = excluded.playtime_seconds,
+                updated_at = excluded.updated_at",
+            params![app_id, total_seconds as i64, now],
+        )
+        .map_err(db_err("save playtime"))?;
+        if session_seconds > 0 {
+            tx.execute(
+                "INSERT INTO sessions (app_id, started_at, ended_at, duration_seconds)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    app_id,
+                    now - session_seconds as i64,
+                    now,
+                    session_seconds as i64
+                ],
+            )
+            .map_err(db_err("save session"))?;
+        }
+        tx.commit().map_err(db_err("save playtime"))?;
+        Ok(())
+    }
+
+    pub fn list_sessions(&self, app_id: &str) -> Result<Vec<SessionRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_id, started_at, ended_at, duration_seconds
+                 FROM sessions WHERE app_id = ?1 ORDER BY started_at DESC",
+            )
+            .map_err(db_err("list sessions"))?;
+        let sessions = stmt
+            .query_map([app_id], |row| {
+                Ok(SessionRecord {
+                    id: row.get(0)?,
+                    app_id: row.get(1)?,
+                    started_at: row.get(2)?,
+                    ended_at: row.get(3)?,
+                    duration_seconds: row.get::<_, i64>(4)?.max(0) as u64,
+                })
+            })
+            .map_err(db_err("list sessions"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err("read sessions"))?;
+        Ok(sessions)
+    }
+
+    // Metadata
+
+    pub fn get_metadata(
+        &self,
+        app_id: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, String> {
+        let conn = self.conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM metadata WHERE app_id = ?1 AND key = ?2",
+                [app_id, key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err("load metadata"))?;
+        value
+            .map(|value| {
+                serde_json::from_str(&value)
+                    .map_err(|e| format!("Failed to parse metadata {}/{}: {}", app_id, key, e))
+            })
+            .transpose()
+    }
+
+    pub fn set_metadata(
+        &self,
+        app_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO metadata (app_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (app_id, key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at",
+            params![app_id, key, value.to_string(), now()],
+        )
+        .map_err(db_err("save metadata"))?;
+        Ok(())
+    }
+
+    pub fn delete_metadata(&self, app_id: &str, key: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn
+            .execute(
+                "DELETE FROM metadata WHERE app_id = ?1 AND key = ?2",
+                [app_id, key],
+            )
+            .map_err(db_err("delete metadata"))?;
+        Ok(deleted > 0)
+    }
+
+    /// Runs `f` inside a transaction on the underlying connection.
+    fn with_transaction<T>(
+        &self,
+        f: impl FnOnce(&rusqlite::Transaction) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(db_err("start transaction"))?;
+        let result = f(&tx)?;
+        tx.commit().map_err(db_err("commit transaction"))?;
+        Ok(result)
+    }
+}
+
+fn game_from_row(row: &Row) -> rusqlite::Result<GameRecord> {
+    let data: Option<String> = row.get("data")?;
+    Ok(GameRecord {
+        app_id: row.get("app_id")?,
+        name: row.get("name")?,
+        exe_path: row.get("exe_path")?,
+        install_dir: row.get("install_dir")?,
+        header_image: row.get("header_image")?,
+        cover: row.get("cover")?,
+        status: row.get("status")?,
+        playtime_seconds: row.get::<_, i64>("playtime_seconds")?.max(0) as u64,
+        data: data.and_then(|data| serde_json::from_str(&data).ok()),
+        added_at: row.get("added_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn read_unlocks(
+    conn: &Connection,
+    app_id: &str,
+) -> Result<BTreeMap<String, AchievementState>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT api_name, unlocked, unlock_time, progress, max_progress, source, updated_at
+             FROM unlocks WHERE app_id = ?1",
+        )
+        .map_err(db_err("load unlocks"))?;
+    let unlocks = stmt
+        .query_map([app_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                AchievementState {
+                    unlocked: row.get(1)?,
+                    unlock_time: row.get(2)?,
+                    progress: row.get(3)?,
+                    max_progress: row.get(4)?,
+                    source: row.get(5)?,
+                    updated_at: row.get(6)?,
+                },
+            ))
+        })
+        .map_err(db_err("load unlocks"))?
+        .collect::<Result<BTreeMap<_, _>, _>>()
+        .map_err(db_err("read unlocks"))?;
+    Ok(unlocks)
+}
+
+#[tauri::command]
+pub fn list_games(db: State<'_, LibraryDb>) -> Result<Vec<GameRecord>, String> {
+    db.list_games()
+}
+
+#[tauri::command]
+pub fn get_game(db: State<'_, LibraryDb>, app_id: String) -> Result<Option<GameRecord>, String> {
+    db.get_game(&app_id)
+}
+
+#[tauri::command]
+pub fn upsert_game(db: State<'_, LibraryDb>, game: GameRecord) -> Result<(), String> {
+    db.upsert_game(&game)
+}
+
+#[tauri::command]
+pub fn delete_game(db: State<'_, LibraryDb>, app_id: String) -> Result<bool, String> {
+    db.delete_game(&app_id)
+}
+
+#[tauri::command]
+pub fn list_achievements(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+) -> Result<Vec<AchievementRecord>, String> {
+    db.list_achievements(&app_id)
+}
+
+#[tauri::command]
+pub fn upsert_achievements(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+    achievements: Vec<AchievementRecord>,
+) -> Result<usize, String> {
+    db.upsert_achievements(&app_id, &achievements)
+}
+
+#[tauri::command]
+pub fn get_achievements_state(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+) -> Result<GameAchievementState, String> {
+    db.achievement_state(&app_id)
+}
+
+#[tauri::command]
+pub fn list_sessions(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+) -> Result<Vec<SessionRecord>, String> {
+    db.list_sessions(&app_id)
+}
+
+#[tauri::command]
+pub fn get_game_metadata(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+    key: String,
+) -> Result<Option<serde_json::Value>, String> {
+    db.get_metadata(&app_id, &key)
+}
+
+#[tauri::command]
+pub fn set_game_metadata(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    db.set_metadata(&app_id, &key, &value)
+}
+
+#[tauri::command]
+pub fn delete_game_metadata(
+    db: State<'_, LibraryDb>,
+    app_id: String,
+    key: String,
+) -> Result<bool, String> {
+    db.delete_metadata(&app_id, &key)
+}
+
+/// e.g. "rarest unlocks this month": `since` = first day of the month, `limit` = 10.
+#[tauri::command]
+pub fn rarest_unlocks(
+    db: State<'_, LibraryDb>,
+    query: UnlockQuery,
+) -> Result<Vec<UnlockRecord>, String> {
+    db.rarest_unlocks(&query)
+}
+
+#[tauri::command]
+pub fn recent_unlocks(
+    db: State<'_, LibraryDb>,
+    query: UnlockQuery,
+) -> Result<Vec<UnlockRecord>, String> {
+    db.recent_unlocks(&query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(app_id: &str, name: &str) -> GameRecord {
+        GameRecord {
+            app_id: app_id.to_string(),
+            name: name.to_string(),
+            data: Some(serde_json::json!({ "appid": app_id })),
+            ..GameRecord::default()
+        }
+    }
+
+    #[test]
+    fn upsert_game_keeps_backend_owned_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = LibraryDb::open(&dir.path().join("library.db")).unwrap();
+
+        db.upsert_game(&game("480", "Spacewar")).unwrap();
+        db.record_playtime("480", 120, 0).unwrap();
+        let added_at = db.get_game("480").unwrap().unwrap().added_at;
+
+        let mut renamed = game("480", "Spacewar II");
+        renamed.status = Some("playing".to_string());
+        renamed.playtime_seconds = 5;
+        renamed.added_at = 1;
+        db.upsert_game(&renamed).unwrap();
+
+        let stored = db.get_game("480").unwrap().unwrap();
+        assert_eq!(stored.name, "Spacewar II");
+        assert_eq!(stored.status.as_deref(), Some("playing"));
+        assert_eq!(stored.playtime_seconds, 120);
+        assert_eq!(stored.added_at, added_at);
+        assert_eq!(stored.data, Some(serde_json::json!({ "appid": "480" })));
+        assert_eq!(db.list_games().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn record_playtime_adds_games_missing_from_the_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = LibraryDb::open(&dir.path().join("library.db")).unwrap();
+
+        db.record_playtime("220", 300, 60).unwrap();
+        let stored = db.get_game("220").unwrap().unwrap();
+        assert_eq!(stored.name, "");
+        assert_eq!(stored.playtime_seconds, 300);
+
+        let sessions = db.list_sessions("220").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].duration_seconds, 60);
+        assert_eq!(sessions[0].ended_at - sessions[0].started_at, 60);
+
+        db.upsert_game(&game("220", "Half-Life 2")).unwrap();
+        db.record_playtime("220", 360, 0).unwrap();
+        let stored = db.get_game("220").unwrap().unwrap();
+        assert_eq!(stored.name, "Half-Life 2");
+        assert_eq!(stored.playtime_seconds, 360);
+        assert_eq!(db.list_sessions("220").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,141 @@
+//! Unlock state as stored in the `unlocks` table.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Known state of one achievement, keyed by its API name in [`GameAchievementState`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AchievementState {
+    pub unlocked: bool,
+    /// Unix timestamp (seconds, UTC) of the unlock.
+    pub unlock_time: Option<i64>,
+    pub progress: Option<u32>,
+    pub max_progress: Option<u32>,
+    /// Emulator or launcher the state was read from (`"goldberg"`, `"codex"`, ...).
+    pub source: Option<String>,
+    /// Unix timestamp of the last change to this entry.
+    pub updated_at: i64,
+}
+
+/// All known achievement state of one game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameAchievementState {
+    pub app_id: String,
+    pub achievements: BTreeMap<String, AchievementState>,
+}
+
+impl GameAchievementState {
+    pub(crate) fn empty(app_id: &str) -> Self {
+        Self {
+            app_id: app_id.to_string(),
+            achievements: BTreeMap::new(),
+        }
+    }
+}
+
+/// A partial update; fields left as `None` keep their stored value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AchievementUpdate {
+    pub api_name: String,
+    pub unlocked: Option<bool>,
+    pub unlock_time: Option<i64>,
+    pub progress: Option<u32>,
+    pub max_progress: Option<u32>,
+    pub source: Option<String>,
+}
+
+impl AchievementState {
+    /// Applies `update`, returning whether anything changed.
+    pub(crate) fn apply(&mut self, update: &AchievementUpdate, now: i64) -> bool {
+        let before = self.clone();
+
+        if let Some(unlocked) = update.unlocked {
+            self.unlocked = unlocked;
+            if !unlocked {
+                self.unlock_time = None;
+            }
+        }
+        if self.unlocked {
+            // Keep the earliest known unlock time.
+            self.unlock_time = match (self.unlock_time, update.unlock_time) {
+                (Some(existing), Some(time)) => Some(existing.min(time)),
+                (existing, time) => existing.or(time).or(Some(now)),
+            };
+        }
+        if update.progress.is_some() {
+            self.progress = update.progress;
+        }
+        if update.max_progress.is_some() {
+            self.max_progress = update.max_progress;
+        }
+        if update.source.is_some() {
+            self.source = update.source.clone();
+        }
+
+        let changed = *self != before;
+        if changed {
+            self.updated_at = now;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(unlocked: Option<bool>, unlock_time: Option<i64>) -> AchievementUpdate {
+        AchievementUpdate {
+            api_name: "ACH_WIN_ONE_GAME".to_string(),
+            unlocked,
+            unlock_time,
+            ..AchievementUpdate::default()
+        }
+    }
+
+    #[test]
+    fn apply_unlocks_relocks_and_keeps_the_earliest_time() {
+        let mut state = AchievementState::default();
+
+        assert!(state.apply(&update(Some(true), None), 100));
+        assert!(state.unlocked);
+        assert_eq!(state.unlock_time, Some(100));
+        assert_eq!(state.updated_at, 100);
+
+        // An earlier time from another source wins, a later one does not.
+        assert!(state.apply(&update(Some(true), Some(50)), 200));
+        assert_eq!(state.unlock_time, Some(50));
+        assert!(!state.apply(&update(Some(true), Some(80)), 300));
+        assert!(!state.apply(&update(None, None), 300));
+        assert_eq!(state.unlock_time, Some(50));
+        assert_eq!(state.updated_at, 200);
+
+        assert!(state.apply(&update(Some(false), Some(80)), 400));
+        assert!(!state.unlocked);
+        assert_eq!(state.unlock_time, None);
+
+        assert!(state.apply(&update(Some(true), Some(500)), 600));
+        assert_eq!(state.unlock_time, Some(500));
+    }
+
+    #[test]
+    fn apply_leaves_unset_fields_alone() {
+        let mut state = AchievementState::default();
+        let progress = AchievementUpdate {
+            progress: Some(3),
+            max_progress: Some(10),
+            source: Some("goldberg".to_string()),
+            ..update(None, Some(100))
+        };
+
+        assert!(state.apply(&progress, 100));
+        assert!(!state.unlocked);
+        assert_eq!(state.unlock_time, None);
+        assert!(!state.apply(&update(None, None), 200));
+        assert_eq!(state.progress, Some(3));
+        assert_eq!(state.max_progress, Some(10));
+        assert_eq!(state.source.as_deref(), Some("goldberg"));
+    }
+}
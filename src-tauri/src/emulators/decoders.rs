@@ -0,0 +1,220 @@
+//! Decoders for save files that are not one of the text layouts
+//! [`unlocks::parse_unlocks`] reads: SmartSteamEmu-style `stats.bin` (also left
+//! in Goldberg save folders), ALI213 `Achievements.Bin`, `achieve.dat` and
+//! CreamAPI's `CreamAPI.Achievements.cfg`. They are picked by file name and work
+//! on raw bytes, so binary and NUL-padded files are never read as lossy text.
+
+use super::ini::{self, Ini};
+use super::unlocks::{self, FileUnlock, FileUnlocks};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Achievement flags and stat values read from a save file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DecodedSave {
+    pub unlocks: FileUnlocks,
+    /// Stat values by API name.
+    pub stats: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SaveFormat {
+    StatsBin,
+    AchievementsBin,
+    AchieveDat,
+    CreamApiCfg,
+}
+
+fn format_for(path: &Path) -> Option<SaveFormat> {
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    match name.as_str() {
+        "stats.bin" => Some(SaveFormat::StatsBin),
+        "achievements.bin" => Some(SaveFormat::AchievementsBin),
+        "achieve.dat" => Some(SaveFormat::AchieveDat),
+        "creamapi.achievements.cfg" => Some(SaveFormat::CreamApiCfg),
+        _ => None,
+    }
+}
+
+/// Whether `path` is a format this module decodes.
+pub fn is_decoded(path: &Path) -> bool {
+    format_for(path).is_some()
+}
+
+/// Whether decoding `path` needs the game's achievement API names, because the
+/// format stores hashes of them.
+pub fn needs_names(path: &Path) -> bool {
+    format_for(path) == Some(SaveFormat::StatsBin)
+}
+
+/// Decodes `bytes` read from `path`. `names` are the game's achievement API
+/// names, used to turn hashed entries back into names. `None` when the file name
+/// is not a decoded format or the content does not match it.
+pub fn decode(path: &Path, bytes: &[u8], names: &[String]) -> Option<DecodedSave> {
+    let format = format_for(path)?;
+    if bytes.is_empty() {
+        return Some(DecodedSave::default());
+    }
+    match format {
+        SaveFormat::StatsBin => decode_stats_bin(bytes, names),
+        SaveFormat::AchievementsBin | SaveFormat::CreamApiCfg => {
+            let unlocks = unlocks::parse_unlocks(&text(bytes))?;
+            Some(DecodedSave {
+                unlocks,
+                stats: BTreeMap::new(),
+            })
+        }
+        SaveFormat::AchieveDat => decode_achieve_dat(bytes),
+    }
+}
+
+/// Text of a config-style save. ALI213 and some CreamAPI builds pad the file
+/// with NUL bytes or write it as UTF-16.
+fn text(bytes: &[u8]) -> String {
+    let end = if bytes.starts_with(&[0xFF, 0xFE]) {
+        bytes.len()
+    } else {
+        bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1)
+    };
+    ini::decode_text(&bytes[..end]).replace('\0', "")
+}
+
+/// Bytes in one `stats.bin` record.
+const STATS_BIN_RECORD: usize = 24;
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// `stats.bin`: a little-endian `u32` record count followed by 24-byte records
+/// of CRC32 of the API name (4), stat value (`i32`, 4), unlock time in Unix
+/// seconds (4), reserved (8) and achieved flag (4). Records for known
+/// achievement names, or with the flag or time set, are achievements; the rest
+/// are stats. Names that are not in `names` are kept as 8-digit hex CRCs.
+fn decode_stats_bin(bytes: &[u8], names: &[String]) -> Option<DecodedSave> {
+    let count = u32_at(bytes.get(..4)?, 0) as usize;
+    let records = bytes.get(4..)?;
+    if records.len() != count.checked_mul(STATS_BIN_RECORD)? {
+        return None;
+    }
+    let by_crc: BTreeMap<u32, &String> = names
+        .iter()
+        .map(|name| (crc32fast::hash(name.as_bytes()), name))
+        .collect();
+
+    let mut decoded = DecodedSave::default();
+    for record in records.chunks_exact(STATS_BIN_RECORD) {
+        let crc = u32_at(record, 0);
+        let value = u32_at(record, 4) as i32;
+        let time = u32_at(record, 8);
+        let achieved = u32_at(record, 20) != 0;
+        let known = by_crc.get(&crc);
+        let name = known.map_or_else(|| format!("{:08x}", crc), |name| name.to_string());
+        if known.is_some() || achieved || time != 0 {
+            let unlock = FileUnlock {
+                unlocked: achieved,
+                unlock_time: unlocks::normalize_time(&time.to_string()),
+            };
+            decoded.unlocks.insert(name, unlock);
+        } else {
+            decoded.stats.insert(name, value.into());
+        }
+    }
+    Some(decoded)
+}
+
+/// `achieve.dat`: the `[ACHIEVE_DATA]` layout 3DM also uses, see
+/// [`unlocks::parse_achieve_data`]. Integer entries in a `[STATS]` section are
+/// stat values.
+fn decode_achieve_dat(bytes: &[u8]) -> Option<DecodedSave> {
+    let ini = Ini::parse(&text(bytes));
+    let mut decoded = DecodedSave {
+        unlocks: unlocks::parse_achieve_data(&ini)?,
+        stats: BTreeMap::new(),
+    };
+    for (key, value) in ini.section("STATS").unwrap_or_default() {
+        if let Ok(value) = value.trim().parse::<i64>() {
+            decoded.stats.insert(key.clone(), value);
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/decoders");
+
+    fn decode_fixture(file: &str, names: &[&str]) -> DecodedSave {
+        let path = Path::new(FIXTURES).join(file);
+        let bytes = std::fs::read(&path).unwrap();
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        decode(&path, &bytes, &names).unwrap()
+    }
+
+    fn assert_shared_state(decoded: &DecodedSave) {
+        assert_eq!(
+            decoded.unlocks.get("ACH_WIN_ONE_GAME"),
+            Some(&FileUnlock {
+                unlocked: true,
+                unlock_time: Some(1700000000),
+            })
+        );
+        assert!(!decoded
+            .unlocks
+            .get("ACH_TRAVEL_FAR_ACCUM")
+            .is_some_and(|unlock| unlock.unlocked));
+    }
+
+    #[test]
+    fn decodes_every_fixture() {
+        for file in [
+            "Achievements.Bin",
+            "achieve.dat",
+            "CreamAPI.Achievements.cfg",
+        ] {
+            assert_shared_state(&decode_fixture(file, &[]));
+        }
+        let stats = decode_fixture("stats.bin", &["ACH_WIN_ONE_GAME", "ACH_TRAVEL_FAR_ACCUM"]);
+        assert_shared_state(&stats);
+        assert_eq!(
+            stats
+                .stats
+                .get(&format!("{:08x}", crc32fast::hash(b"NumGames"))),
+            Some(&42)
+        );
+    }
+
+    #[test]
+    fn achieve_dat_reads_stats_and_time_flags() {
+        let decoded = decode_fixture("achieve.dat", &[]);
+        assert_eq!(decoded.stats.get("NumGames"), Some(&42));
+        assert_eq!(
+            decoded.unlocks.get("ACH_WIN_100_GAMES"),
+            Some(&FileUnlock {
+                unlocked: true,
+                unlock_time: Some(1700003600),
+            })
+        );
+    }
+
+    #[test]
+    fn stats_bin_without_names_keys_by_crc() {
+        let decoded = decode_fixture("stats.bin", &[]);
+        assert_eq!(
+            decoded.unlocks.get("16ea0560"),
+            Some(&FileUnlock {
+                unlocked: true,
+                unlock_time: Some(1700000000),
+            })
+        );
+        assert!(!decoded.unlocks.contains_key("ACH_WIN_ONE_GAME"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Tolerant INI reader for emulator configs and save files. Section and key names
+//! are case-insensitive; `;` and `#` start comments, including after a value.
+
+#[derive(Debug, Clone, Default)]
+pub struct Ini {
+    /// Sections in file order; keys before the first header are in section "".
+    pub sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Ini {
+    pub fn parse(text: &str) -> Ini {
+        let mut sections: Vec<(String, Vec<(String, String)>)> = vec![(String::new(), Vec::new())];
+        for line in text.lines() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.split_once(']')) {
+                sections.push((name.0.trim().to_string(), Vec::new()));
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value
+                .split([';', '#'])
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_matches('"');
+            if let Some((_, entries)) = sections.last_mut() {
+                entries.push((key.trim().to_string(), value.to_string()));
+            }
+        }
+        Ini { sections }
+    }
+
+    pub fn read(path: &std::path::Path) -> Result<Ini, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(Ini::parse(&decode_text(&bytes)))
+    }
+
+    pub fn section(&self, name: &str) -> Option<&[(String, String)]> {
+        self.sections
+            .iter()
+            .find(|(section, _)| section.eq_ignore_ascii_case(name))
+            .map(|(_, entries)| entries.as_slice())
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.section(section)?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// First value for `key` in any section.
+    pub fn find(&self, key: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .flat_map(|(_, entries)| entries)
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Decodes a config file written as UTF-8 or, as some emulators do, UTF-16 LE.
+pub fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    String::from_utf8_lossy(bytes).into_owned()
+}
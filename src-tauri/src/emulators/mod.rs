@@ -0,0 +1,840 @@
+//! Steam emulator detection. A game directory is fingerprinted from the emulator's
+//! config files, directory layout and `steam_api(64).dll` contents, which gives the
+//! appid and the save files that record unlocks.
+
+pub mod decoders;
+pub mod ini;
+pub mod saves;
+pub mod unlocks;
+pub mod valve_ini;
+
+use crate::db::LibraryDb;
+use crate::wine::{self, WinePrefix};
+use decoders::DecodedSave;
+use ini::Ini;
+use saves::{SaveRoot, SaveRule, SaveVars};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use valve_ini::{ValveIni, ValveIniSaves};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmulatorKind {
+    Goldberg,
+    Codex,
+    Rune,
+    OnlineFix,
+    Ali213,
+    SteamConfig,
+    Tenoke,
+    CreamApi,
+    SmartSteamEmu,
+    #[serde(rename = "3dm")]
+    ThreeDm,
+    Skidrow,
+    Reloaded,
+    Flt,
+    Empress,
+}
+
+impl EmulatorKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EmulatorKind::Goldberg => "goldberg",
+            EmulatorKind::Codex => "codex",
+            EmulatorKind::Rune => "rune",
+            EmulatorKind::OnlineFix => "online_fix",
+            EmulatorKind::Ali213 => "ali213",
+            EmulatorKind::SteamConfig => "steam_config",
+            EmulatorKind::Tenoke => "tenoke",
+            EmulatorKind::CreamApi => "cream_api",
+            EmulatorKind::SmartSteamEmu => "smart_steam_emu",
+            EmulatorKind::ThreeDm => "3dm",
+            EmulatorKind::Skidrow => "skidrow",
+            EmulatorKind::Reloaded => "reloaded",
+            EmulatorKind::Flt => "flt",
+            EmulatorKind::Empress => "empress",
+        }
+    }
+}
+
+/// Where an emulator keeps the appid.
+#[derive(Debug, Clone, Copy)]
+enum AppIdSource {
+    /// `key` in an INI file; an empty `section` matches any section.
+    IniKey {
+        file: &'static str,
+        section: &'static str,
+        key: &'static str,
+    },
+    /// A file holding only the appid, like `steam_appid.txt`.
+    TextFile(&'static str),
+}
+
+struct Fingerprint {
+    kind: EmulatorKind,
+    /// Lowercase file or directory names that identify the emulator.
+    markers: &'static [&'static str],
+    /// Text inside a marker file that tells emulators sharing a file name apart.
+    content_markers: &'static [&'static str],
+    /// Byte strings found in the emulator's `steam_api(64).dll`.
+    dll_markers: &'static [&'static [u8]],
+    app_id: &'static [AppIdSource],
+    saves: &'static [SaveRule],
+    /// Lowercase file names that record unlocks wherever they are in the game tree.
+    tree_files: &'static [&'static str],
+}
+
+const GOLDBERG_FILES: &[&str] = &[
+    "achievements.json",
+    "achievements.ini",
+    "achiev.ini",
+    "stats.ini",
+    "Achievements.Bin",
+    "achieve.dat",
+    "stats/achievements.ini",
+    "stats.bin",
+];
+
+/// Save layouts that ALI213 can also imitate, see [`valve_ini`].
+const CODEX_DOCUMENTS_SAVES: SaveRule = SaveRule {
+    root: SaveRoot::PublicDocuments,
+    dir: "Steam/CODEX/{appid}",
+    files: &["achievements.ini"],
+};
+const CODEX_APPDATA_SAVES: SaveRule = SaveRule {
+    root: SaveRoot::AppData,
+    dir: "Steam/CODEX/{appid}",
+    files: &["achievements.ini"],
+};
+const RELOADED_SAVES: SaveRule = SaveRule {
+    root: SaveRoot::ProgramData,
+    dir: "Steam/RLD!/{appid}/stats",
+    files: &["achievements.ini"],
+};
+const SKIDROW_SAVES: SaveRule = SaveRule {
+    root: SaveRoot::Documents,
+    dir: "SKIDROW/{appid}/SteamEmu/UserStats",
+    files: &["achiev.ini"],
+};
+const FLT_SAVES: SaveRule = SaveRule {
+    root: SaveRoot::ProgramData,
+    dir: "FLT/{appid}/stats",
+    files: &["achievements.ini"],
+};
+
+const EMPRESS_FILES: &[&str] = &[
+    "achievements.json",
+    "achievements.ini",
+    "achiev.ini",
+    "stats.ini",
+];
+
+const FINGERPRINTS: &[Fingerprint] = &[
+    Fingerprint {
+        kind: EmulatorKind::Goldberg,
+        markers: &["steam_settings"],
+        content_markers: &[],
+        dll_markers: &[b"Goldberg", b"gbe_fork", b"GSE Saves"],
+        app_id: &[AppIdSource::TextFile("steam_appid.txt")],
+        saves: &[
+            SaveRule {
+                root: SaveRoot::AppData,
+                dir: "GSE Saves/{appid}",
+                files: GOLDBERG_FILES,
+            },
+            SaveRule {
+                root: SaveRoot::AppData,
+                dir: "Goldberg SteamEmu Saves/{appid}",
+                files: GOLDBERG_FILES,
+            },
+        ],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Codex,
+        markers: &["steam_emu.ini"],
+        content_markers: &["CODEX"],
+        dll_markers: &[b"CODEX"],
+        app_id: &[AppIdSource::IniKey {
+            file: "steam_emu.ini",
+            section: "",
+            key: "AppId",
+        }],
+        saves: &[CODEX_DOCUMENTS_SAVES, CODEX_APPDATA_SAVES],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Rune,
+        markers: &["steam_emu.ini"],
+        content_markers: &["RUNE"],
+        dll_markers: &[b"\\RUNE"],
+        app_id: &[AppIdSource::IniKey {
+            file: "steam_emu.ini",
+            section: "",
+            key: "AppId",
+        }],
+        saves: &[SaveRule {
+            root: SaveRoot::PublicDocuments,
+            dir: "Steam/RUNE/{appid}",
+            files: &["achievements.ini"],
+        }],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::OnlineFix,
+        markers: &["onlinefix.ini", "onlinefix64.dll"],
+        content_markers: &[],
+        dll_markers: &[b"OnlineFix"],
+        app_id: &[AppIdSource::IniKey {
+            file: "onlinefix.ini",
+            section: "",
+            key: "RealAppId",
+        }],
+        saves: &[SaveRule {
+            root: SaveRoot::PublicDocuments,
+            dir: "OnlineFix/{appid}/Stats",
+            files: &["Achievements.ini"],
+        }],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Ali213,
+        // Releases ship the emulator in its own folder next to the exe.
+        markers: &["valve.ini", "ali213 steamemu"],
+        content_markers: &[],
+        dll_markers: &[b"ALI213"],
+        app_id: &[AppIdSource::IniKey {
+            file: "valve.ini",
+            section: "Settings",
+            key: "AppID",
+        }],
+        saves: &[SaveRule {
+            root: SaveRoot::ConfigDir,
+            dir: "Profile/{player}/Stats",
+            files: &["achievements.bin"],
+        }],
+        tree_files: &["achievements.bin"],
+    },
+    Fingerprint {
+        kind: EmulatorKind::SteamConfig,
+        markers: &["steamconfig.ini"],
+        content_markers: &[],
+        dll_markers: &[],
+        app_id: &[AppIdSource::IniKey {
+            file: "steamconfig.ini",
+            section: "",
+            key: "AppId",
+        }],
+        saves: &[],
+        tree_files: &["steamconfig.ini"],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Tenoke,
+        markers: &["tenoke.ini"],
+        content_markers: &[],
+        dll_markers: &[b"TENOKE"],
+        app_id: &[AppIdSource::IniKey {
+            file: "tenoke.ini",
+            section: "TENOKE",
+            key: "id",
+        }],
+        saves: &[SaveRule {
+            root: SaveRoot::ConfigDir,
+            dir: "SteamData",
+            files: &["user_stats.ini"],
+        }],
+        tree_files: &["user_stats.ini"],
+    },
+    Fingerprint {
+        kind: EmulatorKind::CreamApi,
+        markers: &["cream_api.ini"],
+        content_markers: &[],
+        dll_markers: &[b"CreamAPI"],
+        app_id: &[AppIdSource::IniKey {
+            file: "cream_api.ini",
+            section: "config",
+            key: "appid",
+        }],
+        saves: &[
+            SaveRule {
+                root: SaveRoot::AppData,
+                dir: "CreamAPI/{appid}/stats",
+                files: &["CreamAPI.Achievements.cfg"],
+            },
+            SaveRule {
+                root: SaveRoot::AppData,
+                dir: "GSE Saves/{appid}/stats",
+                files: &["CreamAPI.Achievements.cfg"],
+            },
+        ],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::SmartSteamEmu,
+        markers: &["smartsteamemu.ini", "smartsteamemu"],
+        content_markers: &[],
+        dll_markers: &[b"SmartSteamEmu"],
+        app_id: &[
+            AppIdSource::IniKey {
+                file: "smartsteamemu.ini",
+                section: "",
+                key: "AppId",
+            },
+            AppIdSource::TextFile("steam_appid.txt"),
+        ],
+        saves: &[SaveRule {
+            root: SaveRoot::AppData,
+            dir: "SmartSteamEmu/{appid}",
+            files: &["stats.bin"],
+        }],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::ThreeDm,
+        markers: &["3dmgame.ini", "3dmgame.dll"],
+        content_markers: &[],
+        dll_markers: &[b"3DMGAME"],
+        app_id: &[AppIdSource::IniKey {
+            file: "3dmgame.ini",
+            section: "",
+            key: "AppID",
+        }],
+        saves: &[
+            SaveRule {
+                root: SaveRoot::ConfigDir,
+                dir: "3DMGAME/Player/stats",
+                files: &["achievements.ini"],
+            },
+            SaveRule {
+                root: SaveRoot::AppData,
+                dir: "3DMGAME/{appid}/Player/stats",
+                files: &["achievements.ini"],
+            },
+        ],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Skidrow,
+        markers: &["skidrow.ini"],
+        content_markers: &[],
+        dll_markers: &[b"SKIDROW"],
+        app_id: &[AppIdSource::IniKey {
+            file: "skidrow.ini",
+            section: "",
+            key: "AppId",
+        }],
+        saves: &[
+            SKIDROW_SAVES,
+            SaveRule {
+                root: SaveRoot::LocalAppData,
+                dir: "SKIDROW/{appid}/SteamEmu/UserStats",
+                files: &["achiev.ini"],
+            },
+        ],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Reloaded,
+        markers: &["steam_emu.ini", "rld.ini"],
+        content_markers: &["RLD!", "RELOADED"],
+        dll_markers: &[b"RLD!"],
+        app_id: &[
+            AppIdSource::IniKey {
+                file: "steam_emu.ini",
+                section: "",
+                key: "AppId",
+            },
+            AppIdSource::IniKey {
+                file: "rld.ini",
+                section: "",
+                key: "AppId",
+            },
+        ],
+        saves: &[
+            RELOADED_SAVES,
+            // Repacks rename the emulator's profile folder.
+            SaveRule {
+                root: SaveRoot::ProgramData,
+                dir: "Steam/Player/{appid}/stats",
+                files: &["achievements.ini"],
+            },
+            SaveRule {
+                root: SaveRoot::ProgramData,
+                dir: "Steam/dodi/{appid}/stats",
+                files: &["achievements.ini"],
+            },
+        ],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Flt,
+        markers: &["steam_emu.ini"],
+        content_markers: &["FLT"],
+        dll_markers: &[b"\\FLT"],
+        app_id: &[AppIdSource::IniKey {
+            file: "steam_emu.ini",
+            section: "",
+            key: "AppId",
+        }],
+        saves: &[FLT_SAVES],
+        tree_files: &[],
+    },
+    Fingerprint {
+        kind: EmulatorKind::Empress,
+        // Goldberg-based, with its own save folder.
+        markers: &["empress.ini", "empress"],
+        content_markers: &["EMPRESS"],
+        dll_markers: &[b"EMPRESS"],
+        app_id: &[
+            AppIdSource::TextFile("steam_appid.txt"),
+            AppIdSource::IniKey {
+                file: "empress.ini",
+                section: "",
+                key: "AppId",
+            },
+        ],
+        saves: &[
+            SaveRule {
+                root: SaveRoot::AppData,
+                dir: "EMPRESS/remote/{appid}",
+                files: EMPRESS_FILES,
+            },
+            SaveRule {
+                root: SaveRoot::PublicDocuments,
+                dir: "EMPRESS/remote/{appid}",
+                files: EMPRESS_FILES,
+            },
+        ],
+        tree_files: &[],
+    },
+];
+
+const MAX_SCAN_DEPTH: usize = 4;
+const MAX_SCAN_ENTRIES: usize = 20_000;
+const MAX_DLL_BYTES: u64 = 32 * 1024 * 1024;
+const STEAM_API_DLLS: [&str; 2] = ["steam_api.dll", "steam_api64.dll"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmulatorDetection {
+    pub emulator: EmulatorKind,
+    pub app_id: Option<String>,
+    /// How strongly the directory matched; detections are sorted by it.
+    pub score: u32,
+    /// Directory of the fingerprinted config (or emulator DLL).
+    pub config_dir: Option<String>,
+    /// Files and directories that matched the fingerprint.
+    pub evidence: Vec<String>,
+    pub save_dir: Option<String>,
+    /// Files to hand to `track_files`. When none exist yet this is where the
+    /// emulator will create its first one.
+    pub watch_files: Vec<String>,
+    pub saves_found: bool,
+}
+
+/// Every file and directory under the game directory, by lowercase name.
+struct TreeIndex {
+    entries: HashMap<String, Vec<PathBuf>>,
+}
+
+impl TreeIndex {
+    fn scan(root: &Path) -> TreeIndex {
+        let mut entries: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        let mut pending = vec![(root.to_path_buf(), 0)];
+        let mut seen = 0;
+        while let Some((dir, depth)) = pending.pop() {
+            let Ok(read) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read.flatten() {
+                seen += 1;
+                if seen > MAX_SCAN_ENTRIES {
+                    println!("Stopped scanning {} after {} entries", root.display(), seen);
+                    return TreeIndex { entries };
+                }
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                if depth < MAX_SCAN_DEPTH && path.is_dir() {
+                    pending.push((path.clone(), depth + 1));
+                }
+                entries.entry(name).or_default().push(path);
+            }
+        }
+        // Shallowest first, so the copy next to the exe wins.
+        for paths in entries.values_mut() {
+            paths.sort_by_key(|p| p.components().count());
+        }
+        TreeIndex { entries }
+    }
+
+    fn get(&self, name: &str) -> &[PathBuf] {
+        self.entries
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    !needle.is_empty() && haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+fn read_app_id(source: &AppIdSource, tree: &TreeIndex) -> Option<String> {
+    let valid = |id: &str| {
+        let id = id.trim();
+        (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| id.to_string())
+    };
+    match source {
+        AppIdSource::TextFile(file) => tree
+            .get(file)
+            .iter()
+            .find_map(|path| valid(&ini::decode_text(&std::fs::read(path).ok()?))),
+        AppIdSource::IniKey { file, section, key } => tree.get(file).iter().find_map(|path| {
+            let ini = Ini::read(path).ok()?;
+            let value = if section.is_empty() {
+                ini.find(key)
+            } else {
+                ini.get(section, key)
+            };
+            valid(value?)
+        }),
+    }
+}
+
+/// Player name used in ALI213 profile paths.
+fn player_name(tree: &TreeIndex) -> Option<String> {
+    tree.get("valve.ini").iter().find_map(|path| {
+        Ini::read(path)
+            .ok()?
+            .get("Settings", "PlayerName")
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// Wine prefixes to look for saves in: the launcher's own first, then guessed ones.
+fn prefixes_for(
+    app_id: Option<&str>,
+    game_dir: &Path,
+    prefix: Option<&WinePrefix>,
+) -> Vec<WinePrefix> {
+    let mut prefixes: Vec<WinePrefix> = prefix.cloned().into_iter().collect();
+    prefixes.extend(
+        wine::candidate_prefixes(app_id, Some(game_dir))
+            .into_iter()
+            .filter(|p| prefix.is_none_or(|own| own.path != p.path)),
+    );
+    prefixes
+}
+
+fn detect_with(
+    fingerprint: &Fingerprint,
+    tree: &TreeIndex,
+    dlls: &[(PathBuf, Vec<u8>)],
+    game_dir: &Path,
+    prefix: Option<&WinePrefix>,
+) -> Option<EmulatorDetection> {
+    let mut score = 0;
+    let mut evidence = Vec::new();
+    let mut config_dir = None;
+
+    let marker_paths: Vec<&PathBuf> = fingerprint
+        .markers
+        .iter()
+        .flat_map(|marker| tree.get(marker))
+        .collect();
+    if let Some(first) = marker_paths.first() {
+        score += 2;
+        config_dir = first.parent().map(Path::to_path_buf);
+        evidence.extend(
+            marker_paths
+                .iter()
+                .map(|p| p.to_string_lossy().into_owned()),
+        );
+    }
+    if !fingerprint.content_markers.is_empty()
+        && marker_paths.iter().filter(|p| p.is_file()).any(|path| {
+            let text = std::fs::read(path)
+                .map(|b| ini::decode_text(&b))
+                .unwrap_or_default();
+            fingerprint
+                .content_markers
+                .iter()
+                .any(|marker| text.contains(marker))
+        })
+    {
+        score += 2;
+    }
+    for (dll, bytes) in dlls {
+        if fingerprint
+            .dll_markers
+            .iter()
+            .any(|marker| contains_bytes(bytes, marker))
+        {
+            score += 3;
+            evidence.push(dll.to_string_lossy().into_owned());
+            if config_dir.is_none() {
+                config_dir = dll.parent().map(Path::to_path_buf);
+            }
+            break;
+        }
+    }
+    if score == 0 {
+        return None;
+    }
+
+    let app_id = fingerprint
+        .app_id
+        .iter()
+        .find_map(|source| read_app_id(source, tree));
+    if app_id.is_some() {
+        score += 1;
+    }
+
+    let vars = SaveVars {
+        app_id: app_id.clone(),
+        player_name: player_name(tree),
+    };
+    // ALI213 writes wherever the `SaveType` in its valve.ini says.
+    let valve_ini = (fingerprint.kind == EmulatorKind::Ali213)
+        .then(|| {
+            tree.get("valve.ini")
+                .iter()
+                .find_map(|path| ValveIni::read(path).ok())
+        })
+        .flatten();
+    let save_rules: Vec<SaveRule> = match &valve_ini {
+        Some(valve_ini) => vec![valve_ini.save_rule()],
+        None => fingerprint.saves.to_vec(),
+    };
+    let prefixes = if save_rules
+        .iter()
+        .any(|rule| rule.root != SaveRoot::ConfigDir)
+    {
+        prefixes_for(app_id.as_deref(), game_dir, prefix)
+    } else {
+        Vec::new()
+    };
+    let base = match &valve_ini {
+        Some(valve_ini) => valve_ini.game_dir(&prefixes),
+        None => config_dir.clone().unwrap_or_else(|| game_dir.to_path_buf()),
+    };
+    let mut save_dir = None;
+    let mut watch_files: Vec<PathBuf> = Vec::new();
+    let mut expected = None;
+    for rule in &save_rules {
+        for dir in rule.candidates(&base, &vars, &prefixes) {
+            let existing: Vec<PathBuf> = rule
+                .files
+                .iter()
+                .map(|file| dir.join(file))
+                .filter(|path| path.is_file())
+                .collect();
+            if existing.is_empty() {
+                expected.get_or_insert_with(|| (dir.clone(), dir.join(rule.files[0])));
+            } else {
+                save_dir.get_or_insert_with(|| dir.clone());
+                watch_files.extend(existing);
+            }
+        }
+    }
+    for file in fingerprint.tree_files {
+        for path in tree.get(file).iter().filter(|p| p.is_file()) {
+            // Save rules may already list it under different casing.
+            let listed = watch_files.iter().any(|w| {
+                w.to_string_lossy()
+                    .eq_ignore_ascii_case(&path.to_string_lossy())
+            });
+            if !listed {
+                if save_dir.is_none() {
+                    save_dir = path.parent().map(Path::to_path_buf);
+                }
+                watch_files.push(path.clone());
+            }
+        }
+    }
+
+    let saves_found = !watch_files.is_empty();
+    if saves_found {
+        score += 2;
+    } else if let Some((dir, file)) = expected {
+        save_dir = Some(dir);
+        watch_files.push(file);
+    }
+
+    Some(EmulatorDetection {
+        emulator: fingerprint.kind,
+        app_id,
+        score,
+        config_dir: config_dir.map(|p| p.to_string_lossy().into_owned()),
+        evidence,
+        save_dir: save_dir.map(|p| p.to_string_lossy().into_owned()),
+        watch_files: watch_files
+            .into_iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect(),
+        saves_found,
+    })
+}
+
+/// Fingerprints the emulator used under `game_dir` (an exe path is taken as its
+/// directory). Returns every match, best first.
+pub fn detect(game_dir: &Path) -> Vec<EmulatorDetection> {
+    detect_in_prefix(game_dir, None)
+}
+
+/// Like [`detect`], for a game whose launcher names its Wine prefix; save paths in
+/// `prefix` are preferred over guessed ones.
+pub fn detect_in_prefix(game_dir: &Path, prefix: Option<&WinePrefix>) -> Vec<EmulatorDetection> {
+    let root = if game_dir.is_file() {
+        game_dir.parent().unwrap_or(game_dir)
+    } else {
+        game_dir
+    };
+    let tree = TreeIndex::scan(root);
+
+    let dlls: Vec<(PathBuf, Vec<u8>)> = STEAM_API_DLLS
+        .iter()
+        .flat_map(|name| tree.get(name))
+        .filter(|path| {
+            path.metadata()
+                .is_ok_and(|m| m.is_file() && m.len() <= MAX_DLL_BYTES)
+        })
+        .filter_map(|path| Some((path.clone(), std::fs::read(path).ok()?)))
+        .collect();
+
+    let mut detections: Vec<EmulatorDetection> = FINGERPRINTS
+        .iter()
+        .filter_map(|fingerprint| detect_with(fingerprint, &tree, &dlls, root, prefix))
+        .collect();
+    // Stable sort keeps table order between equal scores.
+    detections.sort_by_key(|d| std::cmp::Reverse(d.score));
+    detections
+}
+
+/// Scans the directory tree next to a game's exe and reports the emulator, appid,
+/// save location and the files to watch for unlocks.
+#[tauri::command]
+pub async fn detect_emulator(game_dir: String) -> Result<Vec<EmulatorDetection>, String> {
+    let path = PathBuf::from(&game_dir);
+    if !path.exists() {
+        return Err(format!("Game directory not found: {}", game_dir));
+    }
+    let detections = tauri::async_runtime::spawn_blocking(move || detect(&path))
+        .await
+        .map_err(|e| format!("Failed to scan game directory: {}", e))?;
+    match detections.first() {
+        Some(best) => println!(
+            "Detected {} (appid {:?}) in {}",
+            best.emulator.label(),
+            best.app_id,
+            game_dir
+        ),
+        None => println!("No emulator detected in {}", game_dir),
+    }
+    Ok(detections)
+}
+
+/// Decodes a binary or CFG save file (`stats.bin`, `Achievements.Bin`,
+/// `achieve.dat`, `CreamAPI.Achievements.cfg`) into achievement flags and stat
+/// values. Hashed names are resolved against the achievements stored for
+/// `app_id`. `None` when the file is not one of these formats.
+#[tauri::command]
+pub fn decode_save_file(
+    db: State<'_, LibraryDb>,
+    path: String,
+    app_id: Option<String>,
+) -> Result<Option<DecodedSave>, String> {
+    let file = PathBuf::from(&path);
+    if !decoders::is_decoded(&file) {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(&file).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let names: Vec<String> = match app_id.filter(|_| decoders::needs_names(&file)) {
+        Some(app_id) => db
+            .list_achievements(&app_id)?
+            .into_iter()
+            .map(|a| a.api_name)
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok(decoders::decode(&file, &bytes, &names))
+}
+
+/// Reads ALI213's `valve.ini` (the file or its directory) and returns where its
+/// `SaveType` makes the emulator write achievements, on Windows or in the
+/// game's Wine prefixes (`wine_prefix` first when given).
+#[tauri::command]
+pub fn resolve_valve_ini(
+    path: String,
+    wine_prefix: Option<String>,
+) -> Result<ValveIniSaves, String> {
+    let mut file = PathBuf::from(&path);
+    if file.is_dir() {
+        file.push("valve.ini");
+    }
+    let valve_ini = ValveIni::read(&file)?;
+    let own = wine_prefix.and_then(|p| WinePrefix::at(PathBuf::from(p)));
+    let game_dir = file.parent().unwrap_or(Path::new(""));
+    let prefixes = prefixes_for(valve_ini.app_id.as_deref(), game_dir, own.as_ref());
+    let saves = valve_ini.resolve(&prefixes);
+    println!(
+        "valve.ini SaveType {} ({}) for {}: {:?}",
+        saves.save_type, saves.layout, path, saves.achievement_files
+    );
+    Ok(saves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/emulators");
+
+    const FIXTURE_EMULATORS: [(&str, EmulatorKind); 6] = [
+        ("smartsteamemu", EmulatorKind::SmartSteamEmu),
+        ("3dm", EmulatorKind::ThreeDm),
+        ("skidrow", EmulatorKind::Skidrow),
+        ("reloaded", EmulatorKind::Reloaded),
+        ("flt", EmulatorKind::Flt),
+        ("empress", EmulatorKind::Empress),
+    ];
+
+    fn fingerprint(kind: EmulatorKind) -> &'static Fingerprint {
+        FINGERPRINTS.iter().find(|f| f.kind == kind).unwrap()
+    }
+
+    #[test]
+    fn detects_each_fixture_emulator_first() {
+        for (dir, kind) in FIXTURE_EMULATORS {
+            let detections = detect(&Path::new(FIXTURES).join(dir).join("game"));
+            let best = detections.first().unwrap();
+            assert_eq!(best.emulator, kind, "{}", dir);
+            assert_eq!(best.app_id.as_deref(), Some("480"), "{}", dir);
+        }
+    }
+
+    #[test]
+    fn save_rules_find_the_fixture_saves() {
+        let vars = SaveVars {
+            app_id: Some("480".to_string()),
+            player_name: None,
+        };
+        for (dir, kind) in FIXTURE_EMULATORS {
+            // The fixture's `saves/` stands in for whichever root the rule uses.
+            let saves_root = Path::new(FIXTURES).join(dir).join("saves");
+            let found = fingerprint(kind).saves.iter().any(|rule| {
+                let rule = SaveRule {
+                    root: SaveRoot::ConfigDir,
+                    ..*rule
+                };
+                rule.candidates(&saves_root, &vars, &[])
+                    .iter()
+                    .any(|candidate| rule.files.iter().any(|file| candidate.join(file).is_file()))
+            });
+            assert!(found, "{}", dir);
+        }
+    }
+}
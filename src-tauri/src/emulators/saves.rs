@@ -0,0 +1,108 @@
+//! Where emulators keep their save data, as roots that are resolved per machine.
+
+use crate::wine::{self, WindowsFolder, WinePrefix};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Base directory a save rule is relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveRoot {
+    /// The directory holding the emulator config that was fingerprinted.
+    ConfigDir,
+    /// `%APPDATA%` (`AppData/Roaming`).
+    AppData,
+    /// `%LOCALAPPDATA%` (`AppData/Local`).
+    LocalAppData,
+    /// `%PUBLIC%/Documents`.
+    PublicDocuments,
+    /// The user's Documents folder.
+    Documents,
+    /// `%ProgramData%`.
+    ProgramData,
+}
+
+/// A save directory (`dir`, `/`-separated, with `{appid}` and `{player}`
+/// placeholders) and the files in it that record unlocks, most specific first.
+#[derive(Debug, Clone, Copy)]
+pub struct SaveRule {
+    pub root: SaveRoot,
+    pub dir: &'static str,
+    pub files: &'static [&'static str],
+}
+
+/// Values for the placeholders in [`SaveRule::dir`].
+#[derive(Debug, Clone, Default)]
+pub struct SaveVars {
+    pub app_id: Option<String>,
+    pub player_name: Option<String>,
+}
+
+impl SaveRule {
+    /// Possible save directories on this machine, in order of preference. Empty when
+    /// the root does not exist here or a placeholder has no value.
+    pub fn candidates(
+        &self,
+        config_dir: &Path,
+        vars: &SaveVars,
+        prefixes: &[WinePrefix],
+    ) -> Vec<PathBuf> {
+        let mut parts = Vec::new();
+        for part in self.dir.split('/').filter(|p| !p.is_empty()) {
+            let part = match part {
+                "{appid}" => vars.app_id.clone(),
+                "{player}" => vars.player_name.clone(),
+                _ => Some(part.to_string()),
+            };
+            match part {
+                Some(part) => parts.push(part),
+                None => return Vec::new(),
+            }
+        }
+        resolve_roots(self.root, config_dir, prefixes)
+            .into_iter()
+            .map(|mut path| {
+                path.extend(&parts);
+                path
+            })
+            .collect()
+    }
+}
+
+/// Where `root` is on this machine. Outside Windows the user folders only exist
+/// inside Wine or Proton prefixes, so each prefix contributes its own copy.
+pub fn resolve_roots(root: SaveRoot, config_dir: &Path, prefixes: &[WinePrefix]) -> Vec<PathBuf> {
+    let env = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    if root == SaveRoot::ConfigDir {
+        return vec![config_dir.to_path_buf()];
+    }
+    if cfg!(windows) {
+        return match root {
+            SaveRoot::AppData => env("APPDATA"),
+            SaveRoot::LocalAppData => env("LOCALAPPDATA"),
+            SaveRoot::PublicDocuments => env("PUBLIC").map(|p| p.join("Documents")),
+            SaveRoot::Documents => env("USERPROFILE").map(|p| p.join("Documents")),
+            SaveRoot::ProgramData => env("ProgramData"),
+            SaveRoot::ConfigDir => None,
+        }
+        .into_iter()
+        .collect();
+    }
+
+    let (folder, sub) = match root {
+        SaveRoot::AppData => (WindowsFolder::RoamingAppData, None),
+        SaveRoot::LocalAppData => (WindowsFolder::LocalAppData, None),
+        SaveRoot::PublicDocuments => (WindowsFolder::Public, Some("Documents")),
+        SaveRoot::Documents => (WindowsFolder::Documents, None),
+        SaveRoot::ProgramData => (WindowsFolder::ProgramData, None),
+        SaveRoot::ConfigDir => return Vec::new(),
+    };
+    prefixes
+        .iter()
+        .flat_map(|prefix| wine::folder_in_prefix(prefix, folder))
+        .map(|dir| match sub {
+            Some(sub) => dir.join(sub),
+            None => dir,
+        })
+        .collect()
+}
@@ -0,0 +1,422 @@
+//! Unlock state read back from emulator save files, so a change can be diffed
+//! into unlocked and relocked achievements.
+
+use super::ini::Ini;
+use super::EmulatorKind;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FileUnlock {
+    pub unlocked: bool,
+    /// Unix timestamp recorded by the emulator, if any.
+    pub unlock_time: Option<i64>,
+}
+
+/// Achievements in one save file, keyed by API name.
+pub type FileUnlocks = BTreeMap<String, FileUnlock>;
+
+/// Keys that flag an INI section as an unlocked achievement (CODEX/RUNE
+/// `Achieved`, ALI213 and SteamConfig `HaveAchieved`, OnlineFix `achieved`).
+const ACHIEVED_KEYS: &[&str] = &["Achieved", "HaveAchieved", "unlocked"];
+/// Unlock time keys, in the same order: CODEX/RUNE `UnlockTime`, ALI213 and
+/// SteamConfig `HaveAchievedTime` (older ALI213 builds write `Time`), OnlineFix
+/// `timestamp`.
+const TIME_KEYS: &[&str] = &["UnlockTime", "HaveAchievedTime", "timestamp", "time"];
+
+/// Sections that hold bookkeeping rather than an achievement.
+const SKIPPED_SECTIONS: &[&str] = &["", "SteamAchievements", "Settings", "Stats"];
+
+fn truthy(value: &str) -> bool {
+    value == "1" || value.eq_ignore_ascii_case("true")
+}
+
+/// Seconds between 1601-01-01 (Windows FILETIME epoch) and the Unix epoch.
+const FILETIME_UNIX_OFFSET: i64 = 11_644_473_600;
+
+/// Normalizes an emulator's unlock time to Unix seconds (UTC). Most write Unix
+/// seconds; milli- and microseconds and Windows FILETIME values are scaled down, and date
+/// strings without an offset are taken as local time, which is how emulators
+/// that write them record it.
+pub fn normalize_time(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(raw) = value.parse::<i64>() {
+        let seconds = match raw {
+            r if r <= 0 => return None,
+            r if r >= FILETIME_UNIX_OFFSET * 10_000_000 => r / 10_000_000 - FILETIME_UNIX_OFFSET,
+            r if r >= 100_000_000_000_000 => r / 1_000_000,
+            r if r >= 100_000_000_000 => r / 1000,
+            r => r,
+        };
+        return (seconds > 0).then_some(seconds);
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(time.timestamp());
+    }
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y/%m/%d %H:%M:%S",
+        "%d.%m.%Y %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+    .and_then(|naive| naive.and_local_timezone(chrono::Local).earliest())
+    .map(|time| time.timestamp())
+}
+
+/// Goldberg/GSE `achievements.json`: `{"ACH": {"earned": true, "earned_time": 123}}`.
+fn parse_json(text: &str) -> Option<FileUnlocks> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let mut unlocks = FileUnlocks::new();
+    for (name, entry) in value.as_object()? {
+        let Some(earned) = entry.get("earned") else {
+            continue;
+        };
+        let unlocked = earned
+            .as_bool()
+            .unwrap_or_else(|| earned.as_i64() == Some(1));
+        let unlock_time = match entry.get("earned_time") {
+            Some(serde_json::Value::Number(n)) => n
+                .as_i64()
+                .or_else(|| n.as_f64().map(|f| f as i64))
+                .and_then(|t| normalize_time(&t.to_string())),
+            Some(serde_json::Value::String(s)) => normalize_time(s),
+            _ => None,
+        };
+        unlocks.insert(
+            name.clone(),
+            FileUnlock {
+                unlocked,
+                unlock_time,
+            },
+        );
+    }
+    (!unlocks.is_empty() || value.as_object()?.is_empty()).then_some(unlocks)
+}
+
+/// Tenoke `user_stats.ini`: `"ACH" = {unlocked = true, time = 123}` lines under
+/// `[ACHIEVEMENTS]`.
+fn parse_tenoke(ini: &Ini) -> Option<FileUnlocks> {
+    let entries = ini.section("ACHIEVEMENTS")?;
+    let mut unlocks = FileUnlocks::new();
+    for (key, value) in entries {
+        let Some(fields) = value.strip_prefix('{').and_then(|v| v.strip_suffix('}')) else {
+            continue;
+        };
+        let mut unlock = FileUnlock {
+            unlocked: false,
+            unlock_time: None,
+        };
+        for field in fields.split(',') {
+            let Some((name, value)) = field.split_once('=') else {
+                continue;
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "unlocked" => unlock.unlocked = truthy(value.trim()),
+                "time" => unlock.unlock_time = normalize_time(value),
+                _ => {}
+            }
+        }
+        unlocks.insert(key.trim_matches('"').to_string(), unlock);
+    }
+    Some(unlocks)
+}
+
+/// 3DM `achievements.ini` and `achieve.dat`: `API_NAME=1` flags under
+/// `[ACHIEVE_DATA]` (a value above 1 is the unlock time) with an optional
+/// `API_NAME_TIME` entry for the unlock time.
+pub fn parse_achieve_data(ini: &Ini) -> Option<FileUnlocks> {
+    let entries = ini.section("ACHIEVE_DATA")?;
+    let time_of = |name: &str| {
+        let time_key = format!("{}_TIME", name);
+        entries
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&time_key))
+            .and_then(|(_, value)| normalize_time(value))
+    };
+
+    let mut unlocks = FileUnlocks::new();
+    for (key, value) in entries {
+        if key.to_ascii_uppercase().ends_with("_TIME") {
+            continue;
+        }
+        let Ok(flag) = value.trim().parse::<i64>() else {
+            continue;
+        };
+        let unlock = FileUnlock {
+            unlocked: flag != 0,
+            unlock_time: time_of(key).or_else(|| {
+                if flag > 1 {
+                    normalize_time(value)
+                } else {
+                    None
+                }
+            }),
+        };
+        unlocks.insert(key.clone(), unlock);
+    }
+    Some(unlocks)
+}
+
+/// Skidrow `achiev.ini`: `API_NAME=1` flags under `[SteamAchievements]` and unlock
+/// times under `[AchievementsUnlockTimes]`. CODEX uses the same section for its
+/// `00000=API_NAME` index, which has numeric keys.
+fn parse_skidrow(ini: &Ini) -> Option<FileUnlocks> {
+    let entries = ini.section("SteamAchievements")?;
+    let times = ini.section("AchievementsUnlockTimes").unwrap_or_default();
+    let mut unlocks = FileUnlocks::new();
+    for (key, value) in entries {
+        if key.eq_ignore_ascii_case("Count") || key.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let unlock_time = times
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .and_then(|(_, t)| normalize_time(t));
+        unlocks.insert(
+            key.clone(),
+            FileUnlock {
+                unlocked: truthy(value),
+                unlock_time,
+            },
+        );
+    }
+    (!unlocks.is_empty()).then_some(unlocks)
+}
+
+/// Decodes hex digits into bytes.
+fn hex_bytes(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim();
+    if value.is_empty() || !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// RELOADED `achievements.ini`: one section per achievement with a `State` of hex
+/// bytes (the first one set when unlocked) and a `Time` of four little-endian
+/// hex bytes.
+fn parse_state_sections(ini: &Ini) -> Option<FileUnlocks> {
+    let mut unlocks = FileUnlocks::new();
+    for (section, _) in &ini.sections {
+        let Some(state) = ini.get(section, "State") else {
+            continue;
+        };
+        let unlocked = match hex_bytes(state) {
+            Some(bytes) => bytes.first().is_some_and(|&b| b != 0),
+            None => truthy(state),
+        };
+        let unlock_time = ini.get(section, "Time").and_then(|time| {
+            match hex_bytes(time).filter(|bytes| bytes.len() == 4) {
+                Some(bytes) => {
+                    let seconds = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    normalize_time(&seconds.to_string())
+                }
+                None => normalize_time(time),
+            }
+        });
+        unlocks.insert(
+            section.clone(),
+            FileUnlock {
+                unlocked,
+                unlock_time,
+            },
+        );
+    }
+    (!unlocks.is_empty()).then_some(unlocks)
+}
+
+/// One section per achievement, as CODEX, RUNE, OnlineFix, ALI213 and
+/// SteamConfig write them.
+fn parse_sections(ini: &Ini) -> Option<FileUnlocks> {
+    let find = |entries: &[(String, String)], keys: &[&str]| {
+        entries
+            .iter()
+            .find(|(k, _)| keys.iter().any(|key| k.eq_ignore_ascii_case(key)))
+            .map(|(_, v)| v.clone())
+    };
+
+    let mut unlocks = FileUnlocks::new();
+    for (section, entries) in &ini.sections {
+        if SKIPPED_SECTIONS
+            .iter()
+            .any(|s| section.eq_ignore_ascii_case(s))
+        {
+            continue;
+        }
+        let Some(achieved) = find(entries, ACHIEVED_KEYS) else {
+            continue;
+        };
+        unlocks.insert(
+            section.clone(),
+            FileUnlock {
+                unlocked: truthy(&achieved),
+                unlock_time: find(entries, TIME_KEYS).and_then(|t| normalize_time(&t)),
+            },
+        );
+    }
+    // A bare `[SteamAchievements]` header is what CODEX leaves after a reset.
+    let has_index = ini.section("SteamAchievements").is_some();
+    (!unlocks.is_empty() || has_index).then_some(unlocks)
+}
+
+/// Reads the unlock state from a save file's text. `None` when the format is not
+/// recognised; an empty file has no unlocks.
+pub fn parse_unlocks(text: &str) -> Option<FileUnlocks> {
+    let text = text.trim_start_matches('\u{feff}').trim();
+    if text.is_empty() {
+        return Some(FileUnlocks::new());
+    }
+    if text.starts_with('{') {
+        return parse_json(text);
+    }
+    let ini = Ini::parse(text);
+    parse_tenoke(&ini)
+        .or_else(|| parse_achieve_data(&ini))
+        .or_else(|| parse_skidrow(&ini))
+        .or_else(|| parse_state_sections(&ini))
+        .or_else(|| parse_sections(&ini))
+}
+
+/// API names unlocked in `before` that are no longer unlocked in `after`.
+pub fn relocked(before: &FileUnlocks, after: &FileUnlocks) -> Vec<String> {
+    before
+        .iter()
+        .filter(|(_, unlock)| unlock.unlocked)
+        .filter(|(name, _)| !after.get(*name).is_some_and(|u| u.unlocked))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Achievements unlocked in `after` that were not unlocked in `before`, with the
+/// emulator's unlock time when the format records one.
+pub fn newly_unlocked(
+    before: Option<&FileUnlocks>,
+    after: &FileUnlocks,
+) -> Vec<(String, Option<i64>)> {
+    after
+        .iter()
+        .filter(|(_, unlock)| unlock.unlocked)
+        .filter(|(name, _)| {
+            !before
+                .and_then(|b| b.get(*name))
+                .is_some_and(|u| u.unlocked)
+        })
+        .map(|(name, unlock)| (name.clone(), unlock.unlock_time))
+        .collect()
+}
+
+/// The emulator that writes a save file, from its location or, failing that,
+/// its format.
+pub fn emulator_for(path: &Path, text: &str) -> Option<EmulatorKind> {
+    let file_name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    let dirs: Vec<String> = path
+        .ancestors()
+        .skip(1)
+        .filter_map(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .collect();
+    let in_dir = |name: &str| dirs.iter().any(|dir| dir == name);
+
+    let kind = if in_dir("empress") {
+        EmulatorKind::Empress
+    } else if in_dir("gse saves") || in_dir("goldberg steamemu saves") {
+        EmulatorKind::Goldberg
+    } else if in_dir("smartsteamemu") {
+        EmulatorKind::SmartSteamEmu
+    } else if in_dir("3dmgame") {
+        EmulatorKind::ThreeDm
+    } else if in_dir("skidrow") {
+        EmulatorKind::Skidrow
+    } else if in_dir("rld!") || in_dir("dodi") {
+        EmulatorKind::Reloaded
+    } else if in_dir("flt") {
+        EmulatorKind::Flt
+    } else if in_dir("codex") {
+        EmulatorKind::Codex
+    } else if in_dir("rune") {
+        EmulatorKind::Rune
+    } else if in_dir("onlinefix") {
+        EmulatorKind::OnlineFix
+    } else if file_name == "steamconfig.ini" {
+        EmulatorKind::SteamConfig
+    } else if file_name == "user_stats.ini" {
+        EmulatorKind::Tenoke
+    } else if file_name == "achievements.bin" {
+        EmulatorKind::Ali213
+    } else if file_name == "creamapi.achievements.cfg" {
+        EmulatorKind::CreamApi
+    } else {
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        if text.starts_with('{') {
+            EmulatorKind::Goldberg
+        } else if Ini::parse(text).section("ACHIEVEMENTS").is_some() {
+            EmulatorKind::Tenoke
+        } else {
+            return None;
+        }
+    };
+    Some(kind)
+}
+
+/// API names unlocked in `unlocks`.
+pub fn unlocked_names(unlocks: &FileUnlocks) -> impl Iterator<Item = &String> {
+    unlocks
+        .iter()
+        .filter(|(_, unlock)| unlock.unlocked)
+        .map(|(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulators::{decoders, ini};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/emulators");
+
+    const FIXTURE_SAVES: [&str; 6] = [
+        "smartsteamemu/saves/SmartSteamEmu/480/stats.bin",
+        "3dm/saves/3DMGAME/480/Player/stats/achievements.ini",
+        "skidrow/saves/SKIDROW/480/SteamEmu/UserStats/achiev.ini",
+        "reloaded/saves/Steam/RLD!/480/stats/achievements.ini",
+        "flt/saves/FLT/480/stats/achievements.ini",
+        "empress/saves/EMPRESS/remote/480/achievements.json",
+    ];
+
+    #[test]
+    fn parses_every_fixture_save() {
+        let names = [
+            "ACH_WIN_ONE_GAME".to_string(),
+            "ACH_TRAVEL_FAR_ACCUM".to_string(),
+        ];
+        for save in FIXTURE_SAVES {
+            let path = Path::new(FIXTURES).join(save);
+            let bytes = std::fs::read(&path).unwrap();
+            let unlocks = if decoders::is_decoded(&path) {
+                decoders::decode(&path, &bytes, &names).unwrap().unlocks
+            } else {
+                parse_unlocks(&ini::decode_text(&bytes)).unwrap()
+            };
+            assert_eq!(
+                unlocks.get("ACH_WIN_ONE_GAME"),
+                Some(&FileUnlock {
+                    unlocked: true,
+                    unlock_time: Some(1700000000),
+                }),
+                "{}",
+                save
+            );
+            assert!(
+                !unlocks
+                    .get("ACH_TRAVEL_FAR_ACCUM")
+                    .is_some_and(|unlock| unlock.unlocked),
+                "{}",
+                save
+            );
+        }
+    }
+}
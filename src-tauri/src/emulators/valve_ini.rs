@@ -0,0 +1,331 @@
+//! ALI213's `valve.ini`. `SaveType` picks which emulator's save layout ALI213
+//! imitates, which decides where the achievement file ends up.
+
+use super::ini::Ini;
+use super::saves::{SaveRoot, SaveRule, SaveVars};
+use super::{CODEX_APPDATA_SAVES, CODEX_DOCUMENTS_SAVES, FLT_SAVES, RELOADED_SAVES, SKIDROW_SAVES};
+use crate::wine::{self, WinePrefix};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A `SaveType` value and the layout it selects.
+struct SaveType {
+    value: u32,
+    /// Name used in the comments of the bundled `valve.ini`.
+    name: &'static str,
+    rule: SaveRule,
+}
+
+/// `SaveType`s documented in `valve.ini`. 2 and 3 are undocumented and, like
+/// unknown values, behave as 0.
+const SAVE_TYPES: &[SaveType] = &[
+    SaveType {
+        value: 0,
+        name: "VALVE (game dir)",
+        rule: SaveRule {
+            root: SaveRoot::ConfigDir,
+            dir: "Profile/{player}/Stats",
+            files: &["achievements.bin"],
+        },
+    },
+    SaveType {
+        value: 1,
+        name: "VALVE (my documents)",
+        rule: SaveRule {
+            root: SaveRoot::Documents,
+            dir: "VALVE/{appid}/{player}/Stats",
+            files: &["achievements.bin"],
+        },
+    },
+    SaveType {
+        value: 4,
+        name: "RELOADED",
+        rule: RELOADED_SAVES,
+    },
+    SaveType {
+        value: 5,
+        name: "SKIDROW",
+        rule: SKIDROW_SAVES,
+    },
+    SaveType {
+        value: 6,
+        name: "FLT",
+        rule: FLT_SAVES,
+    },
+    SaveType {
+        value: 7,
+        name: "CODEX (3.0.4+, my documents)",
+        rule: CODEX_DOCUMENTS_SAVES,
+    },
+    SaveType {
+        value: 8,
+        name: "CODEX (1.0.0.0+, APPDATA)",
+        rule: CODEX_APPDATA_SAVES,
+    },
+];
+
+/// The `[Settings]` of a `valve.ini` that decide where saves go.
+#[derive(Debug, Clone)]
+pub struct ValveIni {
+    pub app_id: Option<String>,
+    pub save_type: u32,
+    pub player_name: Option<String>,
+    pub game_exe: Option<String>,
+    /// Directory holding the `valve.ini`.
+    dir: PathBuf,
+}
+
+/// Where a `valve.ini` makes ALI213 write achievements, as returned by
+/// `resolve_valve_ini`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValveIniSaves {
+    pub app_id: Option<String>,
+    pub save_type: u32,
+    /// The layout `save_type` imitates.
+    pub layout: &'static str,
+    pub player_name: Option<String>,
+    pub game_exe: Option<String>,
+    /// Save directories on this machine, existing ones first.
+    pub save_dirs: Vec<String>,
+    /// The achievement file in each save directory, existing ones first.
+    pub achievement_files: Vec<String>,
+}
+
+impl ValveIni {
+    pub fn read(path: &Path) -> Result<ValveIni, String> {
+        let ini = Ini::read(path)?;
+        let setting = |key: &str| {
+            ini.get("Settings", key)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Ok(ValveIni {
+            app_id: setting("AppID").filter(|id| id.chars().all(|c| c.is_ascii_digit())),
+            save_type: setting("SaveType")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0),
+            player_name: setting("PlayerName"),
+            game_exe: setting("GameEXE"),
+            dir: path.parent().unwrap_or(Path::new("")).to_path_buf(),
+        })
+    }
+
+    fn save_type(&self) -> &'static SaveType {
+        SAVE_TYPES
+            .iter()
+            .find(|t| t.value == self.save_type)
+            .unwrap_or(&SAVE_TYPES[0])
+    }
+
+    pub fn save_rule(&self) -> SaveRule {
+        self.save_type().rule
+    }
+
+    pub fn vars(&self) -> SaveVars {
+        SaveVars {
+            app_id: self.app_id.clone(),
+            player_name: self.player_name.clone(),
+        }
+    }
+
+    /// The game directory ALI213 writes `Profile` into: the directory of
+    /// `GameEXE` when it is set (relative to `valve.ini`, or an absolute Windows
+    /// path mapped into `prefixes`), otherwise the one holding `valve.ini`.
+    pub fn game_dir(&self, prefixes: &[WinePrefix]) -> PathBuf {
+        let Some(exe) = &self.game_exe else {
+            return self.dir.clone();
+        };
+        let windows_absolute = exe.len() > 1 && exe.as_bytes()[1] == b':';
+        let exe_path = if windows_absolute && !cfg!(windows) {
+            match wine::map_windows_path(exe, prefixes).into_iter().next() {
+                Some(path) => path,
+                None => return self.dir.clone(),
+            }
+        } else {
+            let mut path = self.dir.clone();
+            for part in exe.split(['\\', '/']) {
+                match part {
+                    "" | "." => {}
+                    ".." => {
+                        path.pop();
+                    }
+                    part => path.push(part),
+                }
+            }
+            path
+        };
+        exe_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.dir.clone())
+    }
+
+    /// Save directories and achievement files for the configured `SaveType`, on
+    /// Windows or in `prefixes`.
+    pub fn resolve(&self, prefixes: &[WinePrefix]) -> ValveIniSaves {
+        let save_type = self.save_type();
+        let rule = save_type.rule;
+        let mut save_dirs = rule.candidates(&self.game_dir(prefixes), &self.vars(), prefixes);
+        save_dirs.sort_by_key(|dir| !dir.is_dir());
+        let mut achievement_files: Vec<PathBuf> = save_dirs
+            .iter()
+            .flat_map(|dir| rule.files.iter().map(move |file| dir.join(file)))
+            .collect();
+        achievement_files.sort_by_key(|file| !file.is_file());
+        let strings = |paths: Vec<PathBuf>| {
+            paths
+                .into_iter()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect()
+        };
+        ValveIniSaves {
+            app_id: self.app_id.clone(),
+            save_type: self.save_type,
+            layout: save_type.name,
+            player_name: self.player_name.clone(),
+            game_exe: self.game_exe.clone(),
+            save_dirs: strings(save_dirs),
+            achievement_files: strings(achievement_files),
+        }
+    }
+}
+
+/// Saves only map into prefixes outside Windows.
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    fn write_valve_ini(dir: &Path, save_type: u32, game_exe: &str) -> ValveIni {
+        let path = dir.join("valve.ini");
+        std::fs::write(
+            &path,
+            format!(
+                "[Settings]\nAppID=480\nSaveType={}\nPlayerName=Player\nGameEXE={}\n",
+                save_type, game_exe
+            ),
+        )
+        .unwrap();
+        ValveIni::read(&path).unwrap()
+    }
+
+    fn strings(paths: Vec<PathBuf>) -> Vec<String> {
+        paths
+            .into_iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn each_save_type_resolves_its_layout_in_a_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix_dir = dir.path().join("prefix");
+        let users = prefix_dir.join("drive_c").join("users");
+        let me = users.join("me");
+        std::fs::create_dir_all(&me).unwrap();
+        let prefixes = [WinePrefix::at(prefix_dir.clone()).unwrap()];
+        let game = dir.path().join("game");
+        std::fs::create_dir_all(&game).unwrap();
+
+        let game_dir = game.join("bin").join("Profile/Player/Stats");
+        let program_data = prefix_dir.join("drive_c").join("ProgramData");
+        let cases: [(u32, &str, Vec<PathBuf>, &str); 10] = [
+            (
+                0,
+                "VALVE (game dir)",
+                vec![game_dir.clone()],
+                "achievements.bin",
+            ),
+            (
+                1,
+                "VALVE (my documents)",
+                vec![
+                    me.join("Documents/VALVE/480/Player/Stats"),
+                    me.join("My Documents/VALVE/480/Player/Stats"),
+                ],
+                "achievements.bin",
+            ),
+            (
+                2,
+                "VALVE (game dir)",
+                vec![game_dir.clone()],
+                "achievements.bin",
+            ),
+            (
+                3,
+                "VALVE (game dir)",
+                vec![game_dir.clone()],
+                "achievements.bin",
+            ),
+            (
+                4,
+                "RELOADED",
+                vec![program_data.join("Steam/RLD!/480/stats")],
+                "achievements.ini",
+            ),
+            (
+                5,
+                "SKIDROW",
+                vec![
+                    me.join("Documents/SKIDROW/480/SteamEmu/UserStats"),
+                    me.join("My Documents/SKIDROW/480/SteamEmu/UserStats"),
+                ],
+                "achiev.ini",
+            ),
+            (
+                6,
+                "FLT",
+                vec![program_data.join("FLT/480/stats")],
+                "achievements.ini",
+            ),
+            (
+                7,
+                "CODEX (3.0.4+, my documents)",
+                vec![users.join("Public/Documents/Steam/CODEX/480")],
+                "achievements.ini",
+            ),
+            (
+                8,
+                "CODEX (1.0.0.0+, APPDATA)",
+                vec![
+                    me.join("AppData/Roaming/Steam/CODEX/480"),
+                    me.join("Application Data/Steam/CODEX/480"),
+                ],
+                "achievements.ini",
+            ),
+            (99, "VALVE (game dir)", vec![game_dir], "achievements.bin"),
+        ];
+
+        for (save_type, layout, save_dirs, file) in cases {
+            let saves = write_valve_ini(&game, save_type, r"bin\Game.exe").resolve(&prefixes);
+            assert_eq!(saves.save_type, save_type);
+            assert_eq!(saves.layout, layout, "SaveType={}", save_type);
+            let files: Vec<PathBuf> = save_dirs.iter().map(|dir| dir.join(file)).collect();
+            assert_eq!(
+                saves.save_dirs,
+                strings(save_dirs),
+                "SaveType={}",
+                save_type
+            );
+            assert_eq!(
+                saves.achievement_files,
+                strings(files),
+                "SaveType={}",
+                save_type
+            );
+        }
+    }
+
+    #[test]
+    fn game_exe_with_a_drive_is_found_in_the_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix_dir = dir.path().join("prefix");
+        let stats = prefix_dir.join("drive_c/Games/Spacewar/Profile/Player/Stats");
+        std::fs::create_dir_all(&stats).unwrap();
+        let prefixes = [WinePrefix::at(prefix_dir).unwrap()];
+        let ini = write_valve_ini(dir.path(), 0, r"C:\Games\Spacewar\Game.exe");
+
+        let saves = ini.resolve(&prefixes);
+        assert_eq!(saves.save_dirs, [stats.to_string_lossy()]);
+        assert_eq!(ini.game_dir(&[]), dir.path());
+    }
+}
@@ -0,0 +1,317 @@
+//! Which notify backend watches each tracked file. Native events (inotify,
+//! ReadDirectoryChangesW, FSEvents) never arrive for SMB shares, many FUSE mounts
+//! and some Wine setups, so in `auto` mode such paths are polled: up front when
+//! the mount is known not to deliver events, or once a file changes on disk
+//! without a native event.
+
+use crate::storage::Storage;
+use notify::{
+    Config as NotifyConfig, Event, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub(super) const WATCHER_SETTINGS: &str = "file_watcher";
+
+/// How a tracked path should be watched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchMode {
+    /// Native events, switching to polling when they are not delivered.
+    #[default]
+    Auto,
+    Native,
+    Poll,
+}
+
+/// The notify watcher a path is currently on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchBackend {
+    Native,
+    Poll,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatcherSettings {
+    /// Per-path modes; paths not listed use [`WatchMode::Auto`].
+    pub modes: HashMap<String, WatchMode>,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for WatcherSettings {
+    fn default() -> Self {
+        Self {
+            modes: HashMap::new(),
+            poll_interval_secs: 2,
+        }
+    }
+}
+
+impl WatcherSettings {
+    pub fn mode_for(&self, path: &str) -> WatchMode {
+        self.modes.get(path).copied().unwrap_or_default()
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs.max(1))
+    }
+}
+
+/// Watch state of one tracked path, as reported by `get_watch_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchStatus {
+    pub path: String,
+    pub mode: WatchMode,
+    pub backend: WatchBackend,
+    /// Why the path is polled although its mode is `auto`.
+    pub fallback_reason: Option<String>,
+    /// Unix timestamp (seconds) of the last event for the path.
+    pub last_event: Option<i64>,
+}
+
+pub type StatusMap = Arc<Mutex<BTreeMap<String, WatchStatus>>>;
+
+/// Watcher settings and the status of the running watcher.
+pub struct FileWatchers {
+    settings: Mutex<WatcherSettings>,
+    pub(super) status: StatusMap,
+    generation: Arc<AtomicU64>,
+}
+
+impl FileWatchers {
+    pub fn load(storage: &Storage) -> Self {
+        Self {
+            settings: Mutex::new(storage.load_settings(WATCHER_SETTINGS)),
+            status: Arc::default(),
+            generation: Arc::default(),
+        }
+    }
+
+    pub fn settings(&self) -> WatcherSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: WatcherSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    /// Starts a new watcher generation. The thread of the previous one notices it
+    /// is stale and stops, dropping its watches.
+    pub(super) fn next_generation(&self) -> Generation {
+        let id = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.status.lock().unwrap().clear();
+        Generation {
+            id,
+            current: Arc::clone(&self.generation),
+        }
+    }
+}
+
+pub(super) struct Generation {
+    id: u64,
+    current: Arc<AtomicU64>,
+}
+
+impl Generation {
+    pub fn is_current(&self) -> bool {
+        self.current.load(Ordering::SeqCst) == self.id
+    }
+}
+
+/// File systems that do not deliver native change events.
+const POLLED_FS_TYPES: &[&str] = &[
+    "cifs", "smb3", "smbfs", "nfs", "nfs4", "9p", "sshfs", "davfs", "afs",
+];
+
+/// Why `path` has to be polled, when its location is known not to deliver
+/// native events.
+pub(super) fn needs_polling(path: &Path) -> Option<String> {
+    if cfg!(windows) {
+        let path = path.to_string_lossy();
+        let unc = path.starts_with(r"\\") && !path.starts_with(r"\\?\")
+            || path.to_ascii_uppercase().starts_with(r"\\?\UNC\");
+        return unc.then(|| "network share".to_string());
+    }
+    linux_mount_type(path)
+        .filter(|fs| fs.starts_with("fuse.") || POLLED_FS_TYPES.contains(&fs.as_str()))
+        .map(|fs| format!("{} mount", fs))
+}
+
+/// File system type of the mount `path` is on, from `/proc/self/mounts`.
+fn linux_mount_type(path: &Path) -> Option<String> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // Spaces in mount points are written as `\040`.
+            let mount_point = PathBuf::from(fields.next()?.replace("\\040", " "));
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.components().count(), fs_type.to_string()))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, fs_type)| fs_type)
+}
+
+/// The native and polling watchers of one watcher generation, both feeding the
+/// same channel. The polling watcher is only started when a path needs it.
+pub(super) struct Backends {
+    tx: Sender<notify::Result<Event>>,
+    config: NotifyConfig,
+    native: Option<RecommendedWatcher>,
+    poll: Option<PollWatcher>,
+    dirs: HashSet<(PathBuf, WatchBackend)>,
+}
+
+impl Backends {
+    pub fn new(tx: Sender<notify::Result<Event>>, poll_interval: Duration) -> Self {
+        let config = NotifyConfig::default()
+            .with_compare_contents(true)
+            .with_poll_interval(poll_interval);
+        let native = match RecommendedWatcher::new(tx.clone(), config) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                println!("Failed to create file watcher: {:?}", e);
+                None
+            }
+        };
+        Self {
+            tx,
+            config,
+            native,
+            poll: None,
+            dirs: HashSet::new(),
+        }
+    }
+
+    fn watch_with(&mut self, dir: &Path, backend: WatchBackend) -> Result<(), String> {
+        if self.dirs.contains(&(dir.to_path_buf(), backend)) {
+            return Ok(());
+        }
+        let result = match backend {
+            WatchBackend::Native => match self.native.as_mut() {
+                Some(watcher) => watcher.watch(dir, RecursiveMode::NonRecursive),
+                None => return Err("native watcher unavailable".to_string()),
+            },
+            WatchBackend::Poll => {
+                let watcher = match &mut self.poll {
+                    Some(watcher) => watcher,
+                    None => self.poll.insert(
+                        PollWatcher::new(self.tx.clone(), self.config)
+                            .map_err(|e| format!("Failed to create poll watcher: {:?}", e))?,
+                    ),
+                };
+                watcher.watch(dir, RecursiveMode::NonRecursive)
+            }
+        };
+        result.map_err(|e| format!("Failed to watch {}: {:?}", dir.display(), e))?;
+        self.dirs.insert((dir.to_path_buf(), backend));
+        Ok(())
+    }
+
+    /// Watches `dir` with `backend`. A failed native watch falls back to polling
+    /// unless `strict`; the backend used and the fallback reason are returned.
+    pub fn watch(
+        &mut self,
+        dir: &Path,
+        backend: WatchBackend,
+        strict: bool,
+    ) -> Result<(WatchBackend, Option<String>), String> {
+        match self.watch_with(dir, backend) {
+            Ok(()) => Ok((backend, None)),
+            Err(e) if backend == WatchBackend::Native && !strict => {
+                println!("{}, polling instead", e);
+                self.watch_with(dir, WatchBackend::Poll)?;
+                Ok((WatchBackend::Poll, Some(e)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn settings_load_per_path_modes_and_clamp_the_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::in_dir(dir.path());
+        assert_eq!(
+            FileWatchers::load(&storage).settings().poll_interval(),
+            Duration::from_secs(2)
+        );
+
+        let settings = WatcherSettings {
+            modes: HashMap::from([("/saves/a.ini".to_string(), WatchMode::Poll)]),
+            poll_interval_secs: 0,
+        };
+        storage.save_settings(WATCHER_SETTINGS, &settings).unwrap();
+        let settings = FileWatchers::load(&storage).settings();
+        assert_eq!(settings.mode_for("/saves/a.ini"), WatchMode::Poll);
+        assert_eq!(settings.mode_for("/saves/b.ini"), WatchMode::Auto);
+        assert_eq!(settings.poll_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn a_new_generation_stops_the_previous_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let watchers = FileWatchers::load(&Storage::in_dir(dir.path()));
+        let first = watchers.next_generation();
+        assert!(first.is_current());
+        watchers.status.lock().unwrap().insert(
+            "a.ini".to_string(),
+            WatchStatus {
+                path: "a.ini".to_string(),
+                mode: WatchMode::Auto,
+                backend: WatchBackend::Native,
+                fallback_reason: None,
+                last_event: None,
+            },
+        );
+
+        let second = watchers.next_generation();
+        assert!(!first.is_current());
+        assert!(second.is_current());
+        assert!(watchers.status.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn polling_reports_changes_in_a_watched_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, rx) = mpsc::channel();
+        let mut backends = Backends::new(tx, Duration::from_millis(100));
+
+        let missing = dir.path().join("missing");
+        assert!(backends
+            .watch(&missing, WatchBackend::Native, true)
+            .is_err());
+
+        for _ in 0..2 {
+            assert_eq!(
+                backends.watch(dir.path(), WatchBackend::Poll, false),
+                Ok((WatchBackend::Poll, None))
+            );
+        }
+        assert_eq!(backends.dirs.len(), 1);
+
+        let file = dir.path().join("achievements.ini");
+        std::fs::write(&file, "[ACH_A]\nAchieved=1\n").unwrap();
+        let event = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("no poll event")
+            .unwrap();
+        assert!(event.paths.iter().any(|p| p.ends_with("achievements.ini")));
+    }
+}
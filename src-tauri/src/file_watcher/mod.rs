@@ -0,0 +1,1256 @@
+//! Watches emulator save files and reports what changed in them.
+//!
+//! Each file's parent directory is watched rather than the file itself, so a save
+//! that is deleted, or replaced by an emulator writing a new copy, keeps being
+//! tracked. Paths are watched natively or polled, see [`backend`]. A change is
+//! only read when the file's size or modification time moved, and only the
+//! appended bytes when text was added to the end. Binary files are never
+//! converted to text; the save formats in [`decoders`] are decoded by file name.
+//! `file-change` carries the delta rather than the content.
+//! A save that loses unlocks emits `achievement-relocked` and one that is deleted
+//! or cut short emits `tracked-file-reset`. When the file's game is known,
+//! unlocks (with the emulator's own unlock time) and relocks are written to the
+//! library.
+
+mod backend;
+
+pub use backend::FileWatchers;
+
+use crate::db::AchievementUpdate;
+use crate::db::LibraryDb;
+use crate::emulators::unlocks::{self, FileUnlocks};
+use crate::emulators::EmulatorKind;
+use crate::emulators::{decoders, ini};
+use crate::storage::Storage;
+use backend::{Backends, WatchBackend, WatchMode, WatchStatus, WatcherSettings, WATCHER_SETTINGS};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel as std_channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Last seen state of a tracked file.
+#[derive(Debug, Default)]
+struct WatchedFile {
+    exists: bool,
+    /// Size and modification time when the file was last read.
+    stat: DiskStat,
+    /// Number of bytes last read.
+    len: u64,
+    /// Hash of the bytes last read, kept unfinalized so appended bytes can be
+    /// added without hashing the whole file again.
+    hasher: Sha256,
+    /// The content when it is text; binary files are only hashed.
+    text: Option<String>,
+    /// Parsed unlock state; `None` when the format is not recognised.
+    unlocks: Option<FileUnlocks>,
+    /// The game's achievement API names, for formats that store hashes of them.
+    names: Vec<String>,
+    app_id: Option<String>,
+    source: Option<EmulatorKind>,
+}
+
+type WatchedFiles = Arc<Mutex<HashMap<String, WatchedFile>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResetReason {
+    Deleted,
+    Truncated,
+}
+
+impl ResetReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            ResetReason::Deleted => "deleted",
+            ResetReason::Truncated => "truncated",
+        }
+    }
+}
+
+/// A save file's content: text, or bytes that are never converted to text.
+enum Contents {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Contents {
+    /// UTF-16 files (with a BOM) and UTF-8 without NUL bytes are text.
+    fn decode(bytes: Vec<u8>) -> Self {
+        if bytes.starts_with(&[0xFF, 0xFE]) {
+            return Contents::Text(ini::decode_text(&bytes));
+        }
+        if bytes.contains(&0) {
+            return Contents::Binary(bytes);
+        }
+        match String::from_utf8(bytes) {
+            Ok(text) => Contents::Text(text),
+            Err(e) => Contents::Binary(e.into_bytes()),
+        }
+    }
+
+    fn text(&self) -> Option<&str> {
+        match self {
+            Contents::Text(text) => Some(text),
+            Contents::Binary(_) => None,
+        }
+    }
+
+    /// Unlock state of the file at `path`, decoded by file name for the formats
+    /// [`decoders`] knows and parsed from the text otherwise.
+    fn unlocks(&self, path: &str, names: &[String]) -> Option<FileUnlocks> {
+        let path = Path::new(path);
+        if decoders::is_decoded(path) {
+            let bytes = match self {
+                Contents::Text(text) => text.as_bytes(),
+                Contents::Binary(bytes) => bytes,
+            };
+            return decoders::decode(path, bytes, names).map(|decoded| decoded.unlocks);
+        }
+        self.text().and_then(unlocks::parse_unlocks)
+    }
+}
+
+/// Result of checking a tracked file for changes.
+enum FileRead {
+    /// Same size, modification time or hash as the last read.
+    Unchanged(DiskStat),
+    Changed {
+        contents: Contents,
+        stat: DiskStat,
+        len: u64,
+        hasher: Sha256,
+    },
+}
+
+/// How much of the start and of the end of a file's old content is compared
+/// before only its appended bytes are read. Files up to twice this size are
+/// compared in full.
+const VERIFIED_WINDOW: u64 = 4096;
+
+/// Bytes appended to a file whose old content was `old`, or `None` when the
+/// file was rewritten rather than appended to.
+fn read_appended(path: &str, old: &str, new_len: u64) -> std::io::Result<Option<Vec<u8>>> {
+    let old = old.as_bytes();
+    let old_len = old.len() as u64;
+    if old_len == 0 || new_len <= old_len {
+        return Ok(None);
+    }
+    let mut handle = std::fs::File::open(path)?;
+    let head_len = old_len.min(VERIFIED_WINDOW);
+    let mut head = vec![0; head_len as usize];
+    handle.read_exact(&mut head)?;
+    let tail_start = old_len.saturating_sub(VERIFIED_WINDOW).max(head_len);
+    let mut tail = vec![0; (old_len - tail_start) as usize];
+    handle.seek(SeekFrom::Start(tail_start))?;
+    handle.read_exact(&mut tail)?;
+    if head != old[..head_len as usize] || tail != old[tail_start as usize..] {
+        return Ok(None);
+    }
+    let mut appended = Vec::new();
+    handle.read_to_end(&mut appended)?;
+    Ok(Some(appended))
+}
+
+/// Checks `path` against its last read. Nothing is read when its size and
+/// modification time are unchanged, only the new bytes when text was appended,
+/// and otherwise the whole file, which counts as unchanged if its hash is.
+fn read_changes(path: &str, file: &WatchedFile) -> std::io::Result<FileRead> {
+    let meta = std::fs::metadata(path)?;
+    let stat = Some((meta.len(), meta.modified().ok()));
+    if file.exists && stat == file.stat {
+        return Ok(FileRead::Unchanged(stat));
+    }
+
+    if let Some(old) = file.text.as_deref().filter(|_| file.exists) {
+        if let Ok(Some(appended)) = read_appended(path, old, meta.len()) {
+            if !appended.contains(&0) {
+                if let Ok(added) = std::str::from_utf8(&appended) {
+                    let mut hasher = file.hasher.clone();
+                    hasher.update(&appended);
+                    return Ok(FileRead::Changed {
+                        contents: Contents::Text(format!("{}{}", old, added)),
+                        stat,
+                        len: file.len + appended.len() as u64,
+                        hasher,
+                    });
+                }
+            }
+        }
+    }
+
+    let bytes = std::fs::read(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    if file.exists && hex_digest(&hasher) == hex_digest(&file.hasher) {
+        return Ok(FileRead::Unchanged(stat));
+    }
+    Ok(FileRead::Changed {
+        len: bytes.len() as u64,
+        contents: Contents::decode(bytes),
+        stat,
+        hasher,
+    })
+}
+
+fn hex_digest(hasher: &Sha256) -> String {
+    format!("{:x}", hasher.clone().finalize())
+}
+
+/// Achievement API names of `app_id` when decoding `path` needs them.
+fn achievement_names(app_handle: &AppHandle, path: &str, app_id: Option<&str>) -> Vec<String> {
+    let Some(app_id) = app_id.filter(|_| decoders::needs_names(Path::new(path))) else {
+        return Vec::new();
+    };
+    match app_handle.state::<LibraryDb>().list_achievements(app_id) {
+        Ok(achievements) => achievements.into_iter().map(|a| a.api_name).collect(),
+        Err(e) => {
+            println!("{}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Reads a newly tracked file.
+fn snapshot(app_handle: &AppHandle, path: &str, app_id: Option<String>) -> WatchedFile {
+    let mut file = WatchedFile {
+        exists: true,
+        text: Some(String::new()),
+        names: achievement_names(app_handle, path, app_id.as_deref()),
+        app_id,
+        ..Default::default()
+    };
+    let empty = WatchedFile::default();
+    match read_changes(path, &empty) {
+        Ok(FileRead::Changed {
+            contents,
+            stat,
+            len,
+            hasher,
+        }) => {
+            println!("Read {} bytes from {}", len, path);
+            file.unlocks = contents.unlocks(path, &file.names);
+            file.source =
+                unlocks::emulator_for(Path::new(path), contents.text().unwrap_or_default());
+            file.stat = stat;
+            file.len = len;
+            file.hasher = hasher;
+            file.text = match contents {
+                Contents::Text(text) => Some(text),
+                Contents::Binary(_) => None,
+            };
+        }
+        Ok(FileRead::Unchanged(_)) => {}
+        Err(e) => println!("Failed to read file {}: {:?}", path, e),
+    }
+    file
+}
+
+/// The tracked path an event path refers to. Windows paths compare case-insensitively.
+fn tracked_key(files: &HashMap<String, WatchedFile>, path: &Path) -> Option<String> {
+    let path = path.to_string_lossy();
+    if files.contains_key(path.as_ref()) {
+        return Some(path.into_owned());
+    }
+    files
+        .keys()
+        .find(|key| {
+            if cfg!(windows) {
+                key.eq_ignore_ascii_case(&path)
+            } else {
+                Path::new(key) == Path::new(path.as_ref())
+            }
+        })
+        .cloned()
+}
+
+/// How often native-watched `auto` paths are checked for changes on disk that
+/// produced no event.
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Size and modification time of a file.
+type DiskStat = Option<(u64, Option<SystemTime>)>;
+
+fn disk_stat(path: &str) -> DiskStat {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()))
+}
+
+/// A native-watched `auto` path, checked for changes the native watcher missed.
+struct StallProbe {
+    stat: DiskStat,
+    event_seen: bool,
+    /// The file changed without an event during the previous check.
+    unexplained: bool,
+}
+
+/// Paths whose file changed on disk and got no native event by the following
+/// check. They are removed from `probes`.
+fn stalled_paths(probes: &mut HashMap<String, StallProbe>) -> Vec<String> {
+    let mut stalled = Vec::new();
+    for (path, probe) in probes.iter_mut() {
+        let stat = disk_stat(path);
+        if stat == probe.stat || probe.event_seen {
+            probe.stat = stat;
+            probe.event_seen = false;
+            probe.unexplained = false;
+        } else if probe.unexplained {
+            stalled.push(path.clone());
+        } else {
+            probe.unexplained = true;
+        }
+    }
+    for path in &stalled {
+        probes.remove(path);
+    }
+    stalled
+}
+
+/// The directory watched for `path`.
+fn watch_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(dir) if path.is_file() && !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Processes each path once its events settle, a second after the last one.
+#[derive(Clone)]
+struct Debouncer {
+    app_handle: AppHandle,
+    files: WatchedFiles,
+    last_event_time: Arc<Mutex<HashMap<String, Instant>>>,
+    active_tasks: Arc<Mutex<HashMap<String, bool>>>,
+}
+
+impl Debouncer {
+    const DELAY: Duration = Duration::from_millis(1000);
+
+    fn schedule(&self, path: String) {
+        {
+            let mut tasks = self.active_tasks.lock().unwrap();
+            if tasks.get(&path).unwrap_or(&false) == &true {
+                println!("Skipping duplicate event for path: {}", path);
+                return;
+            }
+            tasks.insert(path.clone(), true);
+        }
+        {
+            let mut times = self.last_event_time.lock().unwrap();
+            times.insert(path.clone(), Instant::now());
+        }
+        let debouncer = self.clone();
+        thread::spawn(move || {
+            thread::sleep(Self::DELAY);
+            let should_process = {
+                let times = debouncer.last_event_time.lock().unwrap();
+                times
+                    .get(&path)
+                    .map(|&last_time| last_time.elapsed() >= Self::DELAY)
+                    .unwrap_or(false)
+            };
+
+            if should_process {
+                println!("Processing file change for: {}", path);
+                process_change(&debouncer.app_handle, &debouncer.files, &path);
+            } else {
+                println!("Skipping processing (not latest event) for: {}", path);
+            }
+            {
+                let mut tasks = debouncer.active_tasks.lock().unwrap();
+                tasks.insert(path, false);
+            }
+        });
+    }
+}
+
+/// Watches `paths` until the next call replaces them. `app_ids` maps a path to
+/// its game so unlocks can be stored; each path is watched natively or polled
+/// according to its [`WatchMode`].
+#[tauri::command]
+pub fn track_files(
+    app_handle: AppHandle,
+    watchers: State<'_, FileWatchers>,
+    paths: Vec<String>,
+    app_ids: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    let valid_paths: Vec<String> = paths
+        .into_iter()
+        .filter(|path| {
+            let path_obj = Path::new(path);
+            if path_obj.exists() {
+                true
+            } else {
+                println!("Warning: Path does not exist, skipping: {}", path);
+                false
+            }
+        })
+        .collect();
+
+    if valid_paths.is_empty() {
+        return Err("No valid paths provided for tracking".to_string());
+    }
+    let app_ids = app_ids.unwrap_or_default();
+    let settings = watchers.settings();
+    let generation = watchers.next_generation();
+    let status = Arc::clone(&watchers.status);
+    thread::spawn(move || {
+        let (tx, rx) = std_channel();
+        let mut backends = Backends::new(tx, settings.poll_interval());
+        let files: WatchedFiles = Arc::new(Mutex::new(HashMap::new()));
+        let debouncer = Debouncer {
+            app_handle: app_handle.clone(),
+            files: Arc::clone(&files),
+            last_event_time: Arc::default(),
+            active_tasks: Arc::default(),
+        };
+        let mut probes = HashMap::<String, StallProbe>::new();
+
+        for p in valid_paths.iter() {
+            let path = Path::new(p);
+            let dir = watch_dir(path);
+            let mode = settings.mode_for(p);
+            let (wanted, reason) = match mode {
+                WatchMode::Native => (WatchBackend::Native, None),
+                WatchMode::Poll => (WatchBackend::Poll, None),
+                WatchMode::Auto => match backend::needs_polling(&dir) {
+                    Some(reason) => (WatchBackend::Poll, Some(reason)),
+                    None => (WatchBackend::Native, None),
+                },
+            };
+            let (backend, fallback) = match backends.watch(&dir, wanted, mode == WatchMode::Native)
+            {
+                Ok(watched) => watched,
+                Err(e) => {
+                    println!("Failed to watch path {}: {}", p, e);
+                    continue;
+                }
+            };
+            println!("Watching ({:?}): {}", backend, p);
+            status.lock().unwrap().insert(
+                p.clone(),
+                WatchStatus {
+                    path: p.clone(),
+                    mode,
+                    backend,
+                    fallback_reason: reason.or(fallback),
+                    last_event: None,
+                },
+            );
+            if mode == WatchMode::Auto && backend == WatchBackend::Native {
+                let probe = StallProbe {
+                    stat: disk_stat(p),
+                    event_seen: false,
+                    unexplained: false,
+                };
+                probes.insert(p.clone(), probe);
+            }
+
+            let file = snapshot(&app_handle, p, app_ids.get(p).cloned());
+            files.lock().unwrap().insert(p.clone(), file);
+        }
+        backfill(&app_handle, &files);
+
+        let mut last_check = Instant::now();
+        while generation.is_current() {
+            match rx.recv_timeout(STALL_CHECK_INTERVAL) {
+                Ok(Ok(event)) => {
+                    let tracked: BTreeSet<String> = {
+                        let files = files.lock().unwrap();
+                        event
+                            .paths
+                            .iter()
+                            .filter_map(|p| tracked_key(&files, p))
+                            .collect()
+                    };
+                    let now = chrono::Utc::now().timestamp();
+                    for path in tracked {
+                        println!(
+                            "🔥 FILE EVENT DETECTED: {:?} for path: {}",
+                            event.kind, path
+                        );
+                        if let Some(probe) = probes.get_mut(&path) {
+                            probe.event_seen = true;
+                        }
+                        if let Some(entry) = status.lock().unwrap().get_mut(&path) {
+                            entry.last_event = Some(now);
+                        }
+                        debouncer.schedule(path);
+                    }
+                }
+                Ok(Err(e)) => {
+                    println!("Watcher error: {:?}", e);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    println!("Watcher channel disconnected");
+                    break;
+                }
+            }
+
+            if last_check.elapsed() < STALL_CHECK_INTERVAL || !generation.is_current() {
+                continue;
+            }
+            last_check = Instant::now();
+            for path in stalled_paths(&mut probes) {
+                println!("No native events for changes to {}, polling instead", path);
+                if let Err(e) =
+                    backends.watch(&watch_dir(Path::new(&path)), WatchBackend::Poll, true)
+                {
+                    println!("{}", e);
+                    continue;
+                }
+                if let Some(entry) = status.lock().unwrap().get_mut(&path) {
+                    entry.backend = WatchBackend::Poll;
+                    entry.fallback_reason =
+                        Some("no native events for changes on disk".to_string());
+                }
+                debouncer.schedule(path);
+            }
+        }
+        println!("File watcher replaced, stopping");
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_watch_status(watchers: State<'_, FileWatchers>) -> Vec<WatchStatus> {
+    watchers.status.lock().unwrap().values().cloned().collect()
+}
+
+#[tauri::command]
+pub fn get_watcher_settings(watchers: State<'_, FileWatchers>) -> WatcherSettings {
+    watchers.settings()
+}
+
+/// Saves the watcher settings. They apply the next time `track_files` starts.
+#[tauri::command]
+pub fn set_watcher_settings(
+    watchers: State<'_, FileWatchers>,
+    storage: State<'_, Storage>,
+    settings: WatcherSettings,
+) -> Result<(), String> {
+    storage.save_settings(WATCHER_SETTINGS, &settings)?;
+    watchers.set_settings(settings);
+    Ok(())
+}
+
+/// Stores unlocks for `app_id` with the emulator's own unlock times; the library
+/// uses the detection time for formats that record none. Returns the unlocks
+/// that changed, as `{api_name, unlock_time}` with the time in Unix seconds.
+fn store_unlocks(
+    db: &LibraryDb,
+    app_id: &str,
+    source: Option<EmulatorKind>,
+    unlocked: Vec<(String, Option<i64>)>,
+) -> Vec<serde_json::Value> {
+    let updates: Vec<AchievementUpdate> = unlocked
+        .into_iter()
+        .map(|(api_name, unlock_time)| AchievementUpdate {
+            api_name,
+            unlocked: Some(true),
+            unlock_time,
+            source: source.map(|kind| kind.label().to_string()),
+            ..Default::default()
+        })
+        .collect();
+    match db.upsert_unlocks(app_id, &updates) {
+        Ok(changed) => changed
+            .iter()
+            .filter(|(_, state)| state.unlocked)
+            .map(|(name, state)| json!({ "api_name": name, "unlock_time": state.unlock_time }))
+            .collect(),
+        Err(e) => {
+            println!("{}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// The watched files of one game and what they record as unlocked.
+#[derive(Debug, Default)]
+struct GameFiles {
+    paths: Vec<String>,
+    source: Option<EmulatorKind>,
+    /// API name -> earliest unlock time any of the files records.
+    unlocked: BTreeMap<String, Option<i64>>,
+}
+
+/// Stores unlocks the newly watched files record but the library does not, i.e.
+/// ones that happened while UnlockIt was closed, keeping the emulator's unlock
+/// times. All of them are reported in one `achievements-backfilled` event, which
+/// is not meant to raise notifications.
+fn backfill(app_handle: &AppHandle, files: &WatchedFiles) {
+    let backfilled = backfill_unlocks(&app_handle.state::<LibraryDb>(), files);
+    if backfilled.is_empty() {
+        return;
+    }
+    if let Err(e) = app_handle.emit("achievements-backfilled", json!({ "games": backfilled })) {
+        println!("Failed to emit achievements-backfilled: {:?}", e);
+    }
+}
+
+/// Stores the offline unlocks of every game with watched files and returns one
+/// `{app_id, paths, achievements}` entry per game that gained any.
+fn backfill_unlocks(db: &LibraryDb, files: &WatchedFiles) -> Vec<serde_json::Value> {
+    let mut games: BTreeMap<String, GameFiles> = BTreeMap::new();
+    {
+        let files = files.lock().unwrap();
+        for (path, file) in files.iter() {
+            let (Some(app_id), Some(file_unlocks)) = (&file.app_id, &file.unlocks) else {
+                continue;
+            };
+            let game = games.entry(app_id.clone()).or_default();
+            game.paths.push(path.clone());
+            game.source = game.source.or(file.source);
+            for (name, unlock) in file_unlocks.iter().filter(|(_, u)| u.unlocked) {
+                let time = game
+                    .unlocked
+                    .entry(name.clone())
+                    .or_insert(unlock.unlock_time);
+                *time = match (*time, unlock.unlock_time) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+    }
+
+    let mut backfilled = Vec::new();
+    for (app_id, game) in games {
+        let stored = match db.achievement_state(&app_id) {
+            Ok(state) => state.achievements,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let offline: Vec<(String, Option<i64>)> = game
+            .unlocked
+            .into_iter()
+            .filter(|(name, _)| !stored.get(name).is_some_and(|s| s.unlocked))
+            .collect();
+        if offline.is_empty() {
+            continue;
+        }
+        let achievements = store_unlocks(db, &app_id, game.source, offline);
+        println!(
+            "Backfilled {} offline unlocks for app {}",
+            achievements.len(),
+            app_id
+        );
+        backfilled.push(json!({
+            "app_id": app_id,
+            "paths": game.paths,
+            "achievements": achievements
+        }));
+    }
+    backfilled
+}
+
+/// Whether a changed file kept only the start of its old content, as when an
+/// emulator resets its save. Binary files only count when they are emptied.
+fn is_truncated(file: &WatchedFile, contents: &Contents, len: u64) -> bool {
+    file.exists
+        && file.len > 0
+        && match (file.text.as_deref(), contents.text()) {
+            (Some(old), Some(new)) => new.len() < old.len() && old.starts_with(new),
+            _ => len == 0,
+        }
+}
+
+/// Checks `path` for changes and emits the events for what changed since the
+/// last read. `file-change` carries only the delta: changed lines for text files
+/// and the parsed unlocks, plus the new size and hash.
+fn process_change(app_handle: &AppHandle, files: &WatchedFiles, path: &str) {
+    let mut guard = files.lock().unwrap();
+    let Some(file) = guard.get_mut(path) else {
+        return;
+    };
+
+    let (contents, stat, len, hasher) = match read_changes(path, file) {
+        Ok(FileRead::Changed {
+            contents,
+            stat,
+            len,
+            hasher,
+        }) => (contents, stat, len, hasher),
+        Ok(FileRead::Unchanged(stat)) => {
+            file.stat = stat;
+            println!("Content unchanged for: {}", path);
+            return;
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if !file.exists {
+                return;
+            }
+            let relocked = file
+                .unlocks
+                .as_ref()
+                .map(|u| unlocks::unlocked_names(u).cloned().collect())
+                .unwrap_or_default();
+            file.exists = false;
+            file.stat = None;
+            file.len = 0;
+            file.hasher = Sha256::new();
+            file.text = Some(String::new());
+            file.unlocks = Some(FileUnlocks::new());
+            let app_id = file.app_id.clone();
+            drop(guard);
+            report_reset(
+                app_handle,
+                files,
+                path,
+                app_id,
+                ResetReason::Deleted,
+                relocked,
+            );
+            return;
+        }
+        Err(e) => {
+            println!("Failed to read file {}: {:?}", path, e);
+            return;
+        }
+    };
+    println!("Old size: {}, new size: {}", file.len, len);
+
+    let truncated = is_truncated(file, &contents, len);
+    let old_text = file.text.take();
+    let new_text = contents.text();
+    let new_unlocks = contents.unlocks(path, &file.names);
+    let relocked = match (&file.unlocks, &new_unlocks) {
+        (Some(before), Some(after)) => unlocks::relocked(before, after),
+        (Some(before), None) if truncated => unlocks::unlocked_names(before).cloned().collect(),
+        _ => Vec::new(),
+    };
+    let newly_unlocked = new_unlocks
+        .as_ref()
+        .map(|after| unlocks::newly_unlocked(file.unlocks.as_ref(), after))
+        .unwrap_or_default();
+    let (added_lines, removed_lines) = match new_text {
+        Some(new) => {
+            let old = old_text.as_deref().unwrap_or_default();
+            (get_added_lines(old, new), get_removed_lines(old, new))
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+    file.exists = true;
+    file.source = file
+        .source
+        .or_else(|| unlocks::emulator_for(Path::new(path), new_text.unwrap_or_default()));
+    file.stat = stat;
+    file.len = len;
+    file.unlocks = new_unlocks;
+    let hash = hex_digest(&hasher);
+    file.hasher = hasher;
+    let binary = matches!(contents, Contents::Binary(_));
+    file.text = match contents {
+        Contents::Text(text) => Some(text),
+        Contents::Binary(_) => None,
+    };
+    let app_id = file.app_id.clone();
+    let source = file.source;
+    drop(guard);
+
+    let unlocked: Vec<serde_json::Value> = match &app_id {
+        Some(app_id) if !newly_unlocked.is_empty() => store_unlocks(
+            &app_handle.state::<LibraryDb>(),
+            app_id,
+            source,
+            newly_unlocked,
+        ),
+        _ => newly_unlocked
+            .iter()
+            .map(|(name, time)| json!({ "api_name": name, "unlock_time": time }))
+            .collect(),
+    };
+
+    if truncated {
+        report_reset(
+            app_handle,
+            files,
+            path,
+            app_id,
+            ResetReason::Truncated,
+            relocked,
+        );
+    } else if !relocked.is_empty() {
+        report_relocked(app_handle, files, path, app_id, relocked);
+    }
+
+    if len == 0 {
+        println!("Empty content read for: {}", path);
+        return;
+    }
+    println!(
+        "File changed: {}, added {} lines, removed {} lines",
+        path,
+        added_lines.len(),
+        removed_lines.len()
+    );
+    println!("Added lines: {:?}", added_lines);
+
+    let kind = "FileModified";
+    let payload = json!({
+        "path": path,
+        "kind": kind,
+        "added_lines": added_lines,
+        "removed_lines": removed_lines,
+        "unlocked": unlocked,
+        "size": len,
+        "hash": hash,
+        "binary": binary
+    });
+
+    println!("Emitting file-change event...");
+    if let Err(e) = app_handle.emit("file-change", payload) {
+        println!("Failed to emit file-change: {:?}", e);
+    } else {
+        println!("Successfully emitted file-change event!");
+    }
+}
+
+/// Drops achievements another tracked file of the same game still has unlocked,
+/// then relocks the rest in the library. Returns the achievements relocked.
+fn reconcile_relocks(
+    db: &LibraryDb,
+    files: &WatchedFiles,
+    path: &str,
+    app_id: Option<&str>,
+    relocked: Vec<String>,
+) -> Vec<String> {
+    let Some(app_id) = app_id else {
+        return relocked;
+    };
+    let still_unlocked: HashSet<String> = {
+        let files = files.lock().unwrap();
+        files
+            .iter()
+            .filter(|(key, file)| key.as_str() != path && file.app_id.as_deref() == Some(app_id))
+            .filter_map(|(_, file)| file.unlocks.as_ref())
+            .flat_map(|u| unlocks::unlocked_names(u).cloned())
+            .collect()
+    };
+    let relocked: Vec<String> = relocked
+        .into_iter()
+        .filter(|name| !still_unlocked.contains(name))
+        .collect();
+    if relocked.is_empty() {
+        return relocked;
+    }
+
+    let updates: Vec<AchievementUpdate> = relocked
+        .iter()
+        .map(|name| AchievementUpdate {
+            api_name: name.clone(),
+            unlocked: Some(false),
+            ..Default::default()
+        })
+        .collect();
+    match db.upsert_unlocks(app_id, &updates) {
+        Ok(changed) => println!(
+            "Relocked {} achievements for app {} from {}",
+            changed.len(),
+            app_id,
+            path
+        ),
+        Err(e) => println!("{}", e),
+    }
+    relocked
+}
+
+fn report_relocked(
+    app_handle: &AppHandle,
+    files: &WatchedFiles,
+    path: &str,
+    app_id: Option<String>,
+    relocked: Vec<String>,
+) {
+    let relocked = reconcile_relocks(
+        &app_handle.state::<LibraryDb>(),
+        files,
+        path,
+        app_id.as_deref(),
+        relocked,
+    );
+    if relocked.is_empty() {
+        return;
+    }
+    println!("Achievements relocked in {}: {:?}", path, relocked);
+    let payload = json!({
+        "path": path,
+        "app_id": app_id,
+        "achievements": relocked
+    });
+    if let Err(e) = app_handle.emit("achievement-relocked", payload) {
+        println!("Failed to emit achievement-relocked: {:?}", e);
+    }
+}
+
+fn report_reset(
+    app_handle: &AppHandle,
+    files: &WatchedFiles,
+    path: &str,
+    app_id: Option<String>,
+    reason: ResetReason,
+    relocked: Vec<String>,
+) {
+    let relocked = reconcile_relocks(
+        &app_handle.state::<LibraryDb>(),
+        files,
+        path,
+        app_id.as_deref(),
+        relocked,
+    );
+    println!("Tracked file {}: {}", reason.as_str(), path);
+    let payload = json!({
+        "path": path,
+        "app_id": app_id,
+        "reason": reason.as_str(),
+        "relocked": relocked
+    });
+    if let Err(e) = app_handle.emit("tracked-file-reset", payload) {
+        println!("Failed to emit tracked-file-reset: {:?}", e);
+    }
+}
+
+fn get_added_lines(old_content: &str, new_content: &str) -> Vec<String> {
+    let old_lines: Vec<&str> = old_content.lines().collect();
+    let new_lines: Vec<&str> = new_content.lines().collect();
+
+    let mut added_lines = Vec::new();
+    for (line_num, new_line) in new_lines.iter().enumerate() {
+        let line_number = line_num + 1;
+        let changed = old_lines
+            .get(line_num)
+            .is_none_or(|old_line| old_line != new_line && !old_lines.contains(new_line));
+        if changed {
+            added_lines.push(format!("Line {}: {}", line_number, new_line));
+        }
+    }
+    added_lines
+}
+
+/// Lines of `old_content` that no longer appear anywhere in `new_content`,
+/// numbered as in the old file.
+fn get_removed_lines(old_content: &str, new_content: &str) -> Vec<String> {
+    let new_lines: HashSet<&str> = new_content.lines().collect();
+    old_content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !new_lines.contains(line))
+        .map(|(line_num, line)| format!("Line {}: {}", line_num + 1, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulators::unlocks::FileUnlock;
+    use std::fs;
+
+    fn path_str(path: &Path) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    /// What `snapshot` records for a newly tracked file.
+    fn tracked(path: &str, app_id: Option<&str>) -> WatchedFile {
+        let mut file = WatchedFile {
+            exists: true,
+            app_id: app_id.map(str::to_string),
+            ..Default::default()
+        };
+        if let FileRead::Changed {
+            contents,
+            stat,
+            len,
+            hasher,
+        } = read_changes(path, &WatchedFile::default()).unwrap()
+        {
+            file.unlocks = contents.unlocks(path, &[]);
+            file.stat = stat;
+            file.len = len;
+            file.hasher = hasher;
+            file.text = contents.text().map(str::to_string);
+        }
+        file
+    }
+
+    fn unlocks(entries: &[(&str, bool, Option<i64>)]) -> Option<FileUnlocks> {
+        Some(
+            entries
+                .iter()
+                .map(|(name, unlocked, unlock_time)| {
+                    let unlock = FileUnlock {
+                        unlocked: *unlocked,
+                        unlock_time: *unlock_time,
+                    };
+                    (name.to_string(), unlock)
+                })
+                .collect(),
+        )
+    }
+
+    fn game_file(app_id: &str, file_unlocks: Option<FileUnlocks>) -> WatchedFile {
+        WatchedFile {
+            exists: true,
+            app_id: Some(app_id.to_string()),
+            unlocks: file_unlocks,
+            ..Default::default()
+        }
+    }
+
+    fn unlock_in(db: &LibraryDb, app_id: &str, names: &[&str]) {
+        let updates: Vec<AchievementUpdate> = names
+            .iter()
+            .map(|name| AchievementUpdate {
+                api_name: name.to_string(),
+                unlocked: Some(true),
+                unlock_time: Some(50),
+                ..Default::default()
+            })
+            .collect();
+        db.upsert_unlocks(app_id, &updates).unwrap();
+    }
+
+    #[test]
+    fn added_lines_are_reported_once() {
+        assert_eq!(
+            get_added_lines("a\nb", "a\nb\nc\nd"),
+            ["Line 3: c", "Line 4: d"]
+        );
+        assert_eq!(get_added_lines("a\nb", "a\nx"), ["Line 2: x"]);
+        assert_eq!(get_added_lines("", "a"), ["Line 1: a"]);
+        // A line that only moved is not new.
+        assert!(get_added_lines("a\nb", "b\na").is_empty());
+    }
+
+    #[test]
+    fn removed_lines_are_numbered_as_in_the_old_file() {
+        assert_eq!(
+            get_removed_lines("[A]\nAchieved=1\n[B]\nAchieved=1", "[A]\nAchieved=1"),
+            ["Line 3: [B]"]
+        );
+        assert_eq!(get_removed_lines("a\nb", ""), ["Line 1: a", "Line 2: b"]);
+        assert!(get_removed_lines("a\nb", "b\na\nc").is_empty());
+    }
+
+    #[test]
+    fn truncation_keeps_only_the_start_of_the_old_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_str(&dir.path().join("achievements.ini"));
+        fs::write(&path, "[ACH_A]\nAchieved=1\n[ACH_B]\nAchieved=1\n").unwrap();
+        let file = tracked(&path, None);
+
+        let text = |s: &str| Contents::Text(s.to_string());
+        assert!(is_truncated(&file, &text("[ACH_A]\nAchieved=1\n"), 19));
+        assert!(is_truncated(&file, &text(""), 0));
+        assert!(!is_truncated(&file, &text("[ACH_A]\nAchieved=0\n"), 19));
+        assert!(!is_truncated(
+            &file,
+            &text("[ACH_A]\nAchieved=1\n[ACH_B]\nAchieved=1\n[ACH_C]\n"),
+            47
+        ));
+        assert!(is_truncated(&file, &Contents::Binary(Vec::new()), 0));
+        assert!(!is_truncated(&file, &Contents::Binary(vec![0, 1]), 2));
+
+        let deleted = WatchedFile {
+            exists: false,
+            ..tracked(&path, None)
+        };
+        assert!(!is_truncated(&deleted, &text(""), 0));
+    }
+
+    #[test]
+    fn relocks_still_unlocked_in_another_file_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = LibraryDb::open(&dir.path().join("library.db")).unwrap();
+        unlock_in(&db, "480", &["ACH_A", "ACH_B"]);
+        let files: WatchedFiles = Arc::new(Mutex::new(HashMap::from([
+            ("a.ini".to_string(), game_file("480", unlocks(&[]))),
+            (
+                "b.ini".to_string(),
+                game_file(
+                    "480",
+                    unlocks(&[("ACH_B", true, None), ("ACH_A", false, None)]),
+                ),
+            ),
+            (
+                "other.ini".to_string(),
+                game_file("220", unlocks(&[("ACH_A", true, None)])),
+            ),
+        ])));
+        let relocked = vec!["ACH_A".to_string(), "ACH_B".to_string()];
+
+        assert_eq!(
+            reconcile_relocks(&db, &files, "a.ini", Some("480"), relocked.clone()),
+            ["ACH_A"]
+        );
+        let state = db.achievement_state("480").unwrap().achievements;
+        assert!(!state["ACH_A"].unlocked);
+        assert!(state["ACH_B"].unlocked);
+
+        // Without a game nothing is stored, and every relock is reported.
+        assert_eq!(
+            reconcile_relocks(&db, &files, "a.ini", None, relocked.clone()),
+            relocked
+        );
+        assert!(db.achievement_state("220").unwrap().achievements.is_empty());
+    }
+
+    #[test]
+    fn backfill_stores_offline_unlocks_once_with_the_earliest_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = LibraryDb::open(&dir.path().join("library.db")).unwrap();
+        unlock_in(&db, "480", &["ACH_A"]);
+        let files: WatchedFiles = Arc::new(Mutex::new(HashMap::from([
+            (
+                "a.ini".to_string(),
+                game_file(
+                    "480",
+                    unlocks(&[("ACH_A", true, Some(10)), ("ACH_B", true, Some(300))]),
+                ),
+            ),
+            (
+                "b.ini".to_string(),
+                game_file(
+                    "480",
+                    unlocks(&[("ACH_B", true, Some(200)), ("ACH_C", false, Some(5))]),
+                ),
+            ),
+            ("c.ini".to_string(), game_file("220", None)),
+        ])));
+
+        let backfilled = backfill_unlocks(&db, &files);
+        assert_eq!(backfilled.len(), 1);
+        assert_eq!(backfilled[0]["app_id"], "480");
+        assert_eq!(
+            backfilled[0]["achievements"],
+            json!([{ "api_name": "ACH_B", "unlock_time": 200 }])
+        );
+
+        let state = db.achievement_state("480").unwrap().achievements;
+        assert_eq!(state["ACH_A"].unlock_time, Some(50));
+        assert_eq!(state["ACH_B"].unlock_time, Some(200));
+        assert!(!state.get("ACH_C").is_some_and(|s| s.unlocked));
+
+        assert!(backfill_unlocks(&db, &files).is_empty());
+    }
+
+    fn probe(path: &str) -> StallProbe {
+        StallProbe {
+            stat: disk_stat(path),
+            event_seen: false,
+            unexplained: false,
+        }
+    }
+
+    #[test]
+    fn a_change_without_an_event_stalls_by_the_next_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let quiet = path_str(&dir.path().join("quiet.ini"));
+        let evented = path_str(&dir.path().join("evented.ini"));
+        let silent = path_str(&dir.path().join("silent.ini"));
+        for path in [&quiet, &evented, &silent] {
+            fs::write(path, "[ACH_A]\n").unwrap();
+        }
+        let mut probes: HashMap<String, StallProbe> = [&quiet, &evented, &silent]
+            .into_iter()
+            .map(|path| (path.clone(), probe(path)))
+            .collect();
+
+        fs::write(&evented, "[ACH_A]\nAchieved=1\n").unwrap();
+        fs::write(&silent, "[ACH_A]\nAchieved=1\n").unwrap();
+        probes.get_mut(&evented).unwrap().event_seen = true;
+        assert!(stalled_paths(&mut probes).is_empty());
+        assert!(probes[&silent].unexplained);
+        assert!(!probes[&evented].unexplained);
+        assert!(!probes[&quiet].unexplained);
+
+        assert_eq!(stalled_paths(&mut probes), [silent.as_str()]);
+        assert!(!probes.contains_key(&silent));
+        assert_eq!(probes.len(), 2);
+        assert!(stalled_paths(&mut probes).is_empty());
+    }
+
+    #[test]
+    fn read_appended_checks_the_old_head_and_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_str(&dir.path().join("achievements.ini"));
+        let old = "[ACH_A]\nAchieved=1\n";
+        let read = |contents: &str, old: &str| {
+            fs::write(&path, contents).unwrap();
+            read_appended(&path, old, contents.len() as u64).unwrap()
+        };
+
+        let appended = read("[ACH_A]\nAchieved=1\n[ACH_B]\n", old);
+        assert_eq!(appended.as_deref(), Some(&b"[ACH_B]\n"[..]));
+        assert_eq!(read("[ACH_B]\nAchieved=1\n[ACH_A]\n", old), None);
+        assert_eq!(read("[ACH_A]\n", old), None);
+        assert_eq!(read(old, old), None);
+        assert_eq!(read("[ACH_A]\n", ""), None);
+
+        // Past two windows only the start and end of the old content are compared.
+        let big = "x".repeat(3 * VERIFIED_WINDOW as usize);
+        let mut middle = big.clone();
+        let at = VERIFIED_WINDOW as usize + 1;
+        middle.replace_range(at..at + 1, "y");
+        assert_eq!(
+            read(&format!("{}z", middle), &big).as_deref(),
+            Some(&b"z"[..])
+        );
+        let tail = format!("{}yz", &big[..big.len() - 1]);
+        assert_eq!(read(&tail, &big), None);
+    }
+
+    #[test]
+    fn read_changes_reads_only_appended_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_str(&dir.path().join("achievements.ini"));
+        fs::write(&path, "[ACH_A]\nAchieved=1\n").unwrap();
+        let file = tracked(&path, None);
+        assert!(matches!(
+            read_changes(&path, &file).unwrap(),
+            FileRead::Unchanged(_)
+        ));
+
+        let full = "[ACH_A]\nAchieved=1\n[ACH_B]\nAchieved=1\n";
+        fs::write(&path, full).unwrap();
+        let FileRead::Changed {
+            contents,
+            len,
+            hasher,
+            ..
+        } = read_changes(&path, &file).unwrap()
+        else {
+            panic!("appended text not read");
+        };
+        assert_eq!(contents.text(), Some(full));
+        assert_eq!(len, full.len() as u64);
+        assert_eq!(
+            hex_digest(&hasher),
+            hex_digest(&Sha256::new_with_prefix(full))
+        );
+
+        // A new modification time alone only costs a hash of the file.
+        let rewritten = WatchedFile {
+            stat: None,
+            ..tracked(&path, None)
+        };
+        assert!(matches!(
+            read_changes(&path, &rewritten).unwrap(),
+            FileRead::Unchanged(_)
+        ));
+
+        fs::write(&path, "[ACH_B]\nAchieved=1\n").unwrap();
+        let FileRead::Changed { contents, .. } = read_changes(&path, &rewritten).unwrap() else {
+            panic!("rewritten file not read");
+        };
+        assert_eq!(contents.text(), Some("[ACH_B]\nAchieved=1\n"));
+    }
+}
@@ -0,0 +1,454 @@
+use crate::storage::Storage;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::State;
+
+/// Processed toast images younger than this may still be on screen, so GC leaves them alone.
+const TOAST_TEMP_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Processed toast images are cheap to regenerate, so cache entries this old are collected.
+const TOAST_CACHE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CategoryUsage {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+impl CategoryUsage {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameUsage {
+    pub app_id: String,
+    pub name: Option<String>,
+    pub categories: BTreeMap<String, CategoryUsage>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageReport {
+    pub games: Vec<GameUsage>,
+    /// Usage that does not belong to a single game (shared icons, caches, temp files).
+    pub shared: BTreeMap<String, CategoryUsage>,
+    /// Files that no game in the library references and `gc_images` would delete.
+    pub orphaned: CategoryUsage,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GcResult {
+    pub dry_run: bool,
+    pub deleted: Vec<String>,
+    pub bytes_freed: u64,
+}
+
+/// A file found under one of the managed directories.
+struct ManagedFile {
+    path: PathBuf,
+    bytes: u64,
+    app_id: Option<String>,
+    category: &'static str,
+    orphaned: bool,
+}
+
+/// What the frontend stores say the library currently contains.
+struct Library {
+    games: HashMap<String, Option<String>>,
+    referenced: HashSet<PathBuf>,
+}
+
+#[tauri::command]
+pub fn storage_report(storage: State<'_, Storage>) -> Result<StorageReport, String> {
+    let library = load_library(&storage)?;
+    let files = scan_managed_files(&storage, &library);
+
+    let mut games: BTreeMap<String, GameUsage> = library
+        .games
+        .iter()
+        .map(|(app_id, name)| {
+            (
+                app_id.clone(),
+                GameUsage {
+                    app_id: app_id.clone(),
+                    name: name.clone(),
+                    categories: BTreeMap::new(),
+                    total_bytes: 0,
+                },
+            )
+        })
+        .collect();
+    let mut shared: BTreeMap<String, CategoryUsage> = BTreeMap::new();
+    let mut orphaned = CategoryUsage::default();
+    let mut total_bytes = 0;
+
+    for file in files {
+        total_bytes += file.bytes;
+        if file.orphaned {
+            orphaned.add(file.bytes);
+            continue;
+        }
+
+        match file.app_id.as_ref().and_then(|id| games.get_mut(id)) {
+            Some(game) => {
+                game.categories
+                    .entry(file.category.to_string())
+                    .or_default()
+                    .add(file.bytes);
+                game.total_bytes += file.bytes;
+            }
+            None => shared
+                .entry(file.category.to_string())
+                .or_default()
+                .add(file.bytes),
+        }
+    }
+
+    let mut games: Vec<GameUsage> = games.into_values().collect();
+    games.sort_by_key(|g| std::cmp::Reverse(g.total_bytes));
+
+    Ok(StorageReport {
+        games,
+        shared,
+        orphaned,
+        total_bytes,
+    })
+}
+
+#[tauri::command]
+pub fn gc_images(storage: State<'_, Storage>, dry_run: bool) -> Result<GcResult, String> {
+    let library = load_library(&storage)?;
+    let mut deleted = Vec::new();
+    let mut bytes_freed = 0;
+
+    for file in scan_managed_files(&storage, &library) {
+        if !file.orphaned {
+            continue;
+        }
+
+        if !dry_run {
+            if let Err(e) = fs::remove_file(&file.path) {
+                println!("Failed to delete {:?}: {} (not critical)", file.path, e);
+                continue;
+            }
+        }
+        bytes_freed += file.bytes;
+        deleted.push(file.path.to_string_lossy().to_string());
+    }
+
+    if !dry_run {
+        remove_empty_dirs(&storage.images_root());
+    }
+
+    println!(
+        "Image GC{}: {} files, {} bytes",
+        if dry_run { " (dry run)" } else { "" },
+        deleted.len(),
+        bytes_freed
+    );
+
+    Ok(GcResult {
+        dry_run,
+        deleted,
+        bytes_freed,
+    })
+}
+
+fn load_library(storage: &Storage) -> Result<Library, String> {
+    let mut library = Library {
+        games: HashMap::new(),
+        referenced: HashSet::new(),
+    };
+
+    if let Some(games_store) = read_json(&storage.app_data_dir().join("my-games.json"))? {
+        if let Some(entries) = games_store.as_object() {
+            for (key, game) in entries {
+                if let Some(app_id) = key.strip_prefix("game_") {
+                    let name = game.get("name").and_then(|n| n.as_str()).map(String::from);
+                    library.games.insert(app_id.to_string(), name);
+                }
+            }
+        }
+        collect_paths(&games_store, &mut library.referenced);
+    }
+
+    for store in ["achievements.json", "howlongtobeat.json"] {
+        if let Some(data) = read_json(&storage.app_data_dir().join(store))? {
+            collect_paths(&data, &mut library.referenced);
+        }
+    }
+
+    // Achievement schemas written by the frontend reference the cached icons.
+    let schema_dir = storage.app_local_data_dir().join("achievements");
+    if let Ok(entries) = fs::read_dir(&schema_dir) {
+        for entry in entries.flatten() {
+            if let Ok(Some(data)) = read_json(&entry.path()) {
+                collect_paths(&data, &mut library.referenced);
+            }
+        }
+    }
+
+    Ok(library)
+}
+
+fn read_json(path: &Path) -> Result<Option<serde_json::Value>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+}
+
+fn collect_paths(value: &serde_json::Value, out: &mut HashSet<PathBuf>) {
+    match value {
+        serde_json::Value::String(s) if !s.starts_with("http") && !s.starts_with("data:") => {
+            out.insert(PathBuf::from(s));
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|item| collect_paths(item, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|item| collect_paths(item, out)),
+        _ => {}
+    }
+}
+
+fn scan_managed_files(storage: &Storage, library: &Library) -> Vec<ManagedFile> {
+    let mut files = Vec::new();
+
+    // images/<appid>/{wallpaper_*, library_cover_*}
+    for (app_id, path, bytes) in per_game_files(&storage.images_root()) {
+        let name = file_name(&path);
+        let category = if name.starts_with("wallpaper_") {
+            "wallpapers"
+        } else if name.starts_with("library_cover_") {
+            "covers"
+        } else {
+            "images"
+        };
+        let orphaned =
+            !library.games.contains_key(&app_id) || !library.referenced.contains(&path);
+        files.push(ManagedFile {
+            path,
+            bytes,
+            app_id: Some(app_id),
+            category,
+            orphaned,
+        });
+    }
+
+    // Achievements/<appid>/... is tracked state, never garbage collected here.
+    for (app_id, path, bytes) in per_game_files(&storage.achievements_root()) {
+        files.push(ManagedFile {
+            path,
+            bytes,
+            app_id: Some(app_id),
+            category: "achievements",
+            orphaned: false,
+        });
+    }
+
+    // assets/{cover_<appid>, background_<appid>, icon_*, icongray_*}
+    for (path, bytes) in walk_files(&storage.assets_dir()) {
+        let name = file_name(&path);
+        let stem = Path::new(&name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let app_id = ["cover_", "background_", "igdb_cover_"]
+            .iter()
+            .find_map(|prefix| stem.strip_prefix(prefix))
+            .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+            .map(String::from);
+        let category = if app_id.is_some() {
+            "assets"
+        } else {
+            "achievement_icons"
+        };
+        let orphaned = !library.referenced.contains(&path);
+        files.push(ManagedFile {
+            path,
+            bytes,
+            app_id,
+            category,
+            orphaned,
+        });
+    }
+
+    let toast_cache_dir = storage.toast_cache_dir();
+    for (path, bytes) in walk_files(&storage.cache_dir()) {
+        let orphaned = path.starts_with(&toast_cache_dir) && older_than(&path, TOAST_CACHE_MAX_AGE);
+        files.push(ManagedFile {
+            path,
+            bytes,
+            app_id: None,
+            category: "cache",
+            orphaned,
+        });
+    }
+
+    // Older versions processed toast images into the temp dir; they are never referenced again.
+    if let Ok(entries) = fs::read_dir(storage.temp_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = file_name(&path);
+            if !name.starts_with("icon_120x120_") && !name.starts_with("processed_hero_") {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let orphaned = older_than(&path, TOAST_TEMP_MIN_AGE);
+            files.push(ManagedFile {
+                path,
+                bytes: metadata.len(),
+                app_id: None,
+                category: "toast_temp",
+                orphaned,
+            });
+        }
+    }
+
+    files
+}
+
+/// Lists `<root>/<appid>/**` files together with the appid directory they live under.
+fn per_game_files(root: &Path) -> Vec<(String, PathBuf, u64)> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(root) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let app_id = entry.file_name().to_string_lossy().to_string();
+        for (path, bytes) in walk_files(&dir) {
+            files.push((app_id.clone(), path, bytes));
+        }
+    }
+    files
+}
+
+pub(crate) fn walk_files(dir: &Path) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(walk_files(&path));
+        } else if let Ok(metadata) = entry.metadata() {
+            files.push((path, metadata.len()));
+        }
+    }
+    files
+}
+
+pub(crate) fn remove_empty_dirs(root: &Path) {
+    if let Ok(entries) = fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                remove_empty_dirs(&path);
+                // Fails (harmlessly) when the directory still has files.
+                let _ = fs::remove_dir(&path);
+            }
+        }
+    }
+}
+
+fn older_than(path: &Path, age: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|elapsed| elapsed >= age)
+        .unwrap_or(false)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, age: Duration) -> PathBuf {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"data").unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+        path.to_path_buf()
+    }
+
+    #[test]
+    fn orphans_are_unreferenced_images_assets_and_stale_toasts() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::in_dir(dir.path());
+        let fresh = Duration::ZERO;
+        let stale = TOAST_CACHE_MAX_AGE + Duration::from_secs(60);
+
+        let kept_wallpaper = write(&storage.images_dir("480").join("wallpaper_1.jpg"), fresh);
+        let unreferenced_cover = write(
+            &storage.images_dir("480").join("library_cover_old.jpg"),
+            fresh,
+        );
+        let removed_game = write(&storage.images_dir("220").join("wallpaper_1.jpg"), fresh);
+        let state = write(&storage.achievements_dir("220").join("data.ini"), fresh);
+        let kept_asset = write(&storage.assets_dir().join("cover_480.jpg"), fresh);
+        let unused_icon = write(&storage.assets_dir().join("icon_ACH_A.jpg"), fresh);
+        let fresh_toast = write(&storage.toast_cache_dir().join("new.png"), fresh);
+        let stale_toast = write(&storage.toast_cache_dir().join("old.png"), stale);
+        let other_cache = write(&storage.cache_dir().join("other").join("old.bin"), stale);
+        let recent_temp = write(&storage.temp_dir().join("icon_120x120_a.png"), fresh);
+        let stale_temp = write(&storage.temp_dir().join("processed_hero_a.png"), stale);
+        write(&storage.temp_dir().join("unrelated.png"), stale);
+
+        let library = Library {
+            games: HashMap::from([("480".to_string(), Some("Spacewar".to_string()))]),
+            referenced: HashSet::from([
+                kept_wallpaper.clone(),
+                removed_game.clone(),
+                kept_asset.clone(),
+            ]),
+        };
+        let orphaned: HashMap<PathBuf, bool> = scan_managed_files(&storage, &library)
+            .into_iter()
+            .map(|file| (file.path, file.orphaned))
+            .collect();
+
+        assert_eq!(orphaned.len(), 11);
+        for path in [
+            &kept_wallpaper,
+            &state,
+            &kept_asset,
+            &fresh_toast,
+            &other_cache,
+            &recent_temp,
+        ] {
+            assert_eq!(orphaned.get(path), Some(&false), "{:?}", path);
+        }
+        for path in [
+            &unreferenced_cover,
+            &removed_game,
+            &unused_icon,
+            &stale_toast,
+            &stale_temp,
+        ] {
+            assert_eq!(orphaned.get(path), Some(&true), "{:?}", path);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! GOG Galaxy 2.0 `galaxy-2.0.db`. Galaxy keys every game by a release key such as
+//! `steam_620` or `gog_1207658924`; titles live in `GamePieces` as JSON and
+//! playtime in `GameTimes` (minutes). Galaxy does not keep unlock history locally.
+
+use super::{parse_timestamp, ImportedGame};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+fn has_table(conn: &Connection, table: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|found| found.is_some())
+    .map_err(|e| format!("Failed to read GOG Galaxy database: {}", e))
+}
+
+fn query_pairs<T: rusqlite::types::FromSql>(
+    conn: &Connection,
+    sql: &str,
+) -> Result<Vec<(String, T)>, String> {
+    let query_err = |e: rusqlite::Error| format!("Failed to read GOG Galaxy database: {}", e);
+    let mut stmt = conn.prepare(sql).map_err(query_err)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(query_err)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(query_err)?;
+    Ok(rows)
+}
+
+pub fn read(path: &Path) -> Result<Vec<ImportedGame>, String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open GOG Galaxy database: {}", e))?;
+    if !has_table(&conn, "GameTimes")? {
+        return Err(format!(
+            "{} is not a GOG Galaxy 2.0 database",
+            path.display()
+        ));
+    }
+
+    let mut games: BTreeMap<String, ImportedGame> = BTreeMap::new();
+    // One row per Galaxy user; the largest total wins.
+    for (release_key, minutes) in query_pairs::<i64>(
+        &conn,
+        "SELECT releaseKey, MAX(minutesInGame) FROM GameTimes GROUP BY releaseKey",
+    )? {
+        let game = games.entry(release_key.clone()).or_default();
+        game.playtime_seconds = minutes.max(0) as u64 * 60;
+    }
+
+    if has_table(&conn, "LastPlayedDates")? {
+        for (release_key, date) in query_pairs::<Option<String>>(
+            &conn,
+            "SELECT gameReleaseKey, lastPlayedDate FROM LastPlayedDates",
+        )? {
+            if let Some(game) = games.get_mut(&release_key) {
+                game.last_played = date.as_deref().and_then(parse_timestamp);
+            }
+        }
+    }
+
+    if has_table(&conn, "GamePieces")? && has_table(&conn, "GamePieceTypes")? {
+        for (release_key, value) in query_pairs::<String>(
+            &conn,
+            "SELECT p.releaseKey, p.value FROM GamePieces p
+             JOIN GamePieceTypes t ON t.id = p.gamePieceTypeId
+             WHERE t.type IN ('originalTitle', 'title')
+             ORDER BY t.type = 'originalTitle'",
+        )? {
+            let Some(game) = games.get_mut(&release_key) else {
+                continue;
+            };
+            if let Some(title) = serde_json::from_str::<serde_json::Value>(&value)
+                .ok()
+                .and_then(|v| v.get("title")?.as_str().map(str::to_string))
+            {
+                game.name = title;
+            }
+        }
+    }
+
+    Ok(games
+        .into_iter()
+        .filter(|(_, game)| game.playtime_seconds > 0)
+        .map(|(release_key, mut game)| {
+            game.steam_app_id = release_key
+                .strip_prefix("steam_")
+                .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+                .map(str::to_string);
+            game.source_id = release_key;
+            game
+        })
+        .collect())
+}
@@ -0,0 +1,349 @@
+//! Importers for playtime and unlocks recorded by other launchers.
+//!
+//! Each source reads its own format into [`ImportedGame`]s. `preview_import` matches
+//! them against the library without writing anything; `apply_import` merges the
+//! selected ones. Merging never lowers local data: playtime only grows to the
+//! imported total and unlocks already recorded locally are left as they are.
+
+mod gog_galaxy;
+mod playnite;
+mod steam;
+
+use crate::db::AchievementUpdate;
+use crate::db::{GameRecord, LibraryDb};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// Playnite data directory, `library/games.db` (LiteDB) or a JSON library export.
+    Playnite,
+    /// Steam `userdata/<id>/config/localconfig.vdf`.
+    Steam,
+    /// GOG Galaxy 2.0 `galaxy-2.0.db`.
+    GogGalaxy,
+}
+
+impl ImportSource {
+    fn label(self) -> &'static str {
+        match self {
+            ImportSource::Playnite => "playnite",
+            ImportSource::Steam => "steam",
+            ImportSource::GogGalaxy => "gog_galaxy",
+        }
+    }
+
+    fn read(self, path: &Path) -> Result<Vec<ImportedGame>, String> {
+        if !path.exists() {
+            return Err(format!("Import source not found: {}", path.display()));
+        }
+        match self {
+            ImportSource::Playnite => playnite::read(path),
+            ImportSource::Steam => steam::read(path),
+            ImportSource::GogGalaxy => gog_galaxy::read(path),
+        }
+    }
+}
+
+/// A game as the other launcher knows it.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedGame {
+    /// The launcher's own id for the game.
+    pub source_id: String,
+    pub name: String,
+    /// Steam appid, when the launcher knows it.
+    pub steam_app_id: Option<String>,
+    pub playtime_seconds: u64,
+    pub last_played: Option<i64>,
+    pub unlocks: Vec<ImportedUnlock>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportedUnlock {
+    pub api_name: String,
+    pub unlock_time: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    AppId,
+    Name,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportCandidate {
+    pub source_id: String,
+    pub name: String,
+    /// Library game the entry merges into; `None` when nothing matched.
+    pub app_id: Option<String>,
+    pub matched_by: Option<MatchKind>,
+    pub playtime_seconds: u64,
+    pub local_playtime_seconds: u64,
+    /// Playtime `apply_import` would add.
+    pub playtime_gain_seconds: u64,
+    pub last_played: Option<i64>,
+    pub unlocks: usize,
+    /// Unlocks not yet recorded locally.
+    pub new_unlocks: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub source: ImportSource,
+    pub path: String,
+    pub candidates: Vec<ImportCandidate>,
+    pub matched: usize,
+    pub unmatched: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub games_updated: usize,
+    pub playtime_seconds_added: u64,
+    pub unlocks_added: usize,
+    /// Candidates without a library match, or not selected.
+    pub skipped: usize,
+    /// Library games that gained playtime or unlocks.
+    pub app_ids: Vec<String>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Matches imported games to library games, by Steam appid first, then by name.
+fn match_game<'a>(
+    game: &ImportedGame,
+    library: &'a [GameRecord],
+    by_name: &HashMap<String, &'a GameRecord>,
+) -> Option<(&'a GameRecord, MatchKind)> {
+    if let Some(app_id) = &game.steam_app_id {
+        if let Some(record) = library.iter().find(|g| &g.app_id == app_id) {
+            return Some((record, MatchKind::AppId));
+        }
+    }
+    let key = normalize_name(&game.name);
+    if key.is_empty() {
+        return None;
+    }
+    by_name.get(&key).map(|record| (*record, MatchKind::Name))
+}
+
+fn new_unlocks(
+    db: &LibraryDb,
+    app_id: &str,
+    game: &ImportedGame,
+    source: ImportSource,
+) -> Result<Vec<AchievementUpdate>, String> {
+    if game.unlocks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let state = db.achievement_state(app_id)?;
+    Ok(game
+        .unlocks
+        .iter()
+        .filter(|u| {
+            !state
+                .achievements
+                .get(&u.api_name)
+                .is_some_and(|s| s.unlocked)
+        })
+        .map(|u| AchievementUpdate {
+            api_name: u.api_name.clone(),
+            unlocked: Some(true),
+            unlock_time: u.unlock_time,
+            source: Some(source.label().to_string()),
+            ..Default::default()
+        })
+        .collect())
+}
+
+fn build_preview(
+    db: &LibraryDb,
+    source: ImportSource,
+    path: &str,
+    imported: &[ImportedGame],
+) -> Result<ImportPreview, String> {
+    let library = db.list_games()?;
+    let by_name: HashMap<String, &GameRecord> = library
+        .iter()
+        .map(|g| (normalize_name(&g.name), g))
+        .filter(|(key, _)| !key.is_empty())
+        .collect();
+
+    let mut candidates = Vec::with_capacity(imported.len());
+    for game in imported {
+        let matched = match_game(game, &library, &by_name);
+        let (local_playtime, new_unlocks) = match matched {
+            Some((record, _)) => (
+                record.playtime_seconds,
+                new_unlocks(db, &record.app_id, game, source)?.len(),
+            ),
+            None => (0, 0),
+        };
+        candidates.push(ImportCandidate {
+            source_id: game.source_id.clone(),
+            name: match matched {
+                Some((record, _)) if game.name.is_empty() => record.name.clone(),
+                _ => game.name.clone(),
+            },
+            app_id: matched.map(|(record, _)| record.app_id.clone()),
+            matched_by: matched.map(|(_, kind)| kind),
+            playtime_seconds: game.playtime_seconds,
+            local_playtime_seconds: local_playtime,
+            playtime_gain_seconds: if matched.is_some() {
+                game.playtime_seconds.saturating_sub(local_playtime)
+            } else {
+                0
+            },
+            last_played: game.last_played,
+            unlocks: game.unlocks.len(),
+            new_unlocks,
+        });
+    }
+
+    let matched = candidates.iter().filter(|c| c.app_id.is_some()).count();
+    Ok(ImportPreview {
+        source,
+        path: path.to_string(),
+        unmatched: candidates.len() - matched,
+        matched,
+        candidates,
+    })
+}
+
+/// Reads `path` and reports what `apply_import` would change, without writing.
+#[tauri::command]
+pub fn preview_import(
+    db: State<'_, LibraryDb>,
+    source: ImportSource,
+    path: String,
+) -> Result<ImportPreview, String> {
+    let imported = source.read(Path::new(&path))?;
+    build_preview(&db, source, &path, &imported)
+}
+
+/// Merges playtime and unlocks from `path` into the library. `app_ids` limits the
+/// merge to those library games; otherwise every matched candidate is applied.
+#[tauri::command]
+pub fn apply_import(
+    db: State<'_, LibraryDb>,
+    source: ImportSource,
+    path: String,
+    app_ids: Option<Vec<String>>,
+) -> Result<ImportReport, String> {
+    let imported = source.read(Path::new(&path))?;
+    let preview = build_preview(&db, source, &path, &imported)?;
+
+    let mut report = ImportReport::default();
+    for (candidate, game) in preview.candidates.iter().zip(&imported) {
+        let app_id = match &candidate.app_id {
+            Some(app_id) if app_ids.as_ref().is_none_or(|ids| ids.contains(app_id)) => app_id,
+            _ => {
+                report.skipped += 1;
+                continue;
+            }
+        };
+
+        let mut updated = false;
+        // Re-read rather than trusting the preview: two entries may match one game.
+        let local_playtime = db.playtime(app_id)?;
+        if game.playtime_seconds > local_playtime {
+            db.record_playtime(app_id, game.playtime_seconds, 0)?;
+            report.playtime_seconds_added += game.playtime_seconds - local_playtime;
+            updated = true;
+        }
+        let unlocks = new_unlocks(&db, app_id, game, source)?;
+        if !unlocks.is_empty() {
+            report.unlocks_added += db.upsert_unlocks(app_id, &unlocks)?.len();
+            updated = true;
+        }
+        if updated && !report.app_ids.contains(app_id) {
+            report.games_updated += 1;
+            report.app_ids.push(app_id.clone());
+        }
+    }
+
+    println!(
+        "Imported from {} ({}): {} games, {}s playtime, {} unlocks",
+        source.label(),
+        path,
+        report.games_updated,
+        report.playtime_seconds_added,
+        report.unlocks_added
+    );
+    Ok(report)
+}
+
+/// Parses a launcher timestamp (RFC 3339, or a naive date taken as UTC) to Unix seconds.
+/// .NET's `DateTime.MinValue` and other pre-epoch placeholders count as "never".
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let seconds = chrono::DateTime::parse_from_rfc3339(value)
+        .map(|t| t.timestamp())
+        .or_else(|_| {
+            [
+                "%Y-%m-%dT%H:%M:%S%.f",
+                "%Y-%m-%d %H:%M:%S%.f",
+                "%Y-%m-%d %H:%M:%S",
+            ]
+            .iter()
+            .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+            .map(|t| t.and_utc().timestamp())
+            .ok_or(())
+        })
+        .ok()?;
+    (seconds > 0).then_some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timestamp_accepts_rfc3339_and_naive_utc_dates() {
+        assert_eq!(parse_timestamp("2023-11-14T22:13:20Z"), Some(1_700_000_000));
+        assert_eq!(
+            parse_timestamp(" 2023-11-15T00:13:20+02:00 "),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            parse_timestamp("2023-11-14T22:13:20.1234567"),
+            Some(1_700_000_000)
+        );
+        assert_eq!(parse_timestamp("2023-11-14 22:13:20"), Some(1_700_000_000));
+        assert_eq!(
+            parse_timestamp("2023-11-14 22:13:20.5"),
+            Some(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_treats_placeholders_as_never() {
+        assert_eq!(parse_timestamp("0001-01-01T00:00:00"), None);
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn normalize_name_ignores_case_punctuation_and_spacing() {
+        assert_eq!(
+            normalize_name("Half-Life 2: Episode One"),
+            "halflife2episodeone"
+        );
+        assert_eq!(
+            normalize_name("HALF LIFE 2 — episode one™"),
+            "halflife2episodeone"
+        );
+        assert_eq!(normalize_name("Café"), "café");
+        assert_eq!(normalize_name(" - "), "");
+    }
+}
@@ -0,0 +1,270 @@
+//! Playnite libraries, from any of:
+//! - the data directory (`%APPDATA%\Playnite`), which also brings in unlocks
+//!   recorded by the SuccessStory extension;
+//! - `library/games.db`, Playnite's LiteDB game collection;
+//! - a JSON export of the library (an array of game objects, or `{ "Games": [...] }`).
+//!
+//! LiteDB has no Rust reader, so `games.db` is scanned for the BSON documents it
+//! stores. A game document larger than one data page is split across pages and
+//! skipped; the JSON export covers those.
+
+use super::{parse_timestamp, ImportedGame, ImportedUnlock};
+use serde_json::Value;
+use std::path::Path;
+
+/// Playnite's built-in Steam library plugin; its games use the appid as `GameId`.
+const STEAM_PLUGIN_ID: &str = "cb91dfc9-b977-43bf-8e70-55f46e410fab";
+/// SuccessStory extension id, the folder name under `ExtensionsData`.
+const SUCCESS_STORY_ID: &str = "cebe6d32-8c46-4459-b993-5a5189d60788";
+
+pub fn read(path: &Path) -> Result<Vec<ImportedGame>, String> {
+    let (library, data_dir) = if path.is_dir() {
+        (path.join("library").join("games.db"), Some(path))
+    } else {
+        (path.to_path_buf(), None)
+    };
+
+    let documents = if is_json(&library)? {
+        read_json(&library)?
+    } else {
+        let bytes = std::fs::read(&library)
+            .map_err(|e| format!("Failed to read {}: {}", library.display(), e))?;
+        bson::scan_documents(&bytes)
+    };
+
+    let mut games: Vec<ImportedGame> = documents.iter().filter_map(game_from_document).collect();
+    if let Some(dir) = data_dir {
+        let success_story = dir
+            .join("ExtensionsData")
+            .join(SUCCESS_STORY_ID)
+            .join("SuccessStory");
+        for game in &mut games {
+            game.unlocks =
+                read_success_story(&success_story.join(format!("{}.json", game.source_id)));
+        }
+    }
+    println!(
+        "Read {} games from Playnite library {}",
+        games.len(),
+        library.display()
+    );
+    Ok(games)
+}
+
+fn is_json(path: &Path) -> Result<bool, String> {
+    use std::io::Read;
+    let mut head = [0u8; 64];
+    let read = std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut head))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let first = head[..read]
+        .iter()
+        .copied()
+        .find(|b| !b.is_ascii_whitespace() && *b != 0xEF && *b != 0xBB && *b != 0xBF);
+    Ok(matches!(first, Some(b'[') | Some(b'{')))
+}
+
+fn read_json(path: &Path) -> Result<Vec<Value>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let value: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Failed to parse Playnite export: {}", e))?;
+    match value {
+        Value::Array(games) => Ok(games),
+        Value::Object(mut map) => match map.remove("Games") {
+            Some(Value::Array(games)) => Ok(games),
+            _ => Err("Playnite export has no Games array".to_string()),
+        },
+        _ => Err("Playnite export is not a game list".to_string()),
+    }
+}
+
+fn game_from_document(doc: &Value) -> Option<ImportedGame> {
+    let name = doc.get("Name")?.as_str()?.to_string();
+    let id = doc
+        .get("Id")
+        .or_else(|| doc.get("_id"))?
+        .as_str()?
+        .to_string();
+    let playtime = doc
+        .get("Playtime")
+        .and_then(|v| v.as_u64().or_else(|| v.as_f64().map(|f| f.max(0.0) as u64)))
+        .unwrap_or(0);
+    let is_steam = doc
+        .get("PluginId")
+        .and_then(Value::as_str)
+        .is_some_and(|plugin| plugin.eq_ignore_ascii_case(STEAM_PLUGIN_ID));
+    let steam_app_id = doc
+        .get("GameId")
+        .and_then(Value::as_str)
+        .filter(|id| is_steam && !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_string);
+    let last_played = doc.get("LastActivity").and_then(|v| match v {
+        Value::String(s) => parse_timestamp(s),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    });
+
+    Some(ImportedGame {
+        source_id: id,
+        name,
+        steam_app_id,
+        playtime_seconds: playtime,
+        last_played,
+        unlocks: Vec::new(),
+    })
+}
+
+/// SuccessStory keeps one file per game with every achievement under `Items`;
+/// locked ones have no `DateUnlocked` (or .NET's minimum date).
+fn read_success_story(path: &Path) -> Vec<ImportedUnlock> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<Value>(text.trim_start_matches('\u{feff}')) else {
+        println!("Skipping unreadable SuccessStory file {}", path.display());
+        return Vec::new();
+    };
+    value
+        .get("Items")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let api_name = item
+                        .get("ApiName")
+                        .and_then(Value::as_str)
+                        .filter(|name| !name.is_empty())?;
+                    let unlock_time = parse_timestamp(item.get("DateUnlocked")?.as_str()?)?;
+                    Some(ImportedUnlock {
+                        api_name: api_name.to_string(),
+                        unlock_time: Some(unlock_time),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Just enough BSON to read LiteDB documents into JSON values.
+mod bson {
+    use serde_json::{Map, Number, Value};
+
+    /// Finds every well-formed document with a `Name` field in a LiteDB file.
+    pub fn scan_documents(bytes: &[u8]) -> Vec<Value> {
+        let mut documents = Vec::new();
+        let mut offset = 0;
+        while offset + 5 <= bytes.len() {
+            let found = read_i32(bytes, offset)
+                .filter(|len| *len >= 5 && offset + *len as usize <= bytes.len())
+                .and_then(|len| {
+                    let end = offset + len as usize;
+                    if bytes[end - 1] != 0 {
+                        return None;
+                    }
+                    let doc = document(bytes, offset + 4, end - 1)?;
+                    doc.get("Name")?.as_str()?;
+                    Some((doc, end))
+                });
+            match found {
+                Some((doc, end)) => {
+                    documents.push(Value::Object(doc));
+                    offset = end;
+                }
+                None => offset += 1,
+            }
+        }
+        documents
+    }
+
+    fn read_i32(bytes: &[u8], at: usize) -> Option<i32> {
+        Some(i32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+    }
+
+    fn read_i64(bytes: &[u8], at: usize) -> Option<i64> {
+        Some(i64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?))
+    }
+
+    /// Elements between `pos` and the terminating zero at `end`.
+    fn document(bytes: &[u8], mut pos: usize, end: usize) -> Option<Map<String, Value>> {
+        let mut map = Map::new();
+        while pos < end {
+            let kind = bytes[pos];
+            let name_end = pos + 1 + bytes.get(pos + 1..end)?.iter().position(|b| *b == 0)?;
+            let name = std::str::from_utf8(&bytes[pos + 1..name_end])
+                .ok()?
+                .to_string();
+            pos = name_end + 1;
+            let (value, size) = element(bytes, kind, pos, end)?;
+            map.insert(name, value);
+            pos += size;
+        }
+        (pos == end).then_some(map)
+    }
+
+    fn element(bytes: &[u8], kind: u8, pos: usize, end: usize) -> Option<(Value, usize)> {
+        Some(match kind {
+            0x01 => {
+                let v = f64::from_le_bytes(bytes.get(pos..pos + 8)?.try_into().ok()?);
+                (Number::from_f64(v).map_or(Value::Null, Value::Number), 8)
+            }
+            0x02 => {
+                let len = read_i32(bytes, pos)?;
+                if len < 1 || pos + 4 + len as usize > end {
+                    return None;
+                }
+                let raw = &bytes[pos + 4..pos + 4 + len as usize - 1];
+                (
+                    Value::String(String::from_utf8_lossy(raw).into_owned()),
+                    4 + len as usize,
+                )
+            }
+            0x03 | 0x04 => {
+                let len = read_i32(bytes, pos)?;
+                if len < 5 || pos + len as usize > end {
+                    return None;
+                }
+                let inner = document(bytes, pos + 4, pos + len as usize - 1)?;
+                let value = if kind == 0x04 {
+                    Value::Array(inner.into_iter().map(|(_, v)| v).collect())
+                } else {
+                    Value::Object(inner)
+                };
+                (value, len as usize)
+            }
+            0x05 => {
+                let len = read_i32(bytes, pos)?;
+                if len < 0 || pos + 5 + len as usize > end {
+                    return None;
+                }
+                let data = &bytes[pos + 5..pos + 5 + len as usize];
+                let value = if bytes[pos + 4] == 0x04 && data.len() == 16 {
+                    Value::String(guid(data))
+                } else {
+                    Value::Null
+                };
+                (value, 5 + len as usize)
+            }
+            0x07 => (Value::Null, 12),
+            0x08 => (Value::Bool(*bytes.get(pos)? != 0), 1),
+            // UTC milliseconds; converted to Unix seconds.
+            0x09 => (Value::from(read_i64(bytes, pos)?.div_euclid(1000)), 8),
+            0x0A | 0xFF | 0x7F => (Value::Null, 0),
+            0x10 => (Value::from(read_i32(bytes, pos)?), 4),
+            0x11 | 0x12 => (Value::from(read_i64(bytes, pos)?), 8),
+            0x13 => (Value::Null, 16),
+            _ => return None,
+        })
+        .filter(|(_, size)| pos + size <= end)
+    }
+
+    /// .NET `Guid` byte order: the first three groups are little-endian.
+    fn guid(b: &[u8]) -> String {
+        format!(
+            "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6],
+            b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+        )
+    }
+}
@@ -0,0 +1,43 @@
+//! Steam `localconfig.vdf`: per-user playtime (minutes) and last-played times.
+//! Unlocks are not stored there.
+
+use super::ImportedGame;
+use crate::vdf::Vdf;
+use std::path::{Path, PathBuf};
+
+/// Accepts the file itself or a `userdata/<id>` directory.
+fn locate(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join("config").join("localconfig.vdf")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+pub fn read(path: &Path) -> Result<Vec<ImportedGame>, String> {
+    let config = Vdf::read(&locate(path))?;
+    let apps = config
+        .get_path(&["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"])
+        .ok_or_else(|| "localconfig.vdf has no apps section".to_string())?;
+
+    Ok(apps
+        .entries()
+        .iter()
+        .filter(|(app_id, _)| app_id.chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|(app_id, app)| {
+            let minutes: u64 = app.get_str("Playtime")?.parse().ok()?;
+            let last_played = app
+                .get_str("LastPlayed")
+                .and_then(|t| t.parse::<i64>().ok())
+                .filter(|t| *t > 0);
+            Some(ImportedGame {
+                source_id: app_id.clone(),
+                steam_app_id: Some(app_id.clone()),
+                playtime_seconds: minutes * 60,
+                last_played,
+                ..Default::default()
+            })
+        })
+        .filter(|game| game.playtime_seconds > 0)
+        .collect())
+}
@@ -0,0 +1,105 @@
+//! Bottles: every bottle is a Wine prefix with a `bottle.yml`, whose
+//! `External_Programs` lists the executables added to it.
+
+use super::{app_dirs, read_yaml, LauncherEntry};
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+fn yaml_str<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key)?.as_str().filter(|s| !s.is_empty())
+}
+
+/// Program paths are usually host paths; Windows ones are taken as inside the bottle.
+fn program_path(bottle: &Path, path: &str) -> PathBuf {
+    let lower = path.to_ascii_lowercase();
+    match lower.strip_prefix("c:") {
+        Some(_) => bottle
+            .join("drive_c")
+            .join(path[2..].trim_start_matches(['\\', '/']).replace('\\', "/")),
+        None => PathBuf::from(path),
+    }
+}
+
+fn bottle_entries(bottle: &Path) -> Vec<LauncherEntry> {
+    let Some(config) = read_yaml(&bottle.join("bottle.yml")) else {
+        return Vec::new();
+    };
+    let bottle_name = yaml_str(&config, "Name").unwrap_or_default().to_string();
+    let Some(programs) = config.get("External_Programs").and_then(Value::as_mapping) else {
+        return Vec::new();
+    };
+
+    programs
+        .iter()
+        .filter_map(|(id, program)| {
+            let exe = program_path(bottle, yaml_str(program, "path")?);
+            let name = yaml_str(program, "name")
+                .or_else(|| yaml_str(program, "executable"))
+                .map(str::to_string)
+                .unwrap_or_else(|| exe.to_string_lossy().into_owned());
+            Some(LauncherEntry {
+                id: format!("{}/{}", bottle_name, id.as_str().unwrap_or_default()),
+                name,
+                install_dir: yaml_str(program, "folder")
+                    .map(|dir| program_path(bottle, dir))
+                    .or_else(|| exe.parent().map(Path::to_path_buf)),
+                exe_path: Some(exe),
+                wine_prefix: Some(bottle.to_path_buf()),
+                runner: yaml_str(&config, "Runner").map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+pub fn entries() -> Vec<LauncherEntry> {
+    let mut entries = Vec::new();
+    for data_dir in app_dirs(
+        &[".local/share/bottles"],
+        "com.usebottles.bottles",
+        &["data/bottles"],
+    ) {
+        let Ok(bottles) = std::fs::read_dir(data_dir.join("bottles")) else {
+            continue;
+        };
+        for bottle in bottles.flatten().filter(|b| b.path().is_dir()) {
+            entries.extend(bottle_entries(&bottle.path()));
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/launchers/bottles");
+
+    #[test]
+    fn programs_map_windows_paths_into_the_bottle() {
+        let bottle = Path::new(FIXTURES).join("Gaming");
+        let entries = bottle_entries(&bottle);
+        assert_eq!(entries.len(), 2);
+
+        let hades = &entries[0];
+        assert_eq!(hades.id, "Gaming/4c1e0bd6-bd8f-4a4b-9e8c-3b1a7f0d5e21");
+        assert_eq!(hades.name, "Hades");
+        assert_eq!(
+            hades.exe_path,
+            Some(PathBuf::from("/games/Hades/x64/Hades.exe"))
+        );
+        assert_eq!(hades.install_dir, Some(PathBuf::from("/games/Hades/x64")));
+        assert_eq!(hades.wine_prefix.as_deref(), Some(bottle.as_path()));
+        assert_eq!(hades.runner.as_deref(), Some("soda-7.0-9"));
+
+        let spacewar = &entries[1];
+        assert_eq!(spacewar.name, "Spacewar.exe");
+        assert_eq!(
+            spacewar.exe_path,
+            Some(bottle.join("drive_c/Games/Spacewar/bin/Spacewar.exe"))
+        );
+        assert_eq!(
+            spacewar.install_dir,
+            Some(bottle.join("drive_c/Games/Spacewar"))
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Heroic Games Launcher: sideloaded apps, GOG and Epic (Legendary) installs.
+//! Per-game Wine settings live in `GamesConfig/<appName>.json`.
+
+use super::{app_dirs, read_json, LauncherEntry};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key)?.as_str().filter(|s| !s.is_empty())
+}
+
+fn wine_prefix(config_dir: &Path, app_name: &str) -> Option<PathBuf> {
+    let config = read_json(
+        &config_dir
+            .join("GamesConfig")
+            .join(format!("{}.json", app_name)),
+    )?;
+    str_field(config.get(app_name)?, "winePrefix").map(PathBuf::from)
+}
+
+/// The primary play task from GOG's `goggame-<id>.info`.
+fn gog_executable(install_dir: &Path, app_name: &str) -> Option<PathBuf> {
+    let info = read_json(&install_dir.join(format!("goggame-{}.info", app_name)))?;
+    let tasks = info.get("playTasks")?.as_array()?;
+    let task = tasks
+        .iter()
+        .find(|t| t.get("isPrimary").and_then(Value::as_bool) == Some(true))
+        .or_else(|| tasks.first())?;
+    Some(install_dir.join(str_field(task, "path")?.replace('\\', "/")))
+}
+
+fn sideload(config_dir: &Path) -> Vec<LauncherEntry> {
+    let Some(library) = read_json(&config_dir.join("sideload_apps").join("library.json")) else {
+        return Vec::new();
+    };
+    let games = library.get("games").and_then(Value::as_array);
+    games
+        .into_iter()
+        .flatten()
+        .filter_map(|game| {
+            let app_name = str_field(game, "app_name")?;
+            let exe = game
+                .get("install")
+                .and_then(|install| str_field(install, "executable"))
+                .map(PathBuf::from);
+            Some(LauncherEntry {
+                id: app_name.to_string(),
+                name: str_field(game, "title").unwrap_or(app_name).to_string(),
+                install_dir: str_field(game, "folder_name")
+                    .map(PathBuf::from)
+                    .or_else(|| exe.as_ref()?.parent().map(Path::to_path_buf)),
+                exe_path: exe,
+                wine_prefix: wine_prefix(config_dir, app_name),
+                runner: Some("sideload".to_string()),
+            })
+        })
+        .collect()
+}
+
+fn gog(config_dir: &Path) -> Vec<LauncherEntry> {
+    let Some(installed) = read_json(&config_dir.join("gog_store").join("installed.json")) else {
+        return Vec::new();
+    };
+
+    let mut titles: HashMap<String, String> = HashMap::new();
+    for library in [
+        config_dir.join("store_cache").join("gog_library.json"),
+        config_dir.join("gog_store").join("library.json"),
+    ] {
+        let Some(library) = read_json(&library) else {
+            continue;
+        };
+        for game in library
+            .get("games")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let (Some(id), Some(title)) = (str_field(game, "app_name"), str_field(game, "title"))
+            {
+                titles
+                    .entry(id.to_string())
+                    .or_insert_with(|| title.to_string());
+            }
+        }
+    }
+
+    let games = installed.get("installed").and_then(Value::as_array);
+    games
+        .into_iter()
+        .flatten()
+        .filter_map(|game| {
+            let app_name = str_field(game, "appName")?;
+            let install_dir = PathBuf::from(str_field(game, "install_path")?);
+            let exe = str_field(game, "executable")
+                .map(|exe| install_dir.join(exe))
+                .or_else(|| gog_executable(&install_dir, app_name));
+            Some(LauncherEntry {
+                id: app_name.to_string(),
+                name: titles
+                    .get(app_name)
+                    .cloned()
+                    .unwrap_or_else(|| app_name.to_string()),
+                exe_path: exe,
+                install_dir: Some(install_dir),
+                wine_prefix: wine_prefix(config_dir, app_name),
+                runner: Some("gog".to_string()),
+            })
+        })
+        .collect()
+}
+
+fn legendary(config_dir: &Path) -> Vec<LauncherEntry> {
+    let mut candidates = vec![config_dir
+        .join("legendaryConfig")
+        .join("legendary")
+        .join("installed.json")];
+    if let Some(home) = std::env::var_os("HOME") {
+        candidates.push(PathBuf::from(home).join(".config/legendary/installed.json"));
+    }
+    let Some(installed) = candidates.iter().find_map(|path| read_json(path)) else {
+        return Vec::new();
+    };
+
+    installed
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(app_name, game)| {
+            let install_dir = PathBuf::from(str_field(game, "install_path")?);
+            Some(LauncherEntry {
+                id: app_name.clone(),
+                name: str_field(game, "title").unwrap_or(app_name).to_string(),
+                exe_path: str_field(game, "executable").map(|exe| install_dir.join(exe)),
+                install_dir: Some(install_dir),
+                wine_prefix: wine_prefix(config_dir, app_name),
+                runner: Some("legendary".to_string()),
+            })
+        })
+        .collect()
+}
+
+pub fn entries() -> Vec<LauncherEntry> {
+    let mut entries = Vec::new();
+    for config_dir in app_dirs(
+        &[".config/heroic"],
+        "com.heroicgameslauncher.hgl",
+        &["config/heroic"],
+    ) {
+        entries.extend(sideload(&config_dir));
+        entries.extend(gog(&config_dir));
+        entries.extend(legendary(&config_dir));
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/launchers/heroic");
+
+    fn path(path: &str) -> Option<PathBuf> {
+        Some(PathBuf::from(path))
+    }
+
+    #[test]
+    fn sideloaded_apps_use_their_games_config_prefix() {
+        let entries = sideload(Path::new(FIXTURES));
+        assert_eq!(entries.len(), 2);
+
+        let spacewar = &entries[0];
+        assert_eq!(spacewar.id, "aB3kLm9x");
+        assert_eq!(spacewar.name, "Spacewar");
+        assert_eq!(spacewar.exe_path, path("/games/Spacewar/bin/Spacewar.exe"));
+        assert_eq!(spacewar.install_dir, path("/games/Spacewar"));
+        assert_eq!(
+            spacewar.wine_prefix,
+            path("/home/player/Games/Heroic/Prefixes/Spacewar")
+        );
+        assert_eq!(spacewar.runner.as_deref(), Some("sideload"));
+
+        let other = &entries[1];
+        assert_eq!(other.name, "noFolder1");
+        assert_eq!(other.install_dir, path("/games/Other"));
+        assert_eq!(other.wine_prefix, None);
+    }
+
+    #[test]
+    fn gog_and_legendary_installs_are_named_from_the_store() {
+        let gog = gog(Path::new(FIXTURES));
+        let names: Vec<&str> = gog.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Unreal Gold", "Hades"]);
+        assert_eq!(gog[0].install_dir, path("/games/GOG/Unreal Gold"));
+        assert_eq!(gog[0].exe_path, None);
+        assert_eq!(gog[1].exe_path, path("/games/GOG/Hades/x64/Hades.exe"));
+        assert!(gog.iter().all(|e| e.runner.as_deref() == Some("gog")));
+
+        let legendary = legendary(Path::new(FIXTURES));
+        assert_eq!(legendary.len(), 1);
+        assert_eq!(legendary[0].id, "Fortnite");
+        assert_eq!(
+            legendary[0].exe_path,
+            path("/games/Epic/Fortnite/FortniteGame/Binaries/Win64/FortniteLauncher.exe")
+        );
+        assert_eq!(legendary[0].runner.as_deref(), Some("legendary"));
+    }
+}
@@ -0,0 +1,168 @@
+//! Lutris: installed games come from the `games` table of `pga.db`; the exe and
+//! Wine prefix are in each game's YAML config, named by its `configpath` column.
+
+use super::{app_dirs, read_yaml, LauncherEntry};
+use rusqlite::{Connection, OpenFlags};
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+struct PgaGame {
+    id: i64,
+    name: String,
+    slug: String,
+    runner: Option<String>,
+    directory: Option<String>,
+    configpath: Option<String>,
+}
+
+fn read_pga(db: &Path) -> Result<Vec<PgaGame>, String> {
+    let conn = Connection::open_with_flags(db, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open {}: {}", db.display(), e))?;
+    let query_err = |e: rusqlite::Error| format!("Failed to read Lutris games: {}", e);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, slug, runner, directory, configpath FROM games
+             WHERE installed = 1",
+        )
+        .map_err(query_err)?;
+    let games = stmt
+        .query_map([], |row| {
+            Ok(PgaGame {
+                id: row.get(0)?,
+                name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                slug: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                runner: row.get(3)?,
+                directory: row.get(4)?,
+                configpath: row.get(5)?,
+            })
+        })
+        .map_err(query_err)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(query_err)?;
+    Ok(games)
+}
+
+fn yaml_str(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)?
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Installed games of one Lutris data dir, with their YAML configs from `config_dirs`.
+fn data_dir_entries(data_dir: &Path, config_dirs: &[PathBuf]) -> Vec<LauncherEntry> {
+    let games = match read_pga(&data_dir.join("pga.db")) {
+        Ok(games) => games,
+        Err(e) => {
+            println!("{}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut entries = Vec::new();
+    for game in games {
+        let config = game.configpath.as_ref().and_then(|name| {
+            config_dirs
+                .iter()
+                .find_map(|dir| read_yaml(&dir.join("games").join(format!("{}.yml", name))))
+        });
+        let section = config.as_ref().and_then(|c| c.get("game"));
+
+        let install_dir = game.directory.as_deref().map(expand_home);
+        let working_dir = section
+            .and_then(|s| yaml_str(s, "working_dir"))
+            .map(|dir| expand_home(&dir));
+        let exe_path = section.and_then(|s| yaml_str(s, "exe")).map(|exe| {
+            let exe = expand_home(&exe);
+            if exe.is_absolute() {
+                exe
+            } else {
+                working_dir
+                    .as_ref()
+                    .or(install_dir.as_ref())
+                    .map(|dir| dir.join(&exe))
+                    .unwrap_or(exe)
+            }
+        });
+
+        entries.push(LauncherEntry {
+            id: if game.slug.is_empty() {
+                game.id.to_string()
+            } else {
+                game.slug
+            },
+            name: game.name,
+            install_dir: install_dir
+                .or(working_dir)
+                .or_else(|| exe_path.as_ref()?.parent().map(Path::to_path_buf)),
+            exe_path,
+            wine_prefix: section
+                .and_then(|s| yaml_str(s, "prefix"))
+                .map(|p| expand_home(&p)),
+            runner: game.runner,
+        });
+    }
+    entries
+}
+
+pub fn entries() -> Vec<LauncherEntry> {
+    let config_dirs = app_dirs(
+        &[".config/lutris", ".local/share/lutris"],
+        "net.lutris.Lutris",
+        &["config/lutris", "data/lutris"],
+    );
+    app_dirs(
+        &[".local/share/lutris"],
+        "net.lutris.Lutris",
+        &["data/lutris"],
+    )
+    .iter()
+    .flat_map(|data_dir| data_dir_entries(data_dir, &config_dirs))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/launchers/lutris");
+
+    fn path(path: &str) -> Option<PathBuf> {
+        Some(PathBuf::from(path))
+    }
+
+    #[test]
+    fn installed_games_take_exe_and_prefix_from_their_config() {
+        let fixtures = PathBuf::from(FIXTURES);
+        let entries = data_dir_entries(&fixtures, std::slice::from_ref(&fixtures));
+        assert_eq!(entries.len(), 2);
+
+        let hades = &entries[0];
+        assert_eq!(hades.id, "hades");
+        assert_eq!(hades.name, "Hades");
+        assert_eq!(hades.exe_path, path("/games/Hades/x64/Hades.exe"));
+        assert_eq!(hades.install_dir, path("/games/Hades"));
+        assert_eq!(hades.wine_prefix, path("/home/player/Games/hades"));
+        assert_eq!(hades.runner.as_deref(), Some("wine"));
+
+        // Without a slug the Lutris id is used.
+        let spacewar = &entries[1];
+        assert_eq!(spacewar.id, "2");
+        assert_eq!(spacewar.exe_path, path("/games/Spacewar/Spacewar.exe"));
+        assert_eq!(spacewar.install_dir, path("/games/Spacewar"));
+    }
+
+    #[test]
+    fn missing_database_yields_no_games() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(data_dir_entries(dir.path(), &[]).is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Games installed through Heroic, Lutris and Bottles, the usual way to run
+//! non-Steam titles on Linux. Each reader yields the exe and Wine prefix; the
+//! emulator fingerprint of the install directory adds the save files to watch.
+
+mod bottles;
+mod heroic;
+mod lutris;
+
+use crate::db::LibraryDb;
+use crate::emulators::{self, EmulatorDetection};
+use crate::wine::WinePrefix;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Launcher {
+    Heroic,
+    Lutris,
+    Bottles,
+}
+
+impl Launcher {
+    fn label(self) -> &'static str {
+        match self {
+            Launcher::Heroic => "Heroic",
+            Launcher::Lutris => "Lutris",
+            Launcher::Bottles => "Bottles",
+        }
+    }
+}
+
+/// A game as a launcher describes it, before emulator detection.
+#[derive(Debug, Clone, Default)]
+pub struct LauncherEntry {
+    pub id: String,
+    pub name: String,
+    pub exe_path: Option<PathBuf>,
+    pub install_dir: Option<PathBuf>,
+    pub wine_prefix: Option<PathBuf>,
+    /// Heroic store (`gog`, `legendary`, `sideload`) or Lutris runner.
+    pub runner: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LauncherGame {
+    pub launcher: Launcher,
+    pub id: String,
+    pub name: String,
+    pub exe_path: Option<String>,
+    pub install_dir: Option<String>,
+    pub wine_prefix: Option<String>,
+    pub runner: Option<String>,
+    /// Emulators found in the install directory, best first.
+    pub emulators: Vec<EmulatorDetection>,
+    /// Save files of the best detection, ready for `track_files`.
+    pub save_files: Vec<String>,
+    pub app_id: Option<String>,
+    /// An UnlockIt game already uses this exe.
+    pub in_library: bool,
+}
+
+/// Config and data directories for a Linux app, native install then Flatpak.
+fn app_dirs(native: &[&str], flatpak_id: &str, flatpak: &[&str]) -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = native.iter().map(|dir| home.join(dir)).collect();
+    let flatpak_root = home.join(".var/app").join(flatpak_id);
+    dirs.extend(flatpak.iter().map(|dir| flatpak_root.join(dir)));
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!("Skipping unreadable {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn read_yaml(path: &Path) -> Option<serde_yaml::Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    match serde_yaml::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!("Skipping unreadable {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn to_game(launcher: Launcher, entry: LauncherEntry) -> LauncherGame {
+    let prefix = entry.wine_prefix.clone().and_then(WinePrefix::at);
+    // Emulator files sit next to the exe; the install dir can be a whole prefix
+    // or a launcher's working dir, so it is only the fallback.
+    let exe_dir = entry.exe_path.as_deref().and_then(Path::parent);
+    let install_dir = entry
+        .install_dir
+        .as_deref()
+        .filter(|dir| Some(*dir) != exe_dir);
+    let detections = [exe_dir, install_dir]
+        .into_iter()
+        .flatten()
+        .filter(|dir| dir.is_dir())
+        .map(|dir| emulators::detect_in_prefix(dir, prefix.as_ref()))
+        .find(|detections| !detections.is_empty())
+        .unwrap_or_default();
+    let best = detections.first();
+    let string = |path: Option<PathBuf>| path.map(|p| p.to_string_lossy().into_owned());
+
+    LauncherGame {
+        launcher,
+        save_files: best.map(|d| d.watch_files.clone()).unwrap_or_default(),
+        app_id: best.and_then(|d| d.app_id.clone()),
+        emulators: detections,
+        id: entry.id,
+        name: entry.name,
+        exe_path: string(entry.exe_path),
+        install_dir: string(entry.install_dir),
+        wine_prefix: string(entry.wine_prefix),
+        runner: entry.runner,
+        in_library: false,
+    }
+}
+
+type EntryReader = fn() -> Vec<LauncherEntry>;
+
+/// Every game Heroic, Lutris and Bottles know about on this machine.
+pub fn scan_launcher_games() -> Vec<LauncherGame> {
+    let sources: [(Launcher, EntryReader); 3] = [
+        (Launcher::Heroic, heroic::entries),
+        (Launcher::Lutris, lutris::entries),
+        (Launcher::Bottles, bottles::entries),
+    ];
+    let mut games = Vec::new();
+    for (launcher, read) in sources {
+        let entries = read();
+        println!("Found {} {} games", entries.len(), launcher.label());
+        games.extend(entries.into_iter().map(|entry| to_game(launcher, entry)));
+    }
+    games
+}
+
+/// Lists games from Heroic, Lutris and Bottles with their exe, Wine prefix and
+/// emulator save files, for the add-game flow and the file watcher.
+#[tauri::command]
+pub async fn discover_launcher_games(
+    db: State<'_, LibraryDb>,
+) -> Result<Vec<LauncherGame>, String> {
+    let mut games = tauri::async_runtime::spawn_blocking(scan_launcher_games)
+        .await
+        .map_err(|e| format!("Failed to scan launcher libraries: {}", e))?;
+
+    let known: HashSet<String> = db
+        .list_games()?
+        .into_iter()
+        .filter_map(|g| g.exe_path)
+        .collect();
+    for game in &mut games {
+        game.in_library = game
+            .exe_path
+            .as_ref()
+            .is_some_and(|exe| known.contains(exe));
+    }
+    Ok(games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulators::EmulatorKind;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+    fn entry(exe_dir: &str, install_dir: &str) -> LauncherEntry {
+        LauncherEntry {
+            id: "game".to_string(),
+            name: "Game".to_string(),
+            exe_path: Some(Path::new(FIXTURES).join(exe_dir).join("Game.exe")),
+            install_dir: Some(Path::new(FIXTURES).join(install_dir)),
+            ..LauncherEntry::default()
+        }
+    }
+
+    #[test]
+    fn to_game_scans_the_exe_dir_before_the_install_dir() {
+        let game = to_game(
+            Launcher::Heroic,
+            entry("emulators/3dm/game", "emulators/skidrow/game"),
+        );
+        assert_eq!(game.emulators[0].emulator, EmulatorKind::ThreeDm);
+        assert_eq!(game.app_id.as_deref(), Some("480"));
+
+        let game = to_game(
+            Launcher::Lutris,
+            entry("launchers", "emulators/skidrow/game"),
+        );
+        assert_eq!(game.emulators[0].emulator, EmulatorKind::Skidrow);
+
+        let game = to_game(Launcher::Bottles, entry("launchers", "launchers/lutris"));
+        assert!(game.emulators.is_empty());
+        assert!(game.save_files.is_empty());
+    }
+}
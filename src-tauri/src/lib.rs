@@ -1,16 +1,41 @@
 use serde_json::json;
-use std::collections::HashMap;
-use std::fs::{create_dir_all, OpenOptions};
-use std::io::Write;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::{Command as StdCommand, Stdio};
-use std::sync::mpsc::channel as std_channel;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
-use tauri::{Emitter, Manager, State};
-use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{Manager, State};
+
+pub mod audio;
+mod backup;
+mod db;
+mod emulators;
+mod file_watcher;
+mod gc;
+mod importers;
+mod launchers;
+pub mod notifications;
+mod process;
+pub mod rarity;
+mod steam_discovery;
+mod storage;
+mod toast_images;
+mod unlock_export;
+mod vdf;
+mod wine;
+use audio::AudioPlayer;
+use db::{AchievementState, AchievementUpdate, LibraryDb};
+use file_watcher::FileWatchers;
+use notifications::{
+    AchievementToast, NotificationCenter, NotificationQueue, NotificationRules,
+    QueuedNotification, ToastRequest,
+};
+use process::is_process_running;
+use rarity::RarityTier;
+use storage::Storage;
+use toast_images::{process_toast_image, ToastImageTransform};
 
 #[tauri::command]
 async fn download_and_install_update(url: String) -> Result<String, String> {
@@ -49,11 +74,12 @@ async fn download_and_install_update(url: String) -> Result<String, String> {
 }
 
 #[derive(Default)]
-struct ProcessManager {
+pub(crate) struct ProcessManager {
     processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
 }
 
 struct ProcessInfo {
+    process_name: String,
     start_time: Instant,
     accumulated_time: u64, // in seconds
     is_running: bool,
@@ -65,6 +91,29 @@ impl ProcessManager {
             processes: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    /// Appids whose process is currently being tracked as running.
+    pub(crate) fn running_app_ids(&self) -> Vec<String> {
+        let processes = self.processes.lock().unwrap();
+        processes
+            .iter()
+            .filter(|(_, info)| info.is_running)
+            .map(|(appid, _)| appid.clone())
+            .collect()
+    }
+
+    /// Appid of the running tracked game whose executable is `process_name`.
+    pub(crate) fn app_id_for_process(&self, process_name: &str) -> Option<String> {
+        let target = process_name.trim_end_matches(".exe").to_lowercase();
+        let processes = self.processes.lock().unwrap();
+        processes
+            .iter()
+            .find(|(_, info)| {
+                info.is_running
+                    && info.process_name.trim_end_matches(".exe").to_lowercase() == target
+            })
+            .map(|(appid, _)| appid.clone())
+    }
 }
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -111,35 +160,19 @@ async fn fetch_achievements(api_key: String, appid: String) -> Result<serde_json
 }
 #[tauri::command]
 async fn store_achievements_by_appid(
-    app_handle: tauri::AppHandle,
+    db: State<'_, LibraryDb>,
     app_id: String,
-    content: String,
-) -> Result<(), String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Could not get app data directory: {}", e))?;
-
-    let folder_path = app_data_dir
-        .join("UnlockIt")
-        .join("Achievements")
-        .join(&app_id);
-    create_dir_all(&folder_path).map_err(|e| e.to_string())?;
-
-    let file_path = folder_path.join("data.ini");
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(file_path)
-        .map_err(|e| e.to_string())?;
-
-    writeln!(file, "{}", content).map_err(|e| e.to_string())?;
+    achievements: Vec<AchievementUpdate>,
+) -> Result<BTreeMap<String, AchievementState>, String> {
+    let changed = db.upsert_unlocks(&app_id, &achievements)?;
 
     println!(
-        "Stored achievements for app {} in: {:?}",
-        app_id, folder_path
+        "Stored achievements for app {}: {} of {} changed",
+        app_id,
+        changed.len(),
+        achievements.len()
     );
-    Ok(())
+    Ok(changed)
 }
 
 #[tauri::command]
@@ -228,11 +261,16 @@ async fn fetch_game_metadata_from_steam(app_id: String) -> Result<serde_json::Va
 }
 #[tauri::command]
 fn load_image(path: String) -> Result<String, String> {
+    image_data_url(Path::new(&path))
+}
+
+/// Reads an image from disk as a `data:` URL the webviews can display directly.
+pub(crate) fn image_data_url(path: &Path) -> Result<String, String> {
     use base64::{engine::general_purpose, Engine};
     use std::fs;
 
-    let bytes = fs::read(&path).map_err(|e| e.to_string())?;
-    let mime = if let Some(ext) = std::path::Path::new(&path)
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let mime = if let Some(ext) = path
         .extension()
         .and_then(|e| e.to_str())
     {
@@ -258,21 +296,14 @@ fn load_image(path: String) -> Result<String, String> {
 async fn start_playtime_tracking(
     app_handle: tauri::AppHandle,
     process_manager: State<'_, ProcessManager>,
+    db: State<'_, LibraryDb>,
     appid: String,
     exe_path: String,
 ) -> Result<(), String> {
     println!("Starting playtime tracking for {} with exe: {}", appid, exe_path);
     
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Could not get app data directory: {}", e))?;
-
-    let playtime_dir = app_data_dir.join("UnlockIt");
-    let playtime_file = playtime_dir.join("playtimes.json");
-    
-    // Load existing playtime from the correct location
-    let existing_playtime = load_playtime(&playtime_file, &appid).await.unwrap_or(0);
+    // Load existing playtime from the library database
+    let existing_playtime = load_playtime(&db, &appid).await.unwrap_or(0);
     println!("Loaded existing playtime: {} seconds", existing_playtime);
     
     {
@@ -316,6 +347,7 @@ async fn start_playtime_tracking(
         processes.insert(
             appid.clone(),
             ProcessInfo {
+                process_name: process_name.clone(),
                 start_time: Instant::now(),
                 accumulated_time: existing_playtime,
                 is_running: true,
@@ -340,7 +372,7 @@ async fn start_playtime_tracking(
         }
 
         println!("Process {} is no longer running", process_name_clone);
-        let final_playtime = {
+        let (final_playtime, session_playtime) = {
             let mut processes = processes_arc.lock().unwrap();
             if let Some(info) = processes.get_mut(&appid_clone) {
                 let session_time = info.start_time.elapsed().as_secs();
@@ -349,17 +381,21 @@ async fn start_playtime_tracking(
                 let final_time = info.accumulated_time;
                 println!("Final playtime for {}: {} seconds (session: {} seconds)", 
                         appid_clone, final_time, session_time);
-                final_time
+                (final_time, session_time)
             } else {
-                0
+                (0, 0)
             }
         };
+
+        // Deferred notifications for this game can be shown now.
+        app_handle_clone
+            .state::<NotificationRules>()
+            .flush_deferred(&app_handle_clone, &appid_clone);
         
         // Save playtime asynchronously
         tokio::spawn(async move {
-            let app_data_dir = app_handle_clone.path().app_data_dir().unwrap();
-            let playtime_file = app_data_dir.join("UnlockIt").join("playtimes.json");
-            if let Err(e) = save_playtime(&playtime_file, &appid_clone, final_playtime).await {
+            let db = app_handle_clone.state::<LibraryDb>();
+            if let Err(e) = save_playtime(&db, &appid_clone, final_playtime, session_playtime).await {
                 println!("Failed to save playtime: {}", e);
             } else {
                 println!("Successfully saved playtime: {} seconds", final_playtime);
@@ -374,6 +410,7 @@ async fn start_playtime_tracking(
 async fn start_process_monitoring(
     app_handle: tauri::AppHandle,
     process_manager: State<'_, ProcessManager>,
+    db: State<'_, LibraryDb>,
     appid: String,
     exe_path: String,
 ) -> Result<(), String> {
@@ -385,13 +422,7 @@ async fn start_process_monitoring(
         .unwrap_or("")
         .to_string();
 
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Could not get app data directory: {}", e))?;
-
-    let playtime_file = app_data_dir.join("UnlockIt").join("playtimes.json");
-    let existing_playtime = load_playtime(&playtime_file, &appid).await.unwrap_or(0);
+    let existing_playtime = load_playtime(&db, &appid).await.unwrap_or(0);
     
     // Check if process is currently running
     if !is_process_running(&process_name) {
@@ -407,6 +438,7 @@ async fn start_process_monitoring(
         processes.insert(
             appid.clone(),
             ProcessInfo {
+                process_name: process_name.clone(),
                 start_time: Instant::now(),
                 accumulated_time: existing_playtime,
                 is_running: true,
@@ -430,7 +462,7 @@ async fn start_process_monitoring(
         }
 
         println!("Process {} is no longer running", process_name_clone);
-        let final_playtime = {
+        let (final_playtime, session_playtime) = {
             let mut processes = processes_arc.lock().unwrap();
             if let Some(info) = processes.get_mut(&appid_clone) {
                 let session_time = info.start_time.elapsed().as_secs();
@@ -439,16 +471,20 @@ async fn start_process_monitoring(
                 let final_time = info.accumulated_time;
                 println!("Final playtime for {}: {} seconds (session: {} seconds)", 
                         appid_clone, final_time, session_time);
-                final_time
+                (final_time, session_time)
             } else {
-                0
+                (0, 0)
             }
         };
+
+        // Deferred notifications for this game can be shown now.
+        app_handle_clone
+            .state::<NotificationRules>()
+            .flush_deferred(&app_handle_clone, &appid_clone);
         
         tokio::spawn(async move {
-            let app_data_dir = app_handle_clone.path().app_data_dir().unwrap();
-            let playtime_file = app_data_dir.join("UnlockIt").join("playtimes.json");
-            if let Err(e) = save_playtime(&playtime_file, &appid_clone, final_playtime).await {
+            let db = app_handle_clone.state::<LibraryDb>();
+            if let Err(e) = save_playtime(&db, &appid_clone, final_playtime, session_playtime).await {
                 println!("Failed to save playtime: {}", e);
             } else {
                 println!("Successfully saved playtime: {} seconds", final_playtime);
@@ -493,95 +529,50 @@ async fn get_current_playtime(
 
 #[tauri::command]
 async fn stop_playtime_tracking(
-    app_handle: tauri::AppHandle,
     process_manager: State<'_, ProcessManager>,
+    db: State<'_, LibraryDb>,
     appid: String,
 ) -> Result<u64, String> {
     println!("Stopping playtime tracking for {}", appid);
 
-    let final_playtime = {
+    let (final_playtime, session_playtime) = {
         let mut processes = process_manager.processes.lock().unwrap();
         if let Some(info) = processes.get_mut(&appid) {
+            let mut session_time = 0;
             if info.is_running {
-                let session_time = info.start_time.elapsed().as_secs();
+                session_time = info.start_time.elapsed().as_secs();
                 info.accumulated_time += session_time;
                 info.is_running = false;
                 println!("Session time: {} seconds, Total: {} seconds", session_time, info.accumulated_time);
             }
-            info.accumulated_time
+            (info.accumulated_time, session_time)
         } else {
             return Err("No tracking session found for this app".to_string());
         }
     };
-    
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Could not get app data directory: {}", e))?;
 
-    let playtime_file = app_data_dir.join("UnlockIt").join("playtimes.json");
-    save_playtime(&playtime_file, &appid, final_playtime).await?;
+    save_playtime(&db, &appid, final_playtime, session_playtime).await?;
 
     Ok(final_playtime)
 }
 
-async fn load_playtime(file_path: &PathBuf, appid: &str) -> Result<u64, String> {
-    // Try different possible file locations
-    let possible_paths = vec![
-        file_path.clone(),
-        file_path.parent().unwrap_or(file_path).join("playtimes.json"),
-    ];
-    
-    for path in possible_paths {
-        if path.exists() {
-            match std::fs::read_to_string(&path) {
-                Ok(content) => {
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                        if let Some(playtime) = json.get(appid).and_then(|v| v.as_u64()) {
-                            println!("Loaded playtime {} for app {} from {:?}", playtime, appid, path);
-                            return Ok(playtime);
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("Failed to read playtime file {:?}: {}", path, e);
-                }
-            }
-        }
-    }
-    
-    println!("No existing playtime found for app {}", appid);
-    Ok(0)
+async fn load_playtime(db: &LibraryDb, appid: &str) -> Result<u64, String> {
+    let playtime = db.playtime(appid)?;
+    println!("Loaded playtime {} for app {}", playtime, appid);
+    Ok(playtime)
 }
 
-async fn save_playtime(file_path: &PathBuf, appid: &str, playtime: u64) -> Result<(), String> {
-    if let Some(parent) = file_path.parent() {
-        create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
-    
-    // Ensure we use the correct path structure
-    let corrected_path = if file_path.to_string_lossy().contains("Playtime") {
-        file_path.clone()
-    } else {
-        file_path.parent().unwrap_or(file_path).join("playtimes.json")
-    };
-    
-    let mut data = if corrected_path.exists() {
-        let content = std::fs::read_to_string(&corrected_path)
-            .map_err(|e| format!("Failed to read existing playtime file: {}", e))?;
-        serde_json::from_str::<serde_json::Value>(&content).unwrap_or(serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-    
-    data[appid] = serde_json::Value::Number(serde_json::Number::from(playtime));
-    let content = serde_json::to_string_pretty(&data)
-        .map_err(|e| format!("Failed to serialize playtime data: {}", e))?;
-
-    std::fs::write(&corrected_path, content)
-        .map_err(|e| format!("Failed to write playtime file: {}", e))?;
-
-    println!("Saved playtime {} for app {} to {:?}", playtime, appid, corrected_path);
+async fn save_playtime(
+    db: &LibraryDb,
+    appid: &str,
+    playtime: u64,
+    session_seconds: u64,
+) -> Result<(), String> {
+    db.record_playtime(appid, playtime, session_seconds)?;
+    println!(
+        "Saved playtime {} for app {} (session: {} seconds)",
+        playtime, appid, session_seconds
+    );
     Ok(())
 }
 
@@ -607,38 +598,6 @@ fn try_spawn_with_elevation(exe_path: &str) -> Result<std::process::Child, Strin
     }
 }
 
-fn is_process_running(process_name: &str) -> bool {
-    use sysinfo::{System, ProcessesToUpdate};
-    
-    let mut sys = System::new_all();
-    sys.refresh_processes(ProcessesToUpdate::All, true);
-    
-    let target_name = process_name.trim_end_matches(".exe").to_lowercase();
-    
-    for (_, process) in sys.processes() {
-        let proc_name = process.name().to_string_lossy().to_lowercase();
-        let proc_name_no_ext = proc_name.trim_end_matches(".exe");
-        
-        if proc_name == target_name || proc_name_no_ext == target_name {
-            return true;
-        }
-    }
-    
-    false
-}
-
-#[cfg(not(windows))]
-fn is_process_running(process_name: &str) -> bool {
-    let output = std::process::Command::new("pgrep")
-        .arg(process_name)
-        .output();
-
-    match output {
-        Ok(output) => output.status.success(),
-        Err(_) => false,
-    }
-}
-
 #[cfg(windows)]
 fn try_spawn_elevated(exe_path: &str) -> Result<std::process::Child, String> {
     let mut cmd = StdCommand::new("powershell");
@@ -654,292 +613,44 @@ fn try_spawn_elevated(exe_path: &str) -> Result<std::process::Child, String> {
 }
 
 #[cfg(not(windows))]
-fn try_spawn_elevated(exe_path: &str) -> Result<std::process::Child, String> {
+fn try_spawn_elevated(_exe_path: &str) -> Result<std::process::Child, String> {
     Err("Elevation not supported on this platform".to_string())
 }
-#[tauri::command]
-fn track_files(app_handle: tauri::AppHandle, paths: Vec<String>) -> Result<(), String> {
-    let valid_paths: Vec<String> = paths
-        .into_iter()
-        .filter(|path| {
-            let path_obj = Path::new(path);
-            if path_obj.exists() {
-                true
-            } else {
-                println!("Warning: Path does not exist, skipping: {}", path);
-                false
-            }
-        })
-        .collect();
-
-    if valid_paths.is_empty() {
-        return Err("No valid paths provided for tracking".to_string());
-    }
-    thread::spawn(move || {
-        let (tx, rx) = std_channel();
-        let config = NotifyConfig::default()
-            .with_compare_contents(true)
-            .with_poll_interval(Duration::from_secs(2));
-
-        let watcher_result: notify::Result<RecommendedWatcher> = RecommendedWatcher::new(
-            move |res| {
-                let _ = tx.send(res);
-            },
-            config,
-        );
-
-        let mut watcher = match watcher_result {
-            Ok(w) => w,
-            Err(e) => {
-                println!("Failed to create file watcher: {:?}", e);
-                return;
-            }
-        };
-        let file_contents = Arc::new(Mutex::new(HashMap::<String, String>::new()));
-        let last_event_time = Arc::new(Mutex::new(HashMap::<String, Instant>::new()));
-        let active_tasks = Arc::new(Mutex::new(HashMap::<String, bool>::new()));
-
-        let debounce_duration = Duration::from_millis(1000); // Increased to 1 second
-        for p in valid_paths.iter() {
-            let path = Path::new(p);
-            if let Err(e) = watcher.watch(path, RecursiveMode::NonRecursive) {
-                println!("Failed to watch path {}: {:?}", p, e);
-            } else {
-                println!("Watching: {}", p);
-                let initial_content = match std::fs::read_to_string(path) {
-                    Ok(content) => {
-                        println!("Read text content for {}: {} chars", p, content.len());
-                        content
-                    }
-                    Err(_) => {
-                        match std::fs::read(path) {
-                            Ok(bytes) => {
-                                println!("Read binary content for {}: {} bytes", p, bytes.len());
-                                String::from_utf8_lossy(&bytes).to_string()
-                            }
-                            Err(e) => {
-                                println!("Failed to read file {}: {:?}", p, e);
-                                String::new()
-                            }
-                        }
-                    }
-                };
-
-                let mut contents = file_contents.lock().unwrap();
-                contents.insert(p.clone(), initial_content);
-            }
-        }
-        loop {
-            match rx.recv() {
-                Ok(Ok(event)) => {
-                    let path = event
-                        .paths
-                        .get(0)
-                        .map(|p| p.to_string_lossy().to_string())
-                        .unwrap_or_default();
-
-                    println!(
-                        "🔥 FILE EVENT DETECTED: {:?} for path: {}",
-                        event.kind, path
-                    );
-                    {
-                        let mut tasks = active_tasks.lock().unwrap();
-                        if tasks.get(&path).unwrap_or(&false) == &true {
-                            println!("Skipping duplicate event for path: {}", path);
-                            continue;
-                        }
-                        tasks.insert(path.clone(), true);
-                    }
-                    {
-                        let mut times = last_event_time.lock().unwrap();
-                        times.insert(path.clone(), Instant::now());
-                    }
-                    let app_handle_clone = app_handle.clone();
-                    let path_clone = path.clone();
-                    let file_contents_clone = Arc::clone(&file_contents);
-                    let last_event_time_clone = Arc::clone(&last_event_time);
-                    let active_tasks_clone = Arc::clone(&active_tasks);
-
-                    thread::spawn(move || {
-                        thread::sleep(debounce_duration);
-                        let should_process = {
-                            let times = last_event_time_clone.lock().unwrap();
-                            times
-                                .get(&path_clone)
-                                .map(|&last_time| {
-                                    let elapsed = last_time.elapsed();
-                                    elapsed >= debounce_duration
-                                })
-                                .unwrap_or(false)
-                        };
-
-                        if should_process {
-                            println!("Processing file change for: {}", path_clone);
-                            let new_content = match std::fs::read_to_string(&path_clone) {
-                                Ok(content) => {
-                                    println!("Read text content: {} chars", content.len());
-                                    content
-                                }
-                                Err(_) => {
-                                    match std::fs::read(&path_clone) {
-                                        Ok(bytes) => {
-                                            println!("Read binary content: {} bytes", bytes.len());
-                                            String::from_utf8_lossy(&bytes).to_string()
-                                        }
-                                        Err(e) => {
-                                            println!("Failed to read file {}: {:?}", path_clone, e);
-                                            String::new()
-                                        }
-                                    }
-                                }
-                            };
-
-                            if !new_content.is_empty() {
-                                let old_content = {
-                                    let contents = file_contents_clone.lock().unwrap();
-                                    contents.get(&path_clone).cloned().unwrap_or_default()
-                                };
-
-                                println!(
-                                    "Old content length: {}, New content length: {}",
-                                    old_content.len(),
-                                    new_content.len()
-                                );
-                                if old_content != new_content {
-                                    let added_lines = get_added_lines(&old_content, &new_content);
-                                    {
-                                        let mut contents = file_contents_clone.lock().unwrap();
-                                        contents.insert(path_clone.clone(), new_content.clone());
-                                    }
-
-                                    println!(
-                                        "File changed: {}, added {} lines",
-                                        path_clone,
-                                        added_lines.len()
-                                    );
-                                    println!("Added lines: {:?}", added_lines);
-
-                                    let kind = "FileModified";
-                                    let payload = json!({
-                                        "path": path_clone,
-                                        "kind": kind,
-                                        "added_lines": added_lines,
-                                        "content": new_content
-                                    });
-
-                                    println!("Emitting file-change event...");
-                                    if let Err(e) = app_handle_clone.emit("file-change", payload) {
-                                        println!("Failed to emit file-change: {:?}", e);
-                                    } else {
-                                        println!("Successfully emitted file-change event!");
-                                    }
-                                } else {
-                                    println!("Content unchanged for: {}", path_clone);
-                                }
-                            } else {
-                                println!("Empty content read for: {}", path_clone);
-                            }
-                        } else {
-                            println!("Skipping processing (not latest event) for: {}", path_clone);
-                        }
-                        {
-                            let mut tasks = active_tasks_clone.lock().unwrap();
-                            tasks.insert(path_clone, false);
-                        }
-                    });
-                }
-                Ok(Err(e)) => {
-                    println!("Watcher error: {:?}", e);
-                }
-                Err(e) => {
-                    println!("Watcher channel receive error: {:?}", e);
-                    break;
-                }
-            }
-        }
-    });
-
-    Ok(())
-}
-fn get_added_lines(old_content: &str, new_content: &str) -> Vec<String> {
-    let old_lines: Vec<&str> = old_content.lines().collect();
-    let new_lines: Vec<&str> = new_content.lines().collect();
-
-    let mut added_lines = Vec::new();
-    for (line_num, new_line) in new_lines.iter().enumerate() {
-        let line_number = line_num + 1;
-        if line_num >= old_lines.len() {
-            added_lines.push(format!("Line {}: {}", line_number, new_line));
-        } else if old_lines[line_num] != *new_line {
-            if !old_lines.contains(new_line) {
-                added_lines.push(format!("Line {}: {}", line_number, new_line));
-            }
-        }
-    }
-    if new_lines.len() > old_lines.len() {
-        let diff_count = new_lines.len() - old_lines.len();
-        for i in 0..diff_count {
-            let potential_new_line_index = old_lines.len() + i;
-            if potential_new_line_index < new_lines.len() {
-                let line_number = potential_new_line_index + 1;
-                let line_content = new_lines[potential_new_line_index];
-                if !old_lines.contains(&line_content) {
-                    added_lines.push(format!("Line {}: {}", line_number, line_content));
-                }
-            }
-        }
-    }
-
-    added_lines
-}
 
 #[tauri::command]
 async fn toast_notification(
-    app_handle: tauri::AppHandle,
-    icon_path: String,
-    game_name: String,
-    achievement_name: String,
-    sound_path: Option<String>,
-    hero: Option<String>,
-    progress: Option<String>, // Format: "current/max" for Xbox-style progress tracking
-    is_rare: Option<bool>,    // Mark rare achievements for special styling
+    storage: State<'_, Storage>,
+    queue: State<'_, NotificationQueue>,
+    audio: State<'_, AudioPlayer>,
+    notification: ToastRequest,
 ) -> Result<(), String> {
-    use tauri_winrt_notification::{Duration, IconCrop, Toast};
-    if let Some(sound_filename) = sound_path {
-        if !sound_filename.is_empty() {
-            match app_handle
-                .path()
-                .resolve(&sound_filename, tauri::path::BaseDirectory::Resource)
-            {
-                Ok(sound_file_path) => {
-                    println!("Looking for sound file at: {:?}", sound_file_path);
-
-                    if sound_file_path.exists() {
-                        println!("Sound file found: {:?}", sound_file_path);
-                        play_custom_sound(&sound_file_path).await;
-                    } else {
-                        println!(
-                            "Sound file not found at: {:?}, using default sound",
-                            sound_file_path
-                        );
-                    }
-                }
-                Err(e) => {
-                    println!(
-                        "Failed to resolve sound file path: {}, using default sound",
-                        e
-                    );
-                }
-            }
-        }
-    }
+    let ToastRequest {
+        icon_path,
+        game_name,
+        achievement_name,
+        sound_path,
+        hero,
+        progress,
+        is_rare,
+        rarity,
+        use_overlay,
+        app_id,
+        achievement_id,
+    } = notification;
+    let toast_cache_dir = storage.toast_cache_dir();
     let processed_hero_path = if let Some(hero_path) = hero {
         if !hero_path.is_empty() && std::path::Path::new(&hero_path).exists() {
-            match process_hero_image(&hero_path, &app_handle).await {
+            match process_toast_image(
+                Path::new(&hero_path),
+                ToastImageTransform::HERO,
+                &toast_cache_dir,
+            )
+            .await
+            {
                 Ok(path) => Some(path),
                 Err(e) => {
-                    println!("Failed to process hero image: {}", e);
-                    None
+                    println!("Failed to process hero image, using original: {}", e);
+                    Some(PathBuf::from(&hero_path))
                 }
             }
         } else {
@@ -951,7 +662,13 @@ async fn toast_notification(
     };
     let processed_icon_path = if !icon_path.is_empty() && std::path::Path::new(&icon_path).exists()
     {
-        match process_icon_image(&icon_path, &app_handle).await {
+        match process_toast_image(
+            Path::new(&icon_path),
+            ToastImageTransform::ICON,
+            &toast_cache_dir,
+        )
+        .await
+        {
             Ok(processed_path) => {
                 println!(
                     "Successfully processed icon to 120x120: {:?}",
@@ -967,207 +684,42 @@ async fn toast_notification(
     } else {
         None
     };
-    let xbox_app_id = "Microsoft.XboxGamingOverlay_8wekyb3d8bbwe!App";
-    println!("Using Xbox Game Bar App ID for gaming toast: {}", xbox_app_id);
-    let description = if is_rare.unwrap_or(false) {
-        format!("💎 Rare Achievement Unlocked - {}", achievement_name)
-    } else {
-        format!("🏆 Achievement Unlocked - {}", achievement_name)
-    };
-    let achievement_text = if let Some(prog) = &progress {
-        if !prog.is_empty() {
-            if let Some((current, max)) = prog.split_once('/') {
-                if let (Ok(curr), Ok(total)) = (current.parse::<u32>(), max.parse::<u32>()) {
-                    let percent = (curr as f32 / total as f32 * 100.0) as u32;
-                    format!("{}\n📊 Progress: {}% ({}/{})", description, percent, curr, total)
-                } else {
-                    format!("{}\n📊 {}", description, prog)
-                }
-            } else {
-                format!("{}\n📊 {}", description, prog)
-            }
+    let rarity = rarity
+        .as_deref()
+        .and_then(RarityTier::parse)
+        .unwrap_or(if is_rare.unwrap_or(false) {
+            RarityTier::Rare
         } else {
-            description
-        }
-    } else {
-        description
+            RarityTier::Common
+        });
+    let app_id = app_id.filter(|id| !id.is_empty());
+    let sound = audio.resolve_sound(app_id.as_deref(), rarity, sound_path.as_deref());
+    let toast = AchievementToast {
+        game_name: game_name.clone(),
+        achievement_name: achievement_name.clone(),
+        icon: processed_icon_path,
+        hero: processed_hero_path,
+        progress: progress.filter(|p| !p.is_empty()),
+        rarity,
+        batched: Vec::new(),
     };
+    let queued = queue.enqueue(QueuedNotification {
+        app_id,
+        achievement_id: achievement_id.filter(|id| !id.is_empty()),
+        toast,
+        sound,
+        use_overlay,
+        digest: false,
+    })?;
 
-    let mut toast = Toast::new(xbox_app_id)
-        .title(&game_name) // Use game name as the title
-        .text1(&achievement_text) // Use formatted description as text1
-        .duration(Duration::Short) 
-        .sound(None); // Sound disabled
-    if let Some(hero_path) = processed_hero_path {
-        println!("Adding Xbox-style hero image: {:?}", hero_path);
-        toast = toast.hero(&hero_path, "Game Hero Image");
-    }
-    if let Some(icon_path_buf) = processed_icon_path {
-        println!("Adding Xbox-style achievement icon: {:?}", icon_path_buf);
-        toast = toast.icon(&icon_path_buf, IconCrop::Square, "Achievement Icon");
+    if queued {
+        println!(
+            "Queued notification for achievement: {} in game: {}",
+            achievement_name, game_name
+        );
     }
-    toast
-        .show()
-        .map_err(|e| format!("Failed to show Xbox-style toast notification: {}", e))?;
-
-    println!(
-        "🎮 Xbox-style toast notification shown for achievement: {} in game: {}{}",
-        achievement_name, 
-        game_name,
-        if is_rare.unwrap_or(false) { " (RARE!)" } else { "" }
-    );
     Ok(())
 }
-async fn process_icon_image(
-    icon_path: &str,
-    app_handle: &tauri::AppHandle,
-) -> Result<std::path::PathBuf, String> {
-    println!("🔍 ICON PROCESSING (Native Rust):");
-    println!("Input icon path: {}", icon_path);
-    println!(
-        "Icon file exists: {}",
-        std::path::Path::new(icon_path).exists()
-    );
-    let temp_dir = app_handle
-        .path()
-        .temp_dir()
-        .map_err(|e| format!("Failed to get temp directory: {}", e))?;
-
-    println!("Temp directory: {:?}", temp_dir);
-
-    let input_path = std::path::Path::new(icon_path);
-    let file_name = input_path
-        .file_stem()
-        .and_then(|name| name.to_str())
-        .unwrap_or("achievement_icon");
-
-    let output_path = temp_dir.join(format!("icon_120x120_{}.png", file_name));
-    println!("Output path will be: {:?}", output_path);
-    match image::open(icon_path) {
-        Ok(img) => {
-            println!("✅ Image loaded successfully");
-            println!("Original dimensions: {}x{}", img.width(), img.height());
-            let resized = img.resize_exact(120, 120, image::imageops::FilterType::Lanczos3);
-            println!("Resized to: {}x{}", resized.width(), resized.height());
-            match resized.save_with_format(&output_path, image::ImageFormat::Png) {
-                Ok(_) => {
-                    println!("✅ Successfully saved processed icon to: {:?}", output_path);
-
-                    if output_path.exists() {
-                        let metadata = std::fs::metadata(&output_path)
-                            .map_err(|e| format!("Failed to get output file metadata: {}", e))?;
-                        println!("Output file size: {} bytes", metadata.len());
-                        Ok(output_path)
-                    } else {
-                        Err("Output file was not created".to_string())
-                    }
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to save resized image: {}", e);
-                    println!("❌ {}", error_msg);
-                    Err(error_msg)
-                }
-            }
-        }
-        Err(e) => {
-            let error_msg = format!("Failed to open/decode image: {}", e);
-            println!("❌ {}", error_msg);
-            Err(error_msg)
-        }
-    }
-}
-async fn process_hero_image(
-    hero_path: &str,
-    app_handle: &tauri::AppHandle,
-) -> Result<std::path::PathBuf, String> {
-    println!("🔍 HERO IMAGE PROCESSING (Native Rust):");
-    println!("Input hero path: {}", hero_path);
-    let temp_dir = app_handle
-        .path()
-        .temp_dir()
-        .map_err(|e| format!("Failed to get temp directory: {}", e))?;
-
-    let input_path = std::path::Path::new(hero_path);
-    let file_name = input_path
-        .file_stem()
-        .and_then(|name| name.to_str())
-        .unwrap_or("hero_image");
-
-    let output_path = temp_dir.join(format!("processed_hero_{}.png", file_name));
-    println!("Output path will be: {:?}", output_path);
-    match image::open(hero_path) {
-        Ok(mut img) => {
-            println!("✅ Hero image loaded successfully");
-            println!("Original dimensions: {}x{}", img.width(), img.height());
-            img = img.blur(3.0);
-            img = img.brighten(-50); // Darken by reducing brightness
-            match img.save_with_format(&output_path, image::ImageFormat::Png) {
-                Ok(_) => {
-                    println!("✅ Successfully processed hero image to: {:?}", output_path);
-                    Ok(output_path)
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to save processed hero image: {}", e);
-                    println!("❌ {}", error_msg);
-                    std::fs::copy(hero_path, &output_path)
-                        .map_err(|e| format!("Failed to copy original hero image: {}", e))?;
-                    println!("📄 Used original hero image as fallback");
-                    Ok(output_path)
-                }
-            }
-        }
-        Err(e) => {
-            println!("❌ Failed to process hero image: {}", e);
-            std::fs::copy(hero_path, &output_path)
-                .map_err(|e| format!("Failed to copy original hero image: {}", e))?;
-            println!("📄 Used original hero image as fallback");
-            Ok(output_path)
-        }
-    }
-}
-#[cfg(windows)]
-async fn play_custom_sound(sound_path: &std::path::Path) {
-    use std::os::windows::ffi::OsStrExt;
-    let wide_path: Vec<u16> = sound_path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
-    let wide_path_clone = wide_path.clone();
-    tokio::spawn(async move {
-        unsafe {
-            #[link(name = "winmm")]
-            extern "system" {
-                fn PlaySoundW(
-                    psz_sound: *const u16,
-                    hmod: *const std::ffi::c_void,
-                    fd_sound: u32,
-                ) -> i32;
-            }
-
-            const SND_FILENAME: u32 = 0x00020000;
-            const SND_ASYNC: u32 = 0x00000001;
-            const SND_NODEFAULT: u32 = 0x00000002;
-
-            let result = PlaySoundW(
-                wide_path_clone.as_ptr(),
-                std::ptr::null(),
-                SND_FILENAME | SND_ASYNC | SND_NODEFAULT,
-            );
-
-            if result == 0 {
-                println!("Failed to play custom sound");
-            } else {
-                println!("Custom sound played successfully");
-            }
-        }
-    });
-}
-
-#[cfg(not(windows))]
-async fn play_custom_sound(_sound_path: &std::path::Path) {
-    println!("Custom sound playback not supported on this platform");
-}
 
 #[tauri::command]
 fn show_window(app_handle: tauri::AppHandle) -> Result<(), String> {
@@ -1715,6 +1267,7 @@ async fn search_game_wallpapers(game_name: String) -> Result<Vec<String>, String
 
 #[tauri::command]
 async fn download_and_save_wallpaper(
+    storage: State<'_, Storage>,
     image_url: String, 
     _game_name: String,
     app_id: String,
@@ -1745,14 +1298,7 @@ async fn download_and_save_wallpaper(
         .await
         .map_err(|e| format!("Failed to read image bytes: {}", e))?;
     
-    // Get app data directory
-    let app_data_dir = std::env::var("APPDATA")
-        .map_err(|_| "Failed to get APPDATA directory".to_string())?;
-    
-    let images_dir = Path::new(&app_data_dir)
-        .join("UnlockIt")
-        .join("images")
-        .join(&app_id);
+    let images_dir = storage.images_dir(&app_id);
     
     // Create directory if it doesn't exist
     fs::create_dir_all(&images_dir)
@@ -1886,6 +1432,7 @@ async fn search_game_covers(game_name: String) -> Result<Vec<String>, String> {
 
 #[tauri::command]
 async fn download_and_save_cover(
+    storage: State<'_, Storage>,
     image_url: String, 
     _game_name: String,
     app_id: String,
@@ -1916,13 +1463,7 @@ async fn download_and_save_cover(
         .await
         .map_err(|e| format!("Failed to read image bytes: {}", e))?;
     
-    let app_data_dir = std::env::var("APPDATA")
-        .map_err(|_| "Failed to get APPDATA directory".to_string())?;
-    
-    let images_dir = Path::new(&app_data_dir)
-        .join("UnlockIt")
-        .join("images")
-        .join(&app_id);
+    let images_dir = storage.images_dir(&app_id);
     
     fs::create_dir_all(&images_dir)
         .map_err(|e| format!("Failed to create images directory: {}", e))?;
@@ -1968,12 +1509,31 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(ProcessManager::new())
         .setup(|app| {
             use tauri::tray::TrayIconEvent;
             use tauri::menu::{Menu, MenuItem};
             
             println!("Setting up application with built-in tray...");
+
+            let storage = Storage::new(app.handle())?;
+            if let Err(e) = storage.migrate_legacy_images() {
+                println!("Failed to migrate legacy images: {}", e);
+            }
+            let notifications = NotificationCenter::for_platform(app.handle(), &storage);
+            let notification_queue = NotificationQueue::start(app.handle().clone(), &storage);
+            let audio = AudioPlayer::for_platform(app.handle(), &storage);
+            let notification_rules = NotificationRules::load(&storage);
+            let library_db = LibraryDb::open_for_app(&storage)?;
+            let file_watchers = FileWatchers::load(&storage);
+            app.manage(storage);
+            app.manage(notifications);
+            app.manage(notification_queue);
+            app.manage(audio);
+            app.manage(notification_rules);
+            app.manage(library_db);
+            app.manage(file_watchers);
             
             // Create tray menu
             let show_item = MenuItem::with_id(app, "show", "Show UnlockIt", true, None::<&str>)?;
@@ -2067,6 +1627,30 @@ pub fn run() {
             greet,
             fetch_achievements,
             store_achievements_by_appid,
+            db::get_achievements_state,
+            db::list_games,
+            db::get_game,
+            db::upsert_game,
+            db::delete_game,
+            db::list_achievements,
+            db::upsert_achievements,
+            db::list_sessions,
+            db::get_game_metadata,
+            db::set_game_metadata,
+            db::delete_game_metadata,
+            db::rarest_unlocks,
+            db::recent_unlocks,
+            backup::export_backup,
+            backup::import_backup,
+            unlock_export::export_unlocks,
+            importers::preview_import,
+            importers::apply_import,
+            launchers::discover_launcher_games,
+            steam_discovery::discover_installed_games,
+            emulators::detect_emulator,
+            emulators::decode_save_file,
+            emulators::resolve_valve_ini,
+            wine::resolve_wine_paths,
             fetch_game_metadata_from_steam,
             fetch_igdb_data,
             load_image,
@@ -2075,7 +1659,10 @@ pub fn run() {
             check_process_status,
             get_current_playtime,
             stop_playtime_tracking,
-            track_files,
+            file_watcher::track_files,
+            file_watcher::get_watch_status,
+            file_watcher::get_watcher_settings,
+            file_watcher::set_watcher_settings,
             toast_notification,
             show_window,
             hide_window,
@@ -2086,6 +1673,18 @@ pub fn run() {
             download_and_save_wallpaper,
             search_game_covers,
             download_and_save_cover,
+            gc::storage_report,
+            gc::gc_images,
+            notifications::get_overlay_settings,
+            notifications::set_overlay_settings,
+            notifications::get_notification_queue_settings,
+            notifications::set_notification_queue_settings,
+            notifications::get_notification_rules,
+            notifications::set_notification_rules,
+            audio::get_audio_settings,
+            audio::set_audio_settings,
+            audio::list_sound_packs,
+            audio::preview_sound,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
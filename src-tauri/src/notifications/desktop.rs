@@ -0,0 +1,39 @@
+use super::{AchievementToast, Notifier};
+use tauri_plugin_notification::NotificationExt;
+
+/// freedesktop (and macOS) notifications through `tauri-plugin-notification`.
+///
+/// These have no hero image slot, so the hero is only used as the icon when the
+/// achievement has none, and progress is rendered into the body text.
+pub struct DesktopNotifier {
+    app_handle: tauri::AppHandle,
+}
+
+impl DesktopNotifier {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn show(&self, toast: &AchievementToast) -> Result<(), String> {
+        let mut builder = self
+            .app_handle
+            .notification()
+            .builder()
+            .title(&toast.game_name)
+            .body(toast.body());
+
+        if let Some(icon) = toast.icon.as_ref().or(toast.hero.as_ref()) {
+            builder = builder.icon(icon.to_string_lossy().to_string());
+        }
+
+        builder
+            .show()
+            .map_err(|e| format!("Failed to show desktop notification: {}", e))
+    }
+}
@@ -0,0 +1,323 @@
+//! Achievement notifications behind a platform-neutral [`Notifier`] trait.
+//!
+//! `toast_notification` builds an [`AchievementToast`] and hands it to whichever
+//! backend the [`NotificationCenter`] was created with: WinRT toasts on Windows,
+//! freedesktop notifications (via `tauri-plugin-notification`) elsewhere, or the
+//! [`RecordingNotifier`] when running headless. When enabled in the overlay
+//! settings, toasts go to the in-app [`OverlayNotifier`] window instead.
+//!
+//! Toasts reach the center through the [`NotificationQueue`], which orders,
+//! rate limits, batches and de-duplicates bursts of unlocks. Before a toast is
+//! shown, the [`NotificationRules`] decide whether it is shown, silenced, dropped
+//! or deferred into a digest until the game exits.
+
+mod desktop;
+mod overlay;
+mod queue;
+mod recording;
+mod rules;
+#[cfg(windows)]
+mod winrt;
+
+pub use desktop::DesktopNotifier;
+pub use overlay::{OverlayNotifier, OverlaySettings, ScreenCorner};
+pub use queue::{NotificationQueue, QueueSettings, QueuedNotification};
+pub use recording::RecordingNotifier;
+pub use rules::{
+    NotificationRule, NotificationRules, RuleAction, RuleCondition, RuleContext, RulesSettings,
+};
+#[cfg(windows)]
+pub use winrt::WinRtNotifier;
+
+use crate::rarity::RarityTier;
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::State;
+
+const OVERLAY_SETTINGS: &str = "overlay";
+const QUEUE_SETTINGS: &str = "notification_queue";
+const RULES_SETTINGS: &str = "notification_rules";
+
+/// Arguments of the `toast_notification` command, as the frontend sends them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ToastRequest {
+    pub icon_path: String,
+    pub game_name: String,
+    pub achievement_name: String,
+    pub sound_path: Option<String>,
+    pub hero: Option<String>,
+    /// Format: "current/max" for Xbox-style progress tracking.
+    pub progress: Option<String>,
+    /// Mark rare achievements for special styling.
+    pub is_rare: Option<bool>,
+    /// Tier name ("Common".."Mythic"), overrides `is_rare`.
+    pub rarity: Option<String>,
+    /// Force (or skip) the in-app overlay window for this toast.
+    pub use_overlay: Option<bool>,
+    pub app_id: Option<String>,
+    /// API name, used to drop duplicate unlock toasts.
+    pub achievement_id: Option<String>,
+}
+
+/// Everything a backend needs to render one unlock.
+#[derive(Debug, Clone, Serialize)]
+pub struct AchievementToast {
+    pub game_name: String,
+    pub achievement_name: String,
+    /// Square achievement icon, already processed for toasts.
+    pub icon: Option<PathBuf>,
+    /// Wide background artwork, already blurred and darkened.
+    pub hero: Option<PathBuf>,
+    /// Raw progress string, usually `"current/max"`.
+    pub progress: Option<String>,
+    pub rarity: RarityTier,
+    /// Names of the achievements folded into this toast when it summarizes a burst.
+    pub batched: Vec<String>,
+}
+
+impl AchievementToast {
+    /// First line of the toast, e.g. `"💎 Epic Achievement Unlocked - Name"`.
+    pub fn headline(&self) -> String {
+        if self.batched.len() > 1 {
+            format!(
+                "🏆 {} achievements unlocked in {}",
+                self.batched.len(),
+                self.game_name
+            )
+        } else if self.rarity.is_rare() {
+            format!(
+                "💎 {} Achievement Unlocked - {}",
+                self.rarity.label(),
+                self.achievement_name
+            )
+        } else {
+            format!("🏆 Achievement Unlocked - {}", self.achievement_name)
+        }
+    }
+
+    /// Parsed `(current, max)` when the progress string has that shape.
+    pub fn progress_values(&self) -> Option<(u32, u32)> {
+        let (current, max) = self.progress.as_deref()?.split_once('/')?;
+        match (current.trim().parse::<u32>(), max.trim().parse::<u32>()) {
+            (Ok(current), Ok(max)) if max > 0 => Some((current, max)),
+            _ => None,
+        }
+    }
+
+    /// Human readable progress line for backends without a native progress bar.
+    pub fn progress_text(&self) -> Option<String> {
+        let progress = self.progress.as_deref().filter(|p| !p.is_empty())?;
+        Some(match self.progress_values() {
+            Some((current, max)) => format!(
+                "📊 Progress: {}% ({}/{})",
+                (current as f32 / max as f32 * 100.0) as u32,
+                current,
+                max
+            ),
+            None => format!("📊 {}", progress),
+        })
+    }
+
+    /// Headline plus progress (or the batched names), for backends that only have a
+    /// single body string.
+    pub fn body(&self) -> String {
+        if self.batched.len() > 1 {
+            return format!("{}\n{}", self.headline(), self.batched.join(", "));
+        }
+        match self.progress_text() {
+            Some(progress) => format!("{}\n{}", self.headline(), progress),
+            None => self.headline(),
+        }
+    }
+}
+
+pub trait Notifier: Send + Sync {
+    /// Short identifier used in logs and settings (`"winrt"`, `"desktop"`, ...).
+    fn name(&self) -> &'static str;
+
+    fn show(&self, toast: &AchievementToast) -> Result<(), String>;
+}
+
+impl NotificationRules {
+    pub fn load(storage: &Storage) -> Self {
+        Self::new(storage.load_settings(RULES_SETTINGS))
+    }
+}
+
+/// Managed state owning the OS notification backend and the overlay window.
+pub struct NotificationCenter {
+    notifier: Box<dyn Notifier>,
+    overlay: OverlayNotifier,
+}
+
+impl NotificationCenter {
+    pub fn new(notifier: Box<dyn Notifier>, overlay: OverlayNotifier) -> Self {
+        Self { notifier, overlay }
+    }
+
+    /// Picks the native backend for the current platform.
+    ///
+    /// `UNLOCKIT_NOTIFIER=recording|desktop` overrides the choice, which is handy for
+    /// headless runs and for exercising the freedesktop path on Windows.
+    pub fn for_platform(app_handle: &tauri::AppHandle, storage: &Storage) -> Self {
+        let requested = std::env::var("UNLOCKIT_NOTIFIER").unwrap_or_default();
+        let notifier: Box<dyn Notifier> = match requested.as_str() {
+            "recording" => Box::new(RecordingNotifier::default()),
+            "desktop" => Box::new(DesktopNotifier::new(app_handle.clone())),
+            _ => Self::native(app_handle),
+        };
+        println!("Using '{}' notification backend", notifier.name());
+
+        let overlay =
+            OverlayNotifier::new(app_handle.clone(), storage.load_settings(OVERLAY_SETTINGS));
+        Self::new(notifier, overlay)
+    }
+
+    #[cfg(windows)]
+    fn native(_app_handle: &tauri::AppHandle) -> Box<dyn Notifier> {
+        Box::new(WinRtNotifier::xbox_game_bar())
+    }
+
+    #[cfg(not(windows))]
+    fn native(app_handle: &tauri::AppHandle) -> Box<dyn Notifier> {
+        Box::new(DesktopNotifier::new(app_handle.clone()))
+    }
+
+    /// The backend `show` would use; `use_overlay` overrides the overlay setting.
+    fn backend(&self, use_overlay: Option<bool>) -> &dyn Notifier {
+        if use_overlay.unwrap_or_else(|| self.overlay.is_enabled()) {
+            &self.overlay
+        } else {
+            self.notifier.as_ref()
+        }
+    }
+
+    pub fn backend_name(&self, use_overlay: Option<bool>) -> &'static str {
+        self.backend(use_overlay).name()
+    }
+
+    pub fn show(&self, toast: &AchievementToast, use_overlay: Option<bool>) -> Result<(), String> {
+        self.backend(use_overlay).show(toast)
+    }
+}
+
+#[tauri::command]
+pub fn get_overlay_settings(notifications: State<'_, NotificationCenter>) -> OverlaySettings {
+    notifications.overlay.settings()
+}
+
+#[tauri::command]
+pub fn set_overlay_settings(
+    notifications: State<'_, NotificationCenter>,
+    storage: State<'_, Storage>,
+    settings: OverlaySettings,
+) -> Result<(), String> {
+    storage.save_settings(OVERLAY_SETTINGS, &settings)?;
+    notifications.overlay.set_settings(settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_notification_queue_settings(queue: State<'_, NotificationQueue>) -> QueueSettings {
+    queue.settings()
+}
+
+#[tauri::command]
+pub fn set_notification_queue_settings(
+    queue: State<'_, NotificationQueue>,
+    storage: State<'_, Storage>,
+    settings: QueueSettings,
+) -> Result<(), String> {
+    storage.save_settings(QUEUE_SETTINGS, &settings)?;
+    queue.set_settings(settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_notification_rules(rules: State<'_, NotificationRules>) -> RulesSettings {
+    rules.settings()
+}
+
+#[tauri::command]
+pub fn set_notification_rules(
+    rules: State<'_, NotificationRules>,
+    storage: State<'_, Storage>,
+    settings: RulesSettings,
+) -> Result<(), String> {
+    storage.save_settings(RULES_SETTINGS, &settings)?;
+    rules.set_settings(settings);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toast(achievement_name: &str, rarity: RarityTier) -> AchievementToast {
+        AchievementToast {
+            game_name: "Spacewar".to_string(),
+            achievement_name: achievement_name.to_string(),
+            icon: None,
+            hero: None,
+            progress: None,
+            rarity,
+            batched: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn recording_notifier_keeps_toasts_in_order() {
+        let notifier = RecordingNotifier::default();
+        let backend: &dyn Notifier = &notifier;
+        backend.show(&toast("First", RarityTier::Common)).unwrap();
+        backend.show(&toast("Second", RarityTier::Epic)).unwrap();
+
+        let names: Vec<String> = notifier
+            .recorded()
+            .into_iter()
+            .map(|toast| toast.achievement_name)
+            .collect();
+        assert_eq!(names, ["First", "Second"]);
+        assert_eq!(notifier.take().len(), 2);
+        assert!(notifier.recorded().is_empty());
+    }
+
+    #[test]
+    fn rarity_decides_the_headline() {
+        let notifier = RecordingNotifier::default();
+        notifier
+            .show(&toast("Common", RarityTier::from_percentage(92.0)))
+            .unwrap();
+        notifier
+            .show(&toast("Rare", RarityTier::from_percentage(5.0)))
+            .unwrap();
+
+        let headlines: Vec<String> = notifier.take().iter().map(|t| t.headline()).collect();
+        assert_eq!(
+            headlines,
+            [
+                "🏆 Achievement Unlocked - Common",
+                "💎 Legendary Achievement Unlocked - Rare",
+            ]
+        );
+    }
+
+    #[test]
+    fn body_carries_progress_or_batched_names() {
+        let mut progress = toast("Collector", RarityTier::Common);
+        progress.progress = Some("3/4".to_string());
+        assert_eq!(
+            progress.body(),
+            "🏆 Achievement Unlocked - Collector\n📊 Progress: 75% (3/4)"
+        );
+
+        let mut batched = toast("Collector", RarityTier::Common);
+        batched.batched = vec!["One".to_string(), "Two".to_string()];
+        assert_eq!(
+            batched.body(),
+            "🏆 2 achievements unlocked in Spacewar\nOne, Two"
+        );
+    }
+}
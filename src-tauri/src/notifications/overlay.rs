@@ -0,0 +1,297 @@
+use super::{AchievementToast, Notifier};
+use crate::rarity::RarityTier;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{Emitter, Listener, Manager};
+
+pub const OVERLAY_LABEL: &str = "achievement-overlay";
+
+/// Logical size of the overlay window; the card itself is laid out by `overlay.html`.
+const OVERLAY_WIDTH: f64 = 420.0;
+const OVERLAY_HEIGHT: f64 = 130.0;
+
+/// Time the page needs for the enter and exit animations around `duration_ms`.
+const ANIMATION_MS: u64 = 450;
+
+/// How long a freshly created window gets to load the page and report ready.
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// Render toasts in the overlay window instead of OS notifications.
+    pub enabled: bool,
+    pub corner: ScreenCorner,
+    /// How long each toast stays fully visible, excluding animations.
+    pub duration_ms: u64,
+    /// Distance from the screen edges in logical pixels.
+    pub margin: u32,
+    /// Animation name per tier, interpreted by the overlay page.
+    pub animations: BTreeMap<RarityTier, String>,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        let animations = RarityTier::ALL
+            .into_iter()
+            .map(|tier| {
+                let animation = match tier {
+                    RarityTier::Common | RarityTier::Uncommon => "slide",
+                    RarityTier::Rare | RarityTier::Epic => "glow",
+                    RarityTier::Legendary | RarityTier::Mythic => "burst",
+                };
+                (tier, animation.to_string())
+            })
+            .collect();
+
+        Self {
+            enabled: false,
+            corner: ScreenCorner::BottomRight,
+            duration_ms: 5000,
+            margin: 24,
+            animations,
+        }
+    }
+}
+
+/// What the overlay page receives for one toast.
+#[derive(Debug, Clone, Serialize)]
+struct OverlayPayload {
+    game_name: String,
+    achievement_name: String,
+    headline: String,
+    progress: Option<String>,
+    progress_percent: Option<u32>,
+    rarity: RarityTier,
+    icon: Option<String>,
+    hero: Option<String>,
+    animation: String,
+    duration_ms: u64,
+}
+
+#[derive(Default)]
+struct OverlayQueue {
+    pending: Mutex<VecDeque<AchievementToast>>,
+    wakeup: Condvar,
+}
+
+#[derive(Default)]
+struct ReadyFlag {
+    ready: Mutex<bool>,
+    changed: Condvar,
+}
+
+/// Renders toasts in a transparent, always-on-top, click-through webview window.
+///
+/// OS toasts are not drawn over fullscreen exclusive games; this window is. It is
+/// created on first use, hidden when the queue drains and reused afterwards.
+pub struct OverlayNotifier {
+    settings: Arc<Mutex<OverlaySettings>>,
+    queue: Arc<OverlayQueue>,
+}
+
+impl OverlayNotifier {
+    pub fn new(app_handle: tauri::AppHandle, settings: OverlaySettings) -> Self {
+        let settings = Arc::new(Mutex::new(settings));
+        let queue = Arc::new(OverlayQueue::default());
+
+        let ready = Arc::new(ReadyFlag::default());
+        let ready_clone = Arc::clone(&ready);
+        app_handle.listen("overlay-ready", move |_| {
+            *ready_clone.ready.lock().unwrap() = true;
+            ready_clone.changed.notify_all();
+        });
+
+        let settings_clone = Arc::clone(&settings);
+        let queue_clone = Arc::clone(&queue);
+        thread::spawn(move || run_overlay_queue(app_handle, settings_clone, queue_clone, ready));
+
+        Self { settings, queue }
+    }
+
+    pub fn settings(&self) -> OverlaySettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: OverlaySettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.lock().unwrap().enabled
+    }
+}
+
+impl Notifier for OverlayNotifier {
+    fn name(&self) -> &'static str {
+        "overlay"
+    }
+
+    fn show(&self, toast: &AchievementToast) -> Result<(), String> {
+        self.queue.pending.lock().unwrap().push_back(toast.clone());
+        self.queue.wakeup.notify_one();
+        Ok(())
+    }
+}
+
+fn run_overlay_queue(
+    app_handle: tauri::AppHandle,
+    settings: Arc<Mutex<OverlaySettings>>,
+    queue: Arc<OverlayQueue>,
+    ready: Arc<ReadyFlag>,
+) {
+    loop {
+        let toast = {
+            let mut pending = queue.pending.lock().unwrap();
+            while pending.is_empty() {
+                pending = queue.wakeup.wait(pending).unwrap();
+            }
+            pending.pop_front().unwrap()
+        };
+
+        let settings = settings.lock().unwrap().clone();
+        let window = match ensure_overlay_window(&app_handle, &ready) {
+            Ok(window) => window,
+            Err(e) => {
+                println!("Failed to prepare overlay window: {}", e);
+                continue;
+            }
+        };
+
+        if let Err(e) = position_overlay_window(&window, settings.corner, settings.margin) {
+            println!("Failed to position overlay window: {}", e);
+        }
+        let _ = window.show();
+        let _ = window.set_ignore_cursor_events(true);
+
+        let payload = OverlayPayload {
+            game_name: toast.game_name.clone(),
+            achievement_name: toast.achievement_name.clone(),
+            headline: toast.headline(),
+            progress: toast.progress_text(),
+            progress_percent: toast
+                .progress_values()
+                .map(|(current, max)| (current as f32 / max as f32 * 100.0) as u32),
+            rarity: toast.rarity,
+            icon: toast
+                .icon
+                .as_ref()
+                .and_then(|p| crate::image_data_url(p).ok()),
+            hero: toast
+                .hero
+                .as_ref()
+                .and_then(|p| crate::image_data_url(p).ok()),
+            animation: settings
+                .animations
+                .get(&toast.rarity)
+                .cloned()
+                .unwrap_or_else(|| "slide".to_string()),
+            duration_ms: settings.duration_ms,
+        };
+        if let Err(e) = app_handle.emit_to(OVERLAY_LABEL, "overlay-toast", payload) {
+            println!("Failed to emit overlay-toast: {:?}", e);
+            continue;
+        }
+
+        thread::sleep(Duration::from_millis(
+            settings.duration_ms + ANIMATION_MS * 2,
+        ));
+
+        if queue.pending.lock().unwrap().is_empty() {
+            let _ = window.hide();
+        }
+    }
+}
+
+fn ensure_overlay_window(
+    app_handle: &tauri::AppHandle,
+    ready: &ReadyFlag,
+) -> Result<tauri::WebviewWindow, String> {
+    if let Some(window) = app_handle.get_webview_window(OVERLAY_LABEL) {
+        return Ok(window);
+    }
+
+    *ready.ready.lock().unwrap() = false;
+    let window = tauri::WebviewWindowBuilder::new(
+        app_handle,
+        OVERLAY_LABEL,
+        tauri::WebviewUrl::App("overlay.html".into()),
+    )
+    .title("UnlockIt Overlay")
+    .transparent(true)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .resizable(false)
+    .focused(false)
+    .shadow(false)
+    .visible(false)
+    .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+    .build()
+    .map_err(|e| format!("Failed to create overlay window: {}", e))?;
+
+    window
+        .set_ignore_cursor_events(true)
+        .map_err(|e| format!("Failed to make overlay click-through: {}", e))?;
+
+    // Events sent before the page registered its listener would be lost.
+    let guard = ready.ready.lock().unwrap();
+    let (guard, timeout) = ready
+        .changed
+        .wait_timeout_while(guard, READY_TIMEOUT, |is_ready| !*is_ready)
+        .unwrap();
+    drop(guard);
+    if timeout.timed_out() {
+        println!("Overlay page did not report ready in time, continuing anyway");
+    }
+
+    Ok(window)
+}
+
+fn position_overlay_window(
+    window: &tauri::WebviewWindow,
+    corner: ScreenCorner,
+    margin: u32,
+) -> Result<(), String> {
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+        .ok_or("No monitor available")?;
+
+    let scale = monitor.scale_factor();
+    let monitor_size = monitor.size();
+    let monitor_position = monitor.position();
+    let width = (OVERLAY_WIDTH * scale) as i32;
+    let height = (OVERLAY_HEIGHT * scale) as i32;
+    let margin = (margin as f64 * scale) as i32;
+
+    let left = monitor_position.x + margin;
+    let right = monitor_position.x + monitor_size.width as i32 - width - margin;
+    let top = monitor_position.y + margin;
+    let bottom = monitor_position.y + monitor_size.height as i32 - height - margin;
+
+    let (x, y) = match corner {
+        ScreenCorner::TopLeft => (left, top),
+        ScreenCorner::TopRight => (right, top),
+        ScreenCorner::BottomLeft => (left, bottom),
+        ScreenCorner::BottomRight => (right, bottom),
+    };
+
+    window
+        .set_position(tauri::PhysicalPosition { x, y })
+        .map_err(|e| e.to_string())
+}
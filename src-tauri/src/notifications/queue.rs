@@ -0,0 +1,382 @@
+use super::rules::{NotificationRules, RuleAction, RuleContext};
+use super::{AchievementToast, NotificationCenter};
+use crate::audio::AudioPlayer;
+use crate::storage::Storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueSettings {
+    /// Minimum gap between two toasts, so they never collide on screen.
+    pub min_interval_ms: u64,
+    /// Unlocks arriving within this window of each other are considered one burst.
+    pub batch_window_ms: u64,
+    /// A burst of at least this many unlocks for one game becomes a single summary toast.
+    pub batch_threshold: usize,
+    /// The same appid/achievement pair is ignored if it was queued this recently.
+    pub dedupe_window_secs: u64,
+}
+
+impl Default for QueueSettings {
+    fn default() -> Self {
+        Self {
+            min_interval_ms: 1500,
+            batch_window_ms: 750,
+            batch_threshold: 3,
+            dedupe_window_secs: 60,
+        }
+    }
+}
+
+/// One `toast_notification` call waiting to be shown.
+#[derive(Debug, Clone)]
+pub struct QueuedNotification {
+    pub app_id: Option<String>,
+    pub achievement_id: Option<String>,
+    pub toast: AchievementToast,
+    /// Resolved sound file, played when the toast is actually shown.
+    pub sound: Option<PathBuf>,
+    pub use_overlay: Option<bool>,
+    /// Digest of notifications the rules deferred. It already went through the rules
+    /// once, so it is shown without evaluating them again.
+    pub digest: bool,
+}
+
+impl QueuedNotification {
+    /// Groups notifications of the same game; falls back to the name for games without an appid.
+    fn game_key(&self) -> String {
+        self.app_id
+            .clone()
+            .unwrap_or_else(|| self.toast.game_name.clone())
+    }
+
+    fn dedupe_key(&self) -> (String, String) {
+        (
+            self.game_key(),
+            self.achievement_id
+                .clone()
+                .unwrap_or_else(|| self.toast.achievement_name.clone()),
+        )
+    }
+}
+
+/// Orders, rate limits and batches toasts before they reach the [`NotificationCenter`].
+pub struct NotificationQueue {
+    sender: UnboundedSender<QueuedNotification>,
+    settings: Arc<Mutex<QueueSettings>>,
+    recent: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl NotificationQueue {
+    pub fn start(app_handle: tauri::AppHandle, storage: &Storage) -> Self {
+        let settings = Arc::new(Mutex::new(storage.load_settings(super::QUEUE_SETTINGS)));
+        let (sender, receiver) = unbounded_channel();
+
+        let settings_clone = Arc::clone(&settings);
+        tauri::async_runtime::spawn(async move {
+            run_dispatcher(app_handle, receiver, settings_clone).await;
+        });
+
+        Self::with_sender(sender, settings)
+    }
+
+    fn with_sender(
+        sender: UnboundedSender<QueuedNotification>,
+        settings: Arc<Mutex<QueueSettings>>,
+    ) -> Self {
+        Self {
+            sender,
+            settings,
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queues a toast. Returns `false` when it was dropped as a duplicate.
+    pub fn enqueue(&self, notification: QueuedNotification) -> Result<bool, String> {
+        let dedupe_window = Duration::from_secs(self.settings.lock().unwrap().dedupe_window_secs);
+        {
+            let mut recent = self.recent.lock().unwrap();
+            recent.retain(|_, queued_at| queued_at.elapsed() < dedupe_window);
+
+            let key = notification.dedupe_key();
+            if recent.contains_key(&key) {
+                println!(
+                    "Suppressing duplicate notification for {} / {}",
+                    key.0, key.1
+                );
+                return Ok(false);
+            }
+            recent.insert(key, Instant::now());
+        }
+
+        self.sender
+            .send(notification)
+            .map_err(|_| "Notification queue is not running".to_string())?;
+        Ok(true)
+    }
+
+    /// Queues notifications held back by the rules as a single digest toast,
+    /// bypassing de-duplication since each of them was already accepted once.
+    pub fn enqueue_digest(&self, notifications: Vec<QueuedNotification>) -> Result<(), String> {
+        if notifications.is_empty() {
+            return Ok(());
+        }
+        let mut digest = if notifications.len() == 1 {
+            notifications.into_iter().next().unwrap()
+        } else {
+            summarize(notifications)
+        };
+        digest.digest = true;
+        self.sender
+            .send(digest)
+            .map_err(|_| "Notification queue is not running".to_string())
+    }
+
+    pub fn settings(&self) -> QueueSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: QueueSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+}
+
+async fn run_dispatcher(
+    app_handle: tauri::AppHandle,
+    mut receiver: UnboundedReceiver<QueuedNotification>,
+    settings: Arc<Mutex<QueueSettings>>,
+) {
+    while let Some(first) = receiver.recv().await {
+        let settings = settings.lock().unwrap().clone();
+
+        // Collect the rest of the burst.
+        let mut burst = vec![first];
+        let deadline =
+            tokio::time::Instant::now() + Duration::from_millis(settings.batch_window_ms);
+        while let Ok(Some(next)) = tokio::time::timeout_at(deadline, receiver.recv()).await {
+            burst.push(next);
+        }
+
+        for group in group_by_game(burst) {
+            for notification in batch(group, settings.batch_threshold) {
+                dispatch(&app_handle, notification).await;
+                tokio::time::sleep(Duration::from_millis(settings.min_interval_ms)).await;
+            }
+        }
+    }
+}
+
+/// Splits a burst per game, keeping games in the order their first unlock arrived.
+fn group_by_game(burst: Vec<QueuedNotification>) -> Vec<Vec<QueuedNotification>> {
+    let mut groups: Vec<Vec<QueuedNotification>> = Vec::new();
+    for notification in burst {
+        let key = notification.game_key();
+        match groups.iter_mut().find(|group| group[0].game_key() == key) {
+            Some(group) => group.push(notification),
+            None => groups.push(vec![notification]),
+        }
+    }
+    groups
+}
+
+/// What is shown for one game's burst: a single summary once it reaches
+/// `batch_threshold` (never less than two unlocks), otherwise every toast.
+fn batch(group: Vec<QueuedNotification>, batch_threshold: usize) -> Vec<QueuedNotification> {
+    if group.len() >= batch_threshold.max(2) {
+        vec![summarize(group)]
+    } else {
+        group
+    }
+}
+
+/// Folds a burst for one game into a single "N achievements unlocked" toast,
+/// presented with the icon, rarity and sound of its rarest unlock.
+fn summarize(group: Vec<QueuedNotification>) -> QueuedNotification {
+    let rarest = group
+        .iter()
+        .max_by_key(|notification| notification.toast.rarity)
+        .cloned()
+        .unwrap_or_else(|| group[0].clone());
+
+    let mut summary = rarest.clone();
+    summary.achievement_id = None;
+    summary.toast.hero = group.iter().find_map(|n| n.toast.hero.clone());
+    summary.toast.progress = None;
+    summary.digest = group.iter().all(|n| n.digest);
+    summary.toast.batched = group
+        .iter()
+        .map(|n| n.toast.achievement_name.clone())
+        .collect();
+    summary.sound = rarest
+        .sound
+        .clone()
+        .or_else(|| group.iter().find_map(|n| n.sound.clone()));
+    summary
+}
+
+async fn dispatch(app_handle: &tauri::AppHandle, notification: QueuedNotification) {
+    let rules = app_handle.state::<NotificationRules>();
+    let action = if notification.digest {
+        RuleAction::Show
+    } else {
+        let context = RuleContext::capture(&app_handle.state::<crate::ProcessManager>());
+        rules.evaluate(&notification, &context)
+    };
+    match action {
+        RuleAction::Suppress => {
+            println!(
+                "Notification for {} suppressed by rules",
+                notification.toast.headline()
+            );
+            return;
+        }
+        RuleAction::Defer => {
+            rules.defer(notification);
+            return;
+        }
+        RuleAction::Show | RuleAction::Silent => {}
+    }
+
+    if let Some(sound) = notification
+        .sound
+        .as_ref()
+        .filter(|_| action == RuleAction::Show)
+    {
+        app_handle.state::<AudioPlayer>().play(sound);
+    }
+
+    let notifications = app_handle.state::<NotificationCenter>();
+    match notifications.show(&notification.toast, notification.use_overlay) {
+        Ok(()) => println!(
+            "🎮 {} notification shown for: {} in game: {}{}",
+            notifications.backend_name(notification.use_overlay),
+            notification.toast.headline(),
+            notification.toast.game_name,
+            if notification.toast.rarity.is_rare() {
+                " (RARE!)"
+            } else {
+                ""
+            }
+        ),
+        Err(e) => println!("Failed to show notification: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::{Notifier, RecordingNotifier};
+    use crate::rarity::RarityTier;
+
+    fn queued(app_id: &str, achievement_id: &str, rarity: RarityTier) -> QueuedNotification {
+        QueuedNotification {
+            app_id: Some(app_id.to_string()),
+            achievement_id: Some(achievement_id.to_string()),
+            toast: AchievementToast {
+                game_name: format!("Game {}", app_id),
+                achievement_name: achievement_id.to_string(),
+                icon: None,
+                hero: None,
+                progress: None,
+                rarity,
+                batched: Vec::new(),
+            },
+            sound: None,
+            use_overlay: None,
+            digest: false,
+        }
+    }
+
+    #[test]
+    fn bursts_are_grouped_per_game_and_summarized() {
+        let burst = vec![
+            queued("480", "ACH_A", RarityTier::Common),
+            queued("220", "ACH_X", RarityTier::Common),
+            queued("480", "ACH_B", RarityTier::Epic),
+            queued("480", "ACH_C", RarityTier::Uncommon),
+        ];
+        let groups = group_by_game(burst);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 3);
+        assert_eq!(groups[1].len(), 1);
+
+        let notifier = RecordingNotifier::default();
+        for group in groups {
+            for notification in batch(group, 2) {
+                notifier.show(&notification.toast).unwrap();
+            }
+        }
+
+        let shown = notifier.take();
+        assert_eq!(shown.len(), 2);
+        assert_eq!(shown[0].batched, ["ACH_A", "ACH_B", "ACH_C"]);
+        assert_eq!(shown[0].rarity, RarityTier::Epic);
+        assert_eq!(
+            shown[0].headline(),
+            "🏆 3 achievements unlocked in Game 480"
+        );
+        assert_eq!(shown[1].achievement_name, "ACH_X");
+    }
+
+    #[test]
+    fn batch_threshold_is_never_below_two() {
+        let group = || {
+            vec![
+                queued("480", "ACH_A", RarityTier::Common),
+                queued("480", "ACH_B", RarityTier::Common),
+                queued("480", "ACH_C", RarityTier::Common),
+            ]
+        };
+        assert_eq!(batch(group(), 3).len(), 1);
+        assert_eq!(batch(group(), 4).len(), 3);
+        assert_eq!(batch(group(), 0).len(), 1);
+
+        let single = batch(vec![queued("480", "ACH_A", RarityTier::Common)], 0);
+        assert_eq!(single.len(), 1);
+        assert!(single[0].toast.batched.is_empty());
+    }
+
+    #[test]
+    fn enqueue_drops_duplicates_within_the_window() {
+        let (sender, mut receiver) = unbounded_channel();
+        let queue =
+            NotificationQueue::with_sender(sender, Arc::new(Mutex::new(QueueSettings::default())));
+        assert_eq!(
+            queue.enqueue(queued("480", "ACH_A", RarityTier::Common)),
+            Ok(true)
+        );
+        assert_eq!(
+            queue.enqueue(queued("480", "ACH_A", RarityTier::Rare)),
+            Ok(false)
+        );
+        assert_eq!(
+            queue.enqueue(queued("480", "ACH_B", RarityTier::Common)),
+            Ok(true)
+        );
+        assert_eq!(
+            queue.enqueue(queued("220", "ACH_A", RarityTier::Common)),
+            Ok(true)
+        );
+
+        let mut received = Vec::new();
+        while let Ok(notification) = receiver.try_recv() {
+            received.push(notification.dedupe_key());
+        }
+        assert_eq!(received.len(), 3);
+
+        queue.set_settings(QueueSettings {
+            dedupe_window_secs: 0,
+            ..QueueSettings::default()
+        });
+        assert_eq!(
+            queue.enqueue(queued("480", "ACH_A", RarityTier::Common)),
+            Ok(true)
+        );
+    }
+}
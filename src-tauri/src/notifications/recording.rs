@@ -0,0 +1,35 @@
+use super::{AchievementToast, Notifier};
+use std::sync::Mutex;
+
+/// Keeps every toast in memory instead of showing it. Used for headless runs and tests.
+#[derive(Default)]
+pub struct RecordingNotifier {
+    shown: Mutex<Vec<AchievementToast>>,
+}
+
+impl RecordingNotifier {
+    /// Toasts shown so far, oldest first.
+    pub fn recorded(&self) -> Vec<AchievementToast> {
+        self.shown.lock().unwrap().clone()
+    }
+
+    /// Returns and clears the recorded toasts.
+    pub fn take(&self) -> Vec<AchievementToast> {
+        std::mem::take(&mut *self.shown.lock().unwrap())
+    }
+}
+
+impl Notifier for RecordingNotifier {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    fn show(&self, toast: &AchievementToast) -> Result<(), String> {
+        println!(
+            "Recorded notification: {} - {}",
+            toast.game_name, toast.achievement_name
+        );
+        self.shown.lock().unwrap().push(toast.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,424 @@
+use super::{NotificationQueue, QueuedNotification};
+use crate::rarity::RarityTier;
+use crate::ProcessManager;
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::Manager;
+
+/// What a matching rule does to a notification.
+///
+/// When several rules match, the most restrictive action wins, in declaration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Show,
+    /// Show the toast without playing a sound.
+    Silent,
+    /// Hold the toast and show it in a digest once the game's process exits.
+    Defer,
+    /// Drop the toast entirely.
+    Suppress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// Local time between `start` and `end` (`"HH:MM"`), wrapping past midnight.
+    QuietHours { start: String, end: String },
+    /// The achievement's tier is below `tier`, e.g. `Rare` keeps only Rare and above.
+    BelowRarity { tier: RarityTier },
+    /// The foreground window belongs to the tracked game `app_id`, or to any
+    /// tracked game when `app_id` is not set.
+    GameFocused { app_id: Option<String> },
+    /// The game that unlocked the achievement is being tracked by the `ProcessManager`.
+    GameRunning { app_id: Option<String> },
+    /// The OS reports a fullscreen app, presentation mode or quiet time (Windows only).
+    SystemBusy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRule {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: RuleCondition,
+    pub action: RuleAction,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesSettings {
+    pub rules: Vec<NotificationRule>,
+}
+
+/// State of the machine a rule is evaluated against.
+#[derive(Debug, Clone, Default)]
+pub struct RuleContext {
+    /// Minutes since local midnight.
+    pub minute_of_day: u32,
+    /// Appids whose processes are currently tracked as running.
+    pub running: Vec<String>,
+    /// Appid of the tracked game owning the foreground window, if any.
+    pub focused: Option<String>,
+    pub system_busy: bool,
+}
+
+impl RuleContext {
+    pub(crate) fn capture(processes: &ProcessManager) -> Self {
+        let now = chrono::Local::now();
+        let focused =
+            foreground_process_name().and_then(|name| processes.app_id_for_process(&name));
+
+        Self {
+            minute_of_day: now.hour() * 60 + now.minute(),
+            running: processes.running_app_ids(),
+            focused,
+            system_busy: system_busy(),
+        }
+    }
+}
+
+impl RuleCondition {
+    fn matches(&self, notification: &QueuedNotification, context: &RuleContext) -> bool {
+        let app_id = notification.app_id.as_deref();
+        match self {
+            Self::QuietHours { start, end } => match (parse_minutes(start), parse_minutes(end)) {
+                (Some(start), Some(end)) if start <= end => {
+                    context.minute_of_day >= start && context.minute_of_day < end
+                }
+                (Some(start), Some(end)) => {
+                    context.minute_of_day >= start || context.minute_of_day < end
+                }
+                _ => false,
+            },
+            Self::BelowRarity { tier } => notification.toast.rarity < *tier,
+            Self::GameFocused { app_id: wanted } => match (&context.focused, wanted) {
+                (Some(focused), Some(wanted)) => focused == wanted,
+                (Some(_), None) => true,
+                (None, _) => false,
+            },
+            Self::GameRunning { app_id: wanted } => {
+                let Some(app_id) = app_id else {
+                    return false;
+                };
+                wanted.as_deref().is_none_or(|wanted| wanted == app_id)
+                    && context.running.iter().any(|running| running == app_id)
+            }
+            Self::SystemBusy => context.system_busy,
+        }
+    }
+}
+
+fn parse_minutes(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Managed state deciding whether a notification is shown, silenced, deferred or dropped.
+#[derive(Default)]
+pub struct NotificationRules {
+    settings: Mutex<RulesSettings>,
+    /// Deferred notifications per appid, flushed when the game exits.
+    deferred: Mutex<HashMap<String, Vec<QueuedNotification>>>,
+}
+
+impl NotificationRules {
+    pub fn new(settings: RulesSettings) -> Self {
+        Self {
+            settings: Mutex::new(settings),
+            deferred: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn settings(&self) -> RulesSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: RulesSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    /// The most restrictive action among the enabled rules that match.
+    pub fn evaluate(&self, notification: &QueuedNotification, context: &RuleContext) -> RuleAction {
+        let action = self
+            .settings
+            .lock()
+            .unwrap()
+            .rules
+            .iter()
+            .filter(|rule| rule.enabled && rule.condition.matches(notification, context))
+            .map(|rule| rule.action)
+            .max()
+            .unwrap_or(RuleAction::Show);
+
+        // Deferred notifications are flushed when the game exits, so one that
+        // isn't running would never flush them.
+        let running = notification
+            .app_id
+            .as_ref()
+            .is_some_and(|app_id| context.running.contains(app_id));
+        if action == RuleAction::Defer && !running {
+            RuleAction::Show
+        } else {
+            action
+        }
+    }
+
+    pub fn defer(&self, notification: QueuedNotification) {
+        let Some(app_id) = notification.app_id.clone() else {
+            return;
+        };
+        println!(
+            "Deferring notification for {} until {} exits",
+            notification.toast.achievement_name, app_id
+        );
+        self.deferred
+            .lock()
+            .unwrap()
+            .entry(app_id)
+            .or_default()
+            .push(notification);
+    }
+
+    /// Sends everything deferred for `app_id` through the queue as one digest.
+    /// Called by the process monitors once the game has exited.
+    pub fn flush_deferred(&self, app_handle: &tauri::AppHandle, app_id: &str) {
+        let Some(deferred) = self.deferred.lock().unwrap().remove(app_id) else {
+            return;
+        };
+        println!(
+            "Flushing {} deferred notification(s) for {}",
+            deferred.len(),
+            app_id
+        );
+        if let Err(e) = app_handle
+            .state::<NotificationQueue>()
+            .enqueue_digest(deferred)
+        {
+            println!("Failed to flush deferred notifications: {}", e);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn foreground_process_name() -> Option<String> {
+    use sysinfo::{Pid, ProcessesToUpdate, System};
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> *mut std::ffi::c_void;
+        fn GetWindowThreadProcessId(hwnd: *mut std::ffi::c_void, process_id: *mut u32) -> u32;
+    }
+
+    let mut process_id = 0u32;
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        GetWindowThreadProcessId(hwnd, &mut process_id);
+    }
+    if process_id == 0 {
+        return None;
+    }
+
+    let pid = Pid::from_u32(process_id);
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    sys.process(pid)
+        .map(|process| process.name().to_string_lossy().to_string())
+}
+
+#[cfg(not(windows))]
+fn foreground_process_name() -> Option<String> {
+    None
+}
+
+#[cfg(windows)]
+fn system_busy() -> bool {
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+
+    const QUNS_BUSY: i32 = 2;
+    const QUNS_RUNNING_D3D_FULL_SCREEN: i32 = 3;
+    const QUNS_PRESENTATION_MODE: i32 = 4;
+    const QUNS_QUIET_TIME: i32 = 6;
+
+    let mut state = 0i32;
+    let result = unsafe { SHQueryUserNotificationState(&mut state) };
+    result == 0
+        && matches!(
+            state,
+            QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME
+        )
+}
+
+#[cfg(not(windows))]
+fn system_busy() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::AchievementToast;
+
+    fn notification(app_id: Option<&str>, rarity: RarityTier) -> QueuedNotification {
+        QueuedNotification {
+            app_id: app_id.map(str::to_string),
+            achievement_id: Some("ACH_WIN_ONE_GAME".to_string()),
+            toast: AchievementToast {
+                game_name: "Spacewar".to_string(),
+                achievement_name: "Winner".to_string(),
+                icon: None,
+                hero: None,
+                progress: None,
+                rarity,
+                batched: Vec::new(),
+            },
+            sound: None,
+            use_overlay: None,
+            digest: false,
+        }
+    }
+
+    fn rule(condition: RuleCondition, action: RuleAction) -> NotificationRule {
+        NotificationRule {
+            enabled: true,
+            condition,
+            action,
+        }
+    }
+
+    fn rules(rules: Vec<NotificationRule>) -> NotificationRules {
+        NotificationRules::new(RulesSettings { rules })
+    }
+
+    fn at(hour: u32, minute: u32) -> RuleContext {
+        RuleContext {
+            minute_of_day: hour * 60 + minute,
+            ..RuleContext::default()
+        }
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let rules = rules(vec![rule(
+            RuleCondition::QuietHours {
+                start: "22:00".to_string(),
+                end: "07:30".to_string(),
+            },
+            RuleAction::Silent,
+        )]);
+        let toast = notification(Some("480"), RarityTier::Common);
+        assert_eq!(rules.evaluate(&toast, &at(23, 15)), RuleAction::Silent);
+        assert_eq!(rules.evaluate(&toast, &at(7, 0)), RuleAction::Silent);
+        assert_eq!(rules.evaluate(&toast, &at(7, 30)), RuleAction::Show);
+        assert_eq!(rules.evaluate(&toast, &at(12, 0)), RuleAction::Show);
+    }
+
+    #[test]
+    fn most_restrictive_matching_rule_wins() {
+        let mut disabled = rule(RuleCondition::SystemBusy, RuleAction::Suppress);
+        disabled.enabled = false;
+        let rules = rules(vec![
+            rule(
+                RuleCondition::BelowRarity {
+                    tier: RarityTier::Rare,
+                },
+                RuleAction::Silent,
+            ),
+            rule(
+                RuleCondition::GameRunning { app_id: None },
+                RuleAction::Defer,
+            ),
+            disabled,
+        ]);
+        let context = RuleContext {
+            running: vec!["480".to_string()],
+            system_busy: true,
+            ..RuleContext::default()
+        };
+
+        let common = notification(Some("480"), RarityTier::Common);
+        assert_eq!(rules.evaluate(&common, &context), RuleAction::Defer);
+        let rare = notification(Some("220"), RarityTier::Epic);
+        assert_eq!(rules.evaluate(&rare, &context), RuleAction::Show);
+        let uncommon = notification(Some("220"), RarityTier::Uncommon);
+        assert_eq!(rules.evaluate(&uncommon, &context), RuleAction::Silent);
+    }
+
+    #[test]
+    fn defer_needs_an_app_id() {
+        let rules = rules(vec![rule(
+            RuleCondition::QuietHours {
+                start: "00:00".to_string(),
+                end: "23:59".to_string(),
+            },
+            RuleAction::Defer,
+        )]);
+        let context = RuleContext {
+            running: vec!["480".to_string()],
+            ..at(12, 0)
+        };
+        assert_eq!(
+            rules.evaluate(&notification(Some("480"), RarityTier::Common), &context),
+            RuleAction::Defer
+        );
+        assert_eq!(
+            rules.evaluate(&notification(None, RarityTier::Common), &context),
+            RuleAction::Show
+        );
+    }
+
+    #[test]
+    fn defer_needs_the_game_running() {
+        let rules = rules(vec![rule(RuleCondition::SystemBusy, RuleAction::Defer)]);
+        let context = RuleContext {
+            running: vec!["480".to_string()],
+            system_busy: true,
+            ..RuleContext::default()
+        };
+        assert_eq!(
+            rules.evaluate(&notification(Some("480"), RarityTier::Common), &context),
+            RuleAction::Defer
+        );
+        assert_eq!(
+            rules.evaluate(&notification(Some("220"), RarityTier::Common), &context),
+            RuleAction::Show
+        );
+    }
+
+    #[test]
+    fn focused_game_matches_by_app_id() {
+        let rules = rules(vec![rule(
+            RuleCondition::GameFocused {
+                app_id: Some("480".to_string()),
+            },
+            RuleAction::Suppress,
+        )]);
+        let focused = |app_id: &str| RuleContext {
+            focused: Some(app_id.to_string()),
+            ..RuleContext::default()
+        };
+        let toast = notification(Some("220"), RarityTier::Common);
+        assert_eq!(
+            rules.evaluate(&toast, &focused("480")),
+            RuleAction::Suppress
+        );
+        assert_eq!(rules.evaluate(&toast, &focused("220")), RuleAction::Show);
+        assert_eq!(
+            rules.evaluate(&toast, &RuleContext::default()),
+            RuleAction::Show
+        );
+    }
+}
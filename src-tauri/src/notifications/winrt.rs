@@ -0,0 +1,53 @@
+use super::{AchievementToast, Notifier};
+use tauri_winrt_notification::{Duration, IconCrop, Toast};
+
+/// Native Windows toasts, posted under another app's AUMID so they get its styling.
+pub struct WinRtNotifier {
+    app_id: String,
+}
+
+impl WinRtNotifier {
+    /// Posts as the Xbox Game Bar, which gives toasts the gaming overlay look.
+    pub fn xbox_game_bar() -> Self {
+        Self {
+            app_id: "Microsoft.XboxGamingOverlay_8wekyb3d8bbwe!App".to_string(),
+        }
+    }
+}
+
+impl Notifier for WinRtNotifier {
+    fn name(&self) -> &'static str {
+        "winrt"
+    }
+
+    fn show(&self, toast: &AchievementToast) -> Result<(), String> {
+        println!(
+            "Using Xbox Game Bar App ID for gaming toast: {}",
+            self.app_id
+        );
+
+        let body = toast.body();
+        let mut winrt_toast = Toast::new(&self.app_id)
+            .title(&toast.game_name)
+            .text1(&body)
+            // Rarer unlocks stay on screen longer.
+            .duration(if toast.rarity.is_rare() {
+                Duration::Long
+            } else {
+                Duration::Short
+            })
+            .sound(None); // Sound is played by UnlockIt itself
+        if let Some(hero_path) = &toast.hero {
+            println!("Adding Xbox-style hero image: {:?}", hero_path);
+            winrt_toast = winrt_toast.hero(hero_path, "Game Hero Image");
+        }
+        if let Some(icon_path) = &toast.icon {
+            println!("Adding Xbox-style achievement icon: {:?}", icon_path);
+            winrt_toast = winrt_toast.icon(icon_path, IconCrop::Square, "Achievement Icon");
+        }
+
+        winrt_toast
+            .show()
+            .map_err(|e| format!("Failed to show Xbox-style toast notification: {}", e))
+    }
+}
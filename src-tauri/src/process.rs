@@ -0,0 +1,129 @@
+//! Finds running games by executable name.
+//!
+//! On Linux, games under Wine or Proton do not run under their own name: the
+//! process is `wine64-preloader` (or `wine-preloader`) and the Windows exe only
+//! appears in `/proc/<pid>/cmdline`, often as a `Z:\...` or `C:\...` path. The Linux
+//! backend therefore checks the process name, the `exe` link and every cmdline
+//! argument. Other platforms go through sysinfo.
+
+/// Lowercase file name of a Windows or Unix path, without an `.exe` extension.
+fn exe_stem(path: &str) -> String {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path).trim();
+    let lower = name.to_lowercase();
+    match lower.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => lower,
+    }
+}
+
+/// `/proc/<pid>/comm` keeps only the first 15 bytes of the name.
+#[cfg(any(target_os = "linux", test))]
+const COMM_LEN: usize = 15;
+
+/// Whether a lowercase `comm` is `target`, or the truncated form of its name.
+#[cfg(any(target_os = "linux", test))]
+fn comm_matches(comm: &str, target: &str) -> bool {
+    let with_exe = format!("{}.exe", target);
+    exe_stem(comm) == target
+        || [target, with_exe.as_str()].iter().any(|name| {
+            name.chars().count() > COMM_LEN && name.chars().take(COMM_LEN).eq(comm.chars())
+        })
+}
+
+/// Whether any NUL-separated argument of a `/proc/<pid>/cmdline` is the `target` exe.
+#[cfg(any(target_os = "linux", test))]
+fn cmdline_matches(cmdline: &[u8], target: &str) -> bool {
+    cmdline
+        .split(|b| *b == 0)
+        .map(String::from_utf8_lossy)
+        .any(|arg| !arg.is_empty() && exe_stem(&arg) == target)
+}
+
+#[cfg(target_os = "linux")]
+fn linux_process_running(target: &str) -> Option<bool> {
+    let entries = std::fs::read_dir("/proc").ok()?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !name.to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let dir = entry.path();
+
+        if let Ok(comm) = std::fs::read_to_string(dir.join("comm")) {
+            if comm_matches(&comm.trim_end().to_lowercase(), target) {
+                return Some(true);
+            }
+        }
+        if let Ok(exe) = std::fs::read_link(dir.join("exe")) {
+            if exe_stem(&exe.to_string_lossy()) == target {
+                return Some(true);
+            }
+        }
+        // Wine rewrites argv so the Windows exe path is one of the arguments.
+        if let Ok(cmdline) = std::fs::read(dir.join("cmdline")) {
+            if cmdline_matches(&cmdline, target) {
+                return Some(true);
+            }
+        }
+    }
+    Some(false)
+}
+
+fn sysinfo_process_running(target: &str) -> bool {
+    use sysinfo::{ProcessesToUpdate, System};
+
+    let mut sys = System::new_all();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    sys.processes()
+        .values()
+        .any(|process| exe_stem(&process.name().to_string_lossy()) == target)
+}
+
+/// Whether a process for `process_name` (`Game.exe` or `Game`) is running.
+pub fn is_process_running(process_name: &str) -> bool {
+    let target = exe_stem(process_name);
+    if target.is_empty() {
+        return false;
+    }
+
+    #[cfg(target_os = "linux")]
+    if let Some(running) = linux_process_running(&target) {
+        return running;
+    }
+
+    sysinfo_process_running(&target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exe_stem_handles_windows_and_unix_paths() {
+        assert_eq!(exe_stem(r"Z:\home\me\Games\Hades\Game.exe"), "game");
+        assert_eq!(exe_stem(r"C:\Program Files\Hades\Hades.EXE"), "hades");
+        assert_eq!(exe_stem("/usr/bin/wine64-preloader"), "wine64-preloader");
+        assert_eq!(exe_stem("Game.exe "), "game");
+        assert_eq!(exe_stem("launcher.exe.bak"), "launcher.exe.bak");
+        assert_eq!(exe_stem(r"C:\Games\"), "");
+    }
+
+    #[test]
+    fn cmdline_matches_the_windows_exe_argument() {
+        let wine = b"/usr/bin/wine64-preloader\0Z:\\home\\me\\Games\\Game.exe\0-windowed\0";
+        assert!(cmdline_matches(wine, "game"));
+        assert!(!cmdline_matches(wine, "windowed"));
+        assert!(cmdline_matches(b"C:\\Games\\Hades.exe\0", "hades"));
+        assert!(!cmdline_matches(b"\0\0", ""));
+        assert!(!cmdline_matches(b"/usr/bin/gamescope\0--\0steam\0", "game"));
+    }
+
+    #[test]
+    fn comm_matches_names_truncated_by_the_kernel() {
+        assert!(comm_matches("game.exe", "game"));
+        assert!(comm_matches("averylongexecut", "averylongexecutablename"));
+        assert!(comm_matches("averylonggame.e", "averylonggame"));
+        assert!(!comm_matches("averylongexecut", "averylongexe"));
+        assert!(!comm_matches("wine64-preloade", "game"));
+    }
+}
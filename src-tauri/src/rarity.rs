@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Rarity tiers derived from the global unlock percentage.
+///
+/// The thresholds match `calculateAchievementTier` in the frontend scoring workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RarityTier {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+    Mythic,
+}
+
+impl RarityTier {
+    pub const ALL: [RarityTier; 6] = [
+        RarityTier::Common,
+        RarityTier::Uncommon,
+        RarityTier::Rare,
+        RarityTier::Epic,
+        RarityTier::Legendary,
+        RarityTier::Mythic,
+    ];
+
+    pub fn from_percentage(percentage: f64) -> Self {
+        if percentage >= 80.0 {
+            RarityTier::Common
+        } else if percentage >= 50.0 {
+            RarityTier::Uncommon
+        } else if percentage >= 25.0 {
+            RarityTier::Rare
+        } else if percentage >= 10.0 {
+            RarityTier::Epic
+        } else if percentage >= 3.0 {
+            RarityTier::Legendary
+        } else {
+            RarityTier::Mythic
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|tier| tier.label().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RarityTier::Common => "Common",
+            RarityTier::Uncommon => "Uncommon",
+            RarityTier::Rare => "Rare",
+            RarityTier::Epic => "Epic",
+            RarityTier::Legendary => "Legendary",
+            RarityTier::Mythic => "Mythic",
+        }
+    }
+
+    pub fn is_rare(&self) -> bool {
+        *self >= RarityTier::Rare
+    }
+}
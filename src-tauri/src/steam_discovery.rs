@@ -0,0 +1,368 @@
+//! Finds games installed through Steam: locates Steam, reads the library list from
+//! `steamapps/libraryfolders.vdf` and each game from its `appmanifest_<appid>.acf`.
+
+use crate::db::LibraryDb;
+use crate::vdf::Vdf;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Steam's own runtimes and redistributables, which also get app manifests.
+const TOOL_NAME_PREFIXES: [&str; 4] = [
+    "Proton",
+    "Steam Linux Runtime",
+    "Steamworks Common Redistributables",
+    "SteamVR",
+];
+
+/// Executables that ship next to games but never are the game, matched against the
+/// start of the lowercase file stem so games like "Crash Bandicoot" still count.
+const IGNORED_EXE_PREFIXES: [&str; 13] = [
+    "unins",
+    "crashreport",
+    "crashhandler",
+    "unitycrashhandler",
+    "dxsetup",
+    "dxwebsetup",
+    "vc_redist",
+    "vcredist",
+    "dotnet",
+    "ue4prereq",
+    "ueprereq",
+    "easyanticheat",
+    "oalinst",
+];
+
+/// Stems ignored only as a whole name.
+const IGNORED_EXE_STEMS: [&str; 4] = ["setup", "install", "installer", "prereqsetup"];
+
+const IGNORED_DIRS: [&str; 6] = [
+    "_commonredist",
+    "redist",
+    "directx",
+    "__installer",
+    "support",
+    "easyanticheat",
+];
+
+const MAX_EXE_DEPTH: usize = 3;
+const MAX_EXECUTABLES: usize = 8;
+
+/// `StateFlags` bit set once an app is fully installed.
+const STATE_FULLY_INSTALLED: u64 = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledGame {
+    pub app_id: String,
+    pub name: String,
+    pub install_dir: String,
+    pub library_dir: String,
+    pub size_on_disk: Option<u64>,
+    pub last_updated: Option<i64>,
+    /// False while an install or update is still in progress.
+    pub fully_installed: bool,
+    /// Likely game executables, best match first.
+    pub executables: Vec<String>,
+    /// Already added to the UnlockIt library.
+    pub in_library: bool,
+}
+
+#[cfg(not(windows))]
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+#[cfg(windows)]
+fn registry_value(key: &str, value: &str) -> Option<PathBuf> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = std::process::Command::new("reg")
+        .args(["query", key, "/v", value])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().find_map(|line| {
+        let (_, data) = line.split_once("REG_SZ")?;
+        Some(PathBuf::from(data.trim()))
+    })
+}
+
+/// Candidate Steam install directories for this platform, most likely first.
+fn steam_root_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    #[cfg(windows)]
+    {
+        candidates.extend(registry_value(r"HKCU\Software\Valve\Steam", "SteamPath"));
+        candidates.extend(registry_value(
+            r"HKLM\SOFTWARE\WOW6432Node\Valve\Steam",
+            "InstallPath",
+        ));
+        candidates.extend(registry_value(r"HKLM\SOFTWARE\Valve\Steam", "InstallPath"));
+        candidates.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+        candidates.push(PathBuf::from(r"C:\Program Files\Steam"));
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Some(home) = home_dir() {
+        candidates.push(home.join("Library/Application Support/Steam"));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    if let Some(home) = home_dir() {
+        candidates.extend([
+            home.join(".steam/steam"),
+            home.join(".steam/root"),
+            home.join(".local/share/Steam"),
+            // Flatpak and Snap installs.
+            home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+            home.join("snap/steam/common/.local/share/Steam"),
+        ]);
+    }
+
+    candidates
+}
+
+/// Steam installs present on this machine, without duplicates from symlinks.
+pub fn locate_steam() -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    steam_root_candidates()
+        .into_iter()
+        .filter(|root| root.join("steamapps").is_dir())
+        .filter(|root| seen.insert(root.canonicalize().unwrap_or_else(|_| root.clone())))
+        .collect()
+}
+
+/// Library folders registered with the Steam install at `root`, including `root`.
+pub fn library_folders(root: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![root.to_path_buf()];
+    let config = root.join("steamapps").join("libraryfolders.vdf");
+    let vdf = match Vdf::read(&config) {
+        Ok(vdf) => vdf,
+        Err(e) => {
+            if config.exists() {
+                println!("{}", e);
+            }
+            return folders;
+        }
+    };
+
+    let Some(entries) = vdf.get("libraryfolders") else {
+        return folders;
+    };
+    for (key, entry) in entries.entries() {
+        if !key.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        // Current clients write an object with "path"; older ones the path itself.
+        let path = match entry {
+            Vdf::Str(path) => Some(path.as_str()),
+            Vdf::Obj(_) => entry.get_str("path"),
+        };
+        if let Some(path) = path.filter(|p| !p.is_empty()) {
+            folders.push(PathBuf::from(path));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    folders.retain(|folder| seen.insert(folder.canonicalize().unwrap_or_else(|_| folder.clone())));
+    folders
+}
+
+/// Reads one `appmanifest_<appid>.acf`. Steam tools and runtimes are skipped.
+pub fn read_app_manifest(manifest: &Path, library: &Path) -> Result<Option<InstalledGame>, String> {
+    let vdf = Vdf::read(manifest)?;
+    let state = vdf
+        .get("AppState")
+        .ok_or_else(|| format!("{} has no AppState", manifest.display()))?;
+
+    let (Some(app_id), Some(install_dir)) = (state.get_str("appid"), state.get_str("installdir"))
+    else {
+        return Ok(None);
+    };
+    let name = state.get_str("name").unwrap_or(install_dir).to_string();
+    if TOOL_NAME_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        return Ok(None);
+    }
+
+    let install_path = library.join("steamapps").join("common").join(install_dir);
+    let state_flags: u64 = state
+        .get_str("StateFlags")
+        .and_then(|f| f.parse().ok())
+        .unwrap_or(0);
+
+    Ok(Some(InstalledGame {
+        app_id: app_id.to_string(),
+        executables: find_executables(&install_path, &name),
+        install_dir: install_path.to_string_lossy().into_owned(),
+        library_dir: library.to_string_lossy().into_owned(),
+        size_on_disk: state.get_str("SizeOnDisk").and_then(|s| s.parse().ok()),
+        last_updated: state
+            .get_str("LastUpdated")
+            .and_then(|s| s.parse().ok())
+            .filter(|t| *t > 0),
+        fully_installed: state_flags & STATE_FULLY_INSTALLED != 0,
+        name,
+        in_library: false,
+    }))
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_executable(path: &Path) -> bool {
+    let is_exe = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+    #[cfg(unix)]
+    let is_exe = is_exe || {
+        use std::os::unix::fs::PermissionsExt;
+        path.extension().is_none()
+            && path
+                .metadata()
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    };
+    is_exe
+}
+
+fn is_ignored_exe(path: &Path) -> bool {
+    let stem = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase();
+    IGNORED_EXE_PREFIXES
+        .iter()
+        .any(|prefix| stem.starts_with(prefix))
+        || IGNORED_EXE_STEMS.contains(&stem.as_str())
+}
+
+/// Likely game executables under `dir`, ranked by how well the file name matches
+/// the game name, then by how shallow and how large the file is.
+pub fn find_executables(dir: &Path, game_name: &str) -> Vec<String> {
+    let mut found: Vec<(PathBuf, usize, u64)> = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+    while let Some((current, depth)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let lower = entry.file_name().to_string_lossy().to_lowercase();
+            if path.is_dir() {
+                if depth < MAX_EXE_DEPTH && !IGNORED_DIRS.contains(&lower.as_str()) {
+                    pending.push((path, depth + 1));
+                }
+            } else if is_executable(&path) && !is_ignored_exe(&path) {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                found.push((path, depth, size));
+            }
+        }
+    }
+
+    let game = normalize(game_name);
+    let score = |path: &Path| {
+        let stem = normalize(&path.file_stem().unwrap_or_default().to_string_lossy());
+        if stem.is_empty() || game.is_empty() {
+            0
+        } else if stem == game {
+            2
+        } else if game.contains(&stem) || stem.contains(&game) {
+            1
+        } else {
+            0
+        }
+    };
+    found.sort_by(|(a, a_depth, a_size), (b, b_depth, b_size)| {
+        score(b)
+            .cmp(&score(a))
+            .then(a_depth.cmp(b_depth))
+            .then(b_size.cmp(a_size))
+    });
+    found
+        .into_iter()
+        .take(MAX_EXECUTABLES)
+        .map(|(path, _, _)| path.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Every game installed in every Steam library on this machine.
+pub fn scan_installed_games() -> Vec<InstalledGame> {
+    let mut games = Vec::new();
+    let mut seen = HashSet::new();
+    for root in locate_steam() {
+        for library in library_folders(&root) {
+            let Ok(entries) = std::fs::read_dir(library.join("steamapps")) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_lowercase();
+                if !(file_name.starts_with("appmanifest_") && file_name.ends_with(".acf")) {
+                    continue;
+                }
+                match read_app_manifest(&entry.path(), &library) {
+                    Ok(Some(game)) if seen.insert(game.app_id.clone()) => games.push(game),
+                    Ok(_) => {}
+                    Err(e) => println!("Skipping app manifest: {}", e),
+                }
+            }
+        }
+    }
+    games.sort_by_key(|game| game.name.to_lowercase());
+    games
+}
+
+/// Lists games installed through Steam with their appid, install directory and
+/// candidate executables, so a game can be added without picking files by hand.
+#[tauri::command]
+pub async fn discover_installed_games(
+    db: State<'_, LibraryDb>,
+) -> Result<Vec<InstalledGame>, String> {
+    let mut games = tauri::async_runtime::spawn_blocking(scan_installed_games)
+        .await
+        .map_err(|e| format!("Failed to scan Steam libraries: {}", e))?;
+
+    let known: HashSet<String> = db.list_games()?.into_iter().map(|g| g.app_id).collect();
+    for game in &mut games {
+        game.in_library = known.contains(&game.app_id);
+    }
+    println!("Discovered {} installed Steam games", games.len());
+    Ok(games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_helpers_but_not_games_with_similar_names() {
+        for helper in [
+            "unins000.exe",
+            "CrashReporter.exe",
+            "UnityCrashHandler64.exe",
+            "DXSETUP.exe",
+            "vc_redist.x64.exe",
+            "setup.exe",
+        ] {
+            assert!(is_ignored_exe(Path::new(helper)), "{}", helper);
+        }
+        for game in [
+            "CrashBandicoot.exe",
+            "Reporter.exe",
+            "SetupTheBoard.exe",
+            "RedistrictingSim.exe",
+        ] {
+            assert!(!is_ignored_exe(Path::new(game)), "{}", game);
+        }
+    }
+}
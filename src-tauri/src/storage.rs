@@ -0,0 +1,276 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Owns the on-disk layout of everything UnlockIt writes from the backend.
+///
+/// All paths hang off `<app_data_dir>/UnlockIt`:
+/// - `library.db` games, achievements, unlocks, sessions and metadata (see `db::LibraryDb`)
+/// - `Achievements/<appid>/` legacy per-game achievement data
+/// - `images/<appid>/` custom wallpapers and library covers
+/// - `playtimes.json` legacy playtime per appid, imported into `library.db`
+/// - `settings/<name>.json` backend-owned settings (notifications, ...)
+/// - `cache/` derived files that can always be regenerated (e.g. `cache/toast`)
+///
+/// It also knows where the frontend caches downloaded art (`<app_local_data_dir>/assets`)
+/// and where toast images are processed, so cleanup can cover those too.
+pub struct Storage {
+    app_data_dir: PathBuf,
+    app_local_data_dir: PathBuf,
+    temp_dir: PathBuf,
+    root: PathBuf,
+}
+
+impl Storage {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Could not get app data directory: {}", e))?;
+        let app_local_data_dir = app_handle
+            .path()
+            .app_local_data_dir()
+            .map_err(|e| format!("Could not get app local data directory: {}", e))?;
+        let temp_dir = app_handle
+            .path()
+            .temp_dir()
+            .map_err(|e| format!("Failed to get temp directory: {}", e))?;
+
+        let root = app_data_dir.join("UnlockIt");
+        Ok(Self {
+            app_data_dir,
+            app_local_data_dir,
+            temp_dir,
+            root,
+        })
+    }
+
+    /// Storage laid out under `dir` instead of the app's directories.
+    #[cfg(test)]
+    pub(crate) fn in_dir(dir: &Path) -> Self {
+        let app_data_dir = dir.join("data");
+        Self {
+            root: app_data_dir.join("UnlockIt"),
+            app_data_dir,
+            app_local_data_dir: dir.join("local"),
+            temp_dir: dir.join("temp"),
+        }
+    }
+
+    /// The Tauri app data directory itself, where `tauri-plugin-store` keeps its JSON files.
+    pub fn app_data_dir(&self) -> &Path {
+        &self.app_data_dir
+    }
+
+    pub fn app_local_data_dir(&self) -> &Path {
+        &self.app_local_data_dir
+    }
+
+    /// Art downloaded by the frontend (`cover_<appid>.jpg`, achievement icons, ...).
+    pub fn assets_dir(&self) -> PathBuf {
+        self.app_local_data_dir.join("assets")
+    }
+
+    pub fn temp_dir(&self) -> &Path {
+        &self.temp_dir
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn achievements_root(&self) -> PathBuf {
+        self.root.join("Achievements")
+    }
+
+    pub fn achievements_dir(&self, app_id: &str) -> PathBuf {
+        self.achievements_root().join(app_id)
+    }
+
+    pub fn images_root(&self) -> PathBuf {
+        self.root.join("images")
+    }
+
+    pub fn images_dir(&self, app_id: &str) -> PathBuf {
+        self.images_root().join(app_id)
+    }
+
+    /// SQLite library database, see `db::LibraryDb`.
+    pub fn database_file(&self) -> PathBuf {
+        self.root.join("library.db")
+    }
+
+    pub fn playtimes_file(&self) -> PathBuf {
+        self.root.join("playtimes.json")
+    }
+
+    /// User sound packs, one folder per pack.
+    pub fn sounds_dir(&self) -> PathBuf {
+        self.root.join("sounds")
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root.join("cache")
+    }
+
+    /// Resized icons and blurred hero images prepared for toasts.
+    pub fn toast_cache_dir(&self) -> PathBuf {
+        self.cache_dir().join("toast")
+    }
+
+    pub fn settings_file(&self, name: &str) -> PathBuf {
+        self.root.join("settings").join(format!("{}.json", name))
+    }
+
+    /// Loads `settings/<name>.json`, falling back to defaults when it is missing or unreadable.
+    pub fn load_settings<T: DeserializeOwned + Default>(&self, name: &str) -> T {
+        let path = self.settings_file(name);
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                println!("Invalid settings file {:?}, using defaults: {}", path, e);
+                T::default()
+            }),
+            Err(_) => T::default(),
+        }
+    }
+
+    pub fn save_settings<T: Serialize>(&self, name: &str, value: &T) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        write_atomic(&self.settings_file(name), content.as_bytes())
+    }
+
+    /// Returns `path` after making sure it exists as a directory.
+    pub fn ensure_dir(path: PathBuf) -> Result<PathBuf, String> {
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", path, e))?;
+        Ok(path)
+    }
+
+    /// Moves images written by older versions under `%APPDATA%/UnlockIt/images` into
+    /// the app data dir and rewrites the paths stored in `my-games.json`.
+    ///
+    /// Runs once; a marker file in the storage root records that it happened.
+    pub fn migrate_legacy_images(&self) -> Result<usize, String> {
+        let marker = self.root.join(".images_migrated");
+        if marker.exists() {
+            return Ok(0);
+        }
+
+        let legacy_dir = match std::env::var("APPDATA") {
+            Ok(appdata) => Path::new(&appdata).join("UnlockIt").join("images"),
+            Err(_) => {
+                Self::write_marker(&marker)?;
+                return Ok(0);
+            }
+        };
+        let new_dir = self.images_root();
+
+        if !legacy_dir.exists() || legacy_dir == new_dir {
+            Self::write_marker(&marker)?;
+            return Ok(0);
+        }
+
+        println!("Migrating legacy images from {:?} to {:?}", legacy_dir, new_dir);
+        let moved = move_tree(&legacy_dir, &new_dir)?;
+
+        let games_store = self.app_data_dir.join("my-games.json");
+        if games_store.exists() {
+            let content = fs::read_to_string(&games_store)
+                .map_err(|e| format!("Failed to read games store: {}", e))?;
+            let mut data: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse games store: {}", e))?;
+
+            let old_prefix = legacy_dir.to_string_lossy().to_string();
+            let new_prefix = new_dir.to_string_lossy().to_string();
+            if rewrite_path_prefix(&mut data, &old_prefix, &new_prefix) > 0 {
+                let content = serde_json::to_string_pretty(&data)
+                    .map_err(|e| format!("Failed to serialize games store: {}", e))?;
+                fs::write(&games_store, content)
+                    .map_err(|e| format!("Failed to write games store: {}", e))?;
+            }
+        }
+
+        // Only drop the legacy folder if everything made it across.
+        let _ = fs::remove_dir(&legacy_dir);
+        if let Some(parent) = legacy_dir.parent() {
+            let _ = fs::remove_dir(parent);
+        }
+
+        Self::write_marker(&marker)?;
+        println!("Migrated {} legacy image files", moved);
+        Ok(moved)
+    }
+
+    fn write_marker(marker: &Path) -> Result<(), String> {
+        if let Some(parent) = marker.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create storage root: {}", e))?;
+        }
+        fs::write(marker, b"").map_err(|e| format!("Failed to write migration marker: {}", e))
+    }
+}
+
+/// Writes `contents` to a sibling temp file and renames it over `path`, so readers
+/// never observe a half-written file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    fs::write(&tmp_path, contents).map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+}
+
+/// Moves every file under `from` into the same relative location under `to`,
+/// falling back to copy + delete when a rename crosses filesystems.
+fn move_tree(from: &Path, to: &Path) -> Result<usize, String> {
+    let mut moved = 0;
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {:?}: {}", to, e))?;
+
+    let entries = fs::read_dir(from).map_err(|e| format!("Failed to read {:?}: {}", from, e))?;
+    for entry in entries.flatten() {
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+
+        if source.is_dir() {
+            moved += move_tree(&source, &target)?;
+            let _ = fs::remove_dir(&source);
+        } else if !target.exists() {
+            if fs::rename(&source, &target).is_err() {
+                fs::copy(&source, &target)
+                    .map_err(|e| format!("Failed to copy {:?}: {}", source, e))?;
+                let _ = fs::remove_file(&source);
+            }
+            moved += 1;
+        }
+    }
+
+    Ok(moved)
+}
+
+/// Replaces `old_prefix` with `new_prefix` in every string value of `value`.
+/// Returns the number of strings that were changed.
+pub(crate) fn rewrite_path_prefix(value: &mut serde_json::Value, old_prefix: &str, new_prefix: &str) -> usize {
+    match value {
+        serde_json::Value::String(s) if s.starts_with(old_prefix) => {
+            *s = format!("{}{}", new_prefix, &s[old_prefix.len()..]);
+            1
+        }
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .map(|item| rewrite_path_prefix(item, old_prefix, new_prefix))
+            .sum(),
+        serde_json::Value::Object(map) => map
+            .values_mut()
+            .map(|item| rewrite_path_prefix(item, old_prefix, new_prefix))
+            .sum(),
+        _ => 0,
+    }
+}
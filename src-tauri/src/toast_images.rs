@@ -0,0 +1,112 @@
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Numbers the partial files written by this process.
+static PARTIAL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// How a source image is prepared before it is handed to a toast.
+#[derive(Debug, Clone, Copy)]
+pub enum ToastImageTransform {
+    /// Square achievement icon, resized exactly to `size`x`size`.
+    Icon { size: u32 },
+    /// Blurred and darkened game artwork shown behind the toast text.
+    Hero { blur: f32, brighten: i32 },
+}
+
+impl ToastImageTransform {
+    pub const ICON: Self = Self::Icon { size: 120 };
+    pub const HERO: Self = Self::Hero {
+        blur: 3.0,
+        brighten: -50,
+    };
+
+    /// Part of the cache file name, so changing any parameter produces a new entry.
+    fn cache_key(&self) -> String {
+        match self {
+            Self::Icon { size } => format!("icon_{}", size),
+            Self::Hero { blur, brighten } => format!("hero_b{}_l{}", blur, brighten),
+        }
+    }
+
+    fn apply(&self, img: image::DynamicImage) -> image::DynamicImage {
+        match *self {
+            Self::Icon { size } => {
+                img.resize_exact(size, size, image::imageops::FilterType::Lanczos3)
+            }
+            Self::Hero { blur, brighten } => img.blur(blur).brighten(brighten),
+        }
+    }
+}
+
+/// Returns a processed copy of `source`, reusing a cached result when the same
+/// file content has already been processed with the same transform.
+///
+/// Entries are keyed by the SHA-256 of the source bytes plus the transform
+/// parameters, so two games shipping an `icon.jpg` no longer overwrite each other.
+/// Decoding and encoding run on the blocking pool.
+pub async fn process_toast_image(
+    source: &Path,
+    transform: ToastImageTransform,
+    cache_dir: &Path,
+) -> Result<PathBuf, String> {
+    let source = source.to_path_buf();
+    let cache_dir = cache_dir.to_path_buf();
+
+    tokio::task::spawn_blocking(move || process_blocking(&source, transform, &cache_dir))
+        .await
+        .map_err(|e| format!("Image processing task failed: {}", e))?
+}
+
+fn process_blocking(
+    source: &Path,
+    transform: ToastImageTransform,
+    cache_dir: &Path,
+) -> Result<PathBuf, String> {
+    let bytes =
+        std::fs::read(source).map_err(|e| format!("Failed to read image {:?}: {}", source, e))?;
+
+    let digest = Sha256::digest(&bytes);
+    let hash: String = digest
+        .iter()
+        .take(16)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let output_path = cache_dir.join(format!("{}_{}.png", hash, transform.cache_key()));
+
+    if output_path.exists() {
+        println!("Using cached toast image: {:?}", output_path);
+        return Ok(output_path);
+    }
+
+    std::fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Failed to create toast image cache: {}", e))?;
+
+    let img = image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to open/decode image: {}", e))?;
+    println!(
+        "Processing toast image {:?} ({}x{}) as {}",
+        source,
+        img.width(),
+        img.height(),
+        transform.cache_key()
+    );
+
+    // Write to a temp name first so a concurrent toast never picks up a half-written file.
+    // The name is unique per process and call, so two toasts racing on the same image never
+    // write to the same partial file.
+    let partial_path = output_path.with_extension(format!(
+        "{}.{}.partial",
+        std::process::id(),
+        PARTIAL_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    transform
+        .apply(img)
+        .save_with_format(&partial_path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to save processed image: {}", e))?;
+    std::fs::rename(&partial_path, &output_path)
+        .map_err(|e| format!("Failed to finalize processed image: {}", e))?;
+
+    println!("Saved processed toast image to: {:?}", output_path);
+    Ok(output_path)
+}
@@ -0,0 +1,232 @@
+use crate::db::{LibraryDb, UnlockQuery, UnlockRecord};
+use crate::storage::write_atomic;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+const CSV_HEADER: [&str; 10] = [
+    "game",
+    "app_id",
+    "api_name",
+    "display_name",
+    "unlock_time",
+    "unlock_timestamp",
+    "global_percent",
+    "rarity",
+    "source",
+    "icon",
+];
+
+/// One line of the unlock timeline as it appears in every export format.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedUnlock {
+    pub game: Option<String>,
+    pub app_id: String,
+    pub api_name: String,
+    pub display_name: Option<String>,
+    /// RFC 3339, UTC.
+    pub unlock_time: Option<String>,
+    pub unlock_timestamp: Option<i64>,
+    pub global_percent: Option<f64>,
+    pub rarity: Option<String>,
+    pub source: Option<String>,
+    pub icon: Option<String>,
+}
+
+impl From<UnlockRecord> for ExportedUnlock {
+    fn from(record: UnlockRecord) -> Self {
+        Self {
+            game: record.game_name.filter(|name| !name.is_empty()),
+            unlock_time: record
+                .unlock_time
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            unlock_timestamp: record.unlock_time,
+            app_id: record.app_id,
+            api_name: record.api_name,
+            display_name: record.display_name,
+            global_percent: record.global_percent,
+            rarity: record.rarity.map(|tier| tier.label().to_string()),
+            source: record.source,
+            icon: record.icon,
+        }
+    }
+}
+
+impl ExportedUnlock {
+    fn csv_fields(&self) -> [String; 10] {
+        [
+            self.game.clone().unwrap_or_default(),
+            self.app_id.clone(),
+            self.api_name.clone(),
+            self.display_name.clone().unwrap_or_default(),
+            self.unlock_time.clone().unwrap_or_default(),
+            self.unlock_timestamp
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            self.global_percent
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+            self.rarity.clone().unwrap_or_default(),
+            self.source.clone().unwrap_or_default(),
+            self.icon.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnlockExport {
+    pub format: ExportFormat,
+    pub records: usize,
+    /// Set when the export was written to a file.
+    pub path: Option<String>,
+    /// The export itself when no `path` was given.
+    pub content: Option<String>,
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn render(unlocks: &[ExportedUnlock], format: ExportFormat) -> Result<String, String> {
+    let serialize_err = |e: serde_json::Error| format!("Failed to serialize unlocks: {}", e);
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(unlocks).map_err(serialize_err),
+        ExportFormat::Ndjson => {
+            let mut out = String::new();
+            for unlock in unlocks {
+                out.push_str(&serde_json::to_string(unlock).map_err(serialize_err)?);
+                out.push('\n');
+            }
+            Ok(out)
+        }
+        ExportFormat::Csv => {
+            let mut out = CSV_HEADER.join(",");
+            out.push_str("\r\n");
+            for unlock in unlocks {
+                let fields: Vec<String> =
+                    unlock.csv_fields().iter().map(|f| csv_escape(f)).collect();
+                out.push_str(&fields.join(","));
+                out.push_str("\r\n");
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Exports the unlock timeline (oldest first) as CSV, JSON or NDJSON.
+///
+/// `filter` narrows by game (`app_id`) and date range (`since`/`until`, Unix seconds).
+/// With `path` the export is written there; otherwise it is returned as `content`.
+#[tauri::command]
+pub fn export_unlocks(
+    db: State<'_, LibraryDb>,
+    format: ExportFormat,
+    filter: Option<UnlockQuery>,
+    path: Option<String>,
+) -> Result<UnlockExport, String> {
+    let unlocks: Vec<ExportedUnlock> = db
+        .unlock_timeline(&filter.unwrap_or_default())?
+        .into_iter()
+        .map(ExportedUnlock::from)
+        .collect();
+    let content = render(&unlocks, format)?;
+
+    let path = path.filter(|p| !p.is_empty());
+    if let Some(path) = &path {
+        write_atomic(std::path::Path::new(path), content.as_bytes())?;
+        println!("Exported {} unlocks to {}", unlocks.len(), path);
+    }
+
+    Ok(UnlockExport {
+        format,
+        records: unlocks.len(),
+        content: if path.is_none() { Some(content) } else { None },
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rarity::RarityTier;
+
+    fn unlocks() -> Vec<ExportedUnlock> {
+        let record =
+            |api_name: &str, display_name: &str, unlock_time, global_percent| UnlockRecord {
+                app_id: "480".to_string(),
+                game_name: Some("Spacewar".to_string()),
+                api_name: api_name.to_string(),
+                display_name: Some(display_name.to_string()),
+                icon: None,
+                unlock_time,
+                global_percent,
+                rarity: global_percent.map(RarityTier::from_percentage),
+                source: Some("goldberg".to_string()),
+            };
+        vec![
+            record("ACH_A", "Winner", Some(0), Some(4.5)).into(),
+            record("ACH_B", "Say \"hi\", twice\nplease", None, None).into(),
+        ]
+    }
+
+    #[test]
+    fn csv_escape_quotes_only_when_needed() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("two\r\nlines"), "\"two\r\nlines\"");
+        assert_eq!(csv_escape(""), "");
+    }
+
+    #[test]
+    fn csv_has_a_header_and_one_escaped_row_per_unlock() {
+        let csv = render(&unlocks(), ExportFormat::Csv).unwrap();
+        let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], CSV_HEADER.join(","));
+        assert_eq!(
+            rows[1],
+            format!(
+                "Spacewar,480,ACH_A,Winner,1970-01-01T00:00:00Z,0,4.5,{},goldberg,",
+                RarityTier::from_percentage(4.5).label()
+            )
+        );
+        assert_eq!(
+            rows[2],
+            "Spacewar,480,ACH_B,\"Say \"\"hi\"\", twice\nplease\",,,,,goldberg,"
+        );
+    }
+
+    #[test]
+    fn json_and_ndjson_hold_the_same_records() {
+        let unlocks = unlocks();
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&unlocks, ExportFormat::Json).unwrap()).unwrap();
+        let ndjson = render(&unlocks, ExportFormat::Ndjson).unwrap();
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert!(ndjson.ends_with('\n'));
+        assert_eq!(json, serde_json::Value::Array(lines.clone()));
+        assert_eq!(lines[0]["unlock_time"], "1970-01-01T00:00:00Z");
+        assert_eq!(lines[0]["unlock_timestamp"], 0);
+        assert_eq!(lines[1]["unlock_time"], serde_json::Value::Null);
+        assert_eq!(render(&[], ExportFormat::Ndjson).unwrap(), "");
+        assert_eq!(render(&[], ExportFormat::Json).unwrap(), "[]");
+    }
+}
@@ -0,0 +1,267 @@
+//! Reader for Valve's text KeyValues format (`.vdf`, `.acf`).
+//!
+//! Keys are compared case-insensitively, as Steam itself does: `localconfig.vdf`
+//! uses both `apps` and `Apps` depending on the client version.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vdf {
+    Str(String),
+    Obj(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// Parses a document; the result is an object holding the top-level keys.
+    pub fn parse(text: &str) -> Result<Vdf, String> {
+        let mut parser = Parser {
+            chars: text.chars().peekable(),
+            line: 1,
+        };
+        let root = parser.object(false)?;
+        Ok(Vdf::Obj(root))
+    }
+
+    pub fn read(path: &Path) -> Result<Vdf, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Vdf::parse(&String::from_utf8_lossy(&bytes))
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        self.entries()
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_path(&self, keys: &[&str]) -> Option<&Vdf> {
+        keys.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Vdf::as_str)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Str(s) => Some(s),
+            Vdf::Obj(_) => None,
+        }
+    }
+
+    /// Child entries of an object; empty for a string.
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Obj(entries) => entries,
+            Vdf::Str(_) => &[],
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+impl Parser<'_> {
+    fn object(&mut self, nested: bool) -> Result<Vec<(String, Vdf)>, String> {
+        let mut entries = Vec::new();
+        loop {
+            let key = match self.token()? {
+                None if nested => return Err(format!("line {}: missing '}}'", self.line)),
+                None => return Ok(entries),
+                Some(Token::Close) if nested => return Ok(entries),
+                Some(Token::Close) => return Err(format!("line {}: unexpected '}}'", self.line)),
+                Some(Token::Open) => return Err(format!("line {}: expected a key", self.line)),
+                Some(Token::Text(key)) => key,
+            };
+            let value = match self.token()? {
+                Some(Token::Text(value)) => Vdf::Str(value),
+                Some(Token::Open) => Vdf::Obj(self.object(true)?),
+                _ => return Err(format!("line {}: missing value for '{}'", self.line, key)),
+            };
+            entries.push((key, value));
+        }
+    }
+
+    fn token(&mut self) -> Result<Option<Token>, String> {
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None => return Ok(None),
+                Some('/') => {
+                    self.chars.next();
+                    if self.chars.peek() == Some(&'/') {
+                        self.skip_line();
+                    } else {
+                        return Ok(Some(Token::Text(format!("/{}", self.bare()))));
+                    }
+                }
+                // Platform conditionals such as [$WIN32] apply to the previous entry.
+                Some('[') => {
+                    for c in self.chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                Some('{') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Open));
+                }
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Close));
+                }
+                Some('"') => {
+                    self.chars.next();
+                    return self.quoted().map(|s| Some(Token::Text(s)));
+                }
+                Some(_) => return Ok(Some(Token::Text(self.bare()))),
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        let mut out = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return Ok(out),
+                '\\' => match self.chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some(other) => out.push(other),
+                    None => break,
+                },
+                '\n' => {
+                    self.line += 1;
+                    out.push(c);
+                }
+                _ => out.push(c),
+            }
+        }
+        Err(format!("line {}: unterminated string", self.line))
+    }
+
+    fn bare(&mut self) -> String {
+        let mut out = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || matches!(c, '"' | '{' | '}') {
+                break;
+            }
+            out.push(c);
+            self.chars.next();
+        }
+        out
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        for c in self.chars.by_ref() {
+            if c == '\n' {
+                self.line += 1;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_escaped_paths_comments_and_conditionals() {
+        let text = r#"
+            // written by the Steam client
+            "UserLocalConfigStore"
+            {
+                "Software" { "Valve" { "Steam" {
+                    "apps"
+                    {
+                        "480"   // Spacewar
+                        {
+                            "Playtime"      "42"
+                            "LastPlayed"    "1700000000"    [$WIN32]
+                            "InstallDir"    "C:\\Program Files (x86)\\Steam\\steamapps\\common\\Spacewar"
+                            "Note"          "say \"hi\"\tthere\nbye"
+                        }
+                    }
+                } } }
+                bare_key /usr/share/steam
+            }
+        "#;
+        let root = Vdf::parse(text).unwrap();
+        let app = root
+            .get_path(&[
+                "userlocalconfigstore",
+                "Software",
+                "valve",
+                "STEAM",
+                "Apps",
+                "480",
+            ])
+            .unwrap();
+
+        assert_eq!(app.get_str("playtime"), Some("42"));
+        assert_eq!(app.get_str("LastPlayed"), Some("1700000000"));
+        assert_eq!(
+            app.get_str("InstallDir"),
+            Some(r"C:\Program Files (x86)\Steam\steamapps\common\Spacewar")
+        );
+        assert_eq!(app.get_str("Note"), Some("say \"hi\"\tthere\nbye"));
+        assert_eq!(app.entries().len(), 4);
+        assert_eq!(
+            root.get_path(&["UserLocalConfigStore", "bare_key"])
+                .and_then(Vdf::as_str),
+            Some("/usr/share/steam")
+        );
+        assert_eq!(app.get("Playtime").unwrap().entries(), &[]);
+    }
+
+    #[test]
+    fn parse_reports_the_line_of_malformed_input() {
+        let unclosed = Vdf::parse("\"apps\"\n{\n\"480\" { }\n").unwrap_err();
+        assert!(
+            unclosed.contains("line 4") && unclosed.contains("missing '}'"),
+            "{}",
+            unclosed
+        );
+
+        let unterminated = Vdf::parse("\"apps\" \"oops\n").unwrap_err();
+        assert!(
+            unterminated.contains("unterminated string"),
+            "{}",
+            unterminated
+        );
+
+        let stray = Vdf::parse("\"apps\" { }\n}").unwrap_err();
+        assert!(
+            stray.contains("line 2") && stray.contains("unexpected '}'"),
+            "{}",
+            stray
+        );
+
+        assert!(Vdf::parse("\"key\"").unwrap_err().contains("missing value"));
+        assert_eq!(Vdf::parse("").unwrap(), Vdf::Obj(Vec::new()));
+    }
+}
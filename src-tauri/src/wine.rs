@@ -0,0 +1,387 @@
+//! Wine and Proton prefixes, so Windows save locations can be found on Linux.
+//!
+//! Proton keeps one prefix per game in `steamapps/compatdata/<appid>/pfx` with the
+//! user `steamuser`; plain Wine uses `$WINEPREFIX` or `~/.wine` with the login name.
+//! A game installed inside a prefix (`.../drive_c/Games/...`) uses that prefix.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrefixKind {
+    Proton,
+    Wine,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WinePrefix {
+    pub path: PathBuf,
+    pub kind: PrefixKind,
+    /// The compatdata appid, for Proton prefixes.
+    pub app_id: Option<String>,
+}
+
+impl WinePrefix {
+    fn new(path: PathBuf, kind: PrefixKind, app_id: Option<String>) -> Option<WinePrefix> {
+        path.join("drive_c")
+            .is_dir()
+            .then_some(WinePrefix { path, kind, app_id })
+    }
+
+    /// A prefix named by a launcher, if it has been created.
+    pub fn at(path: PathBuf) -> Option<WinePrefix> {
+        WinePrefix::new(path, PrefixKind::Wine, None)
+    }
+
+    pub fn drive_c(&self) -> PathBuf {
+        self.path.join("drive_c")
+    }
+
+    /// `drive_c/users/<name>` for every real user in the prefix.
+    pub fn user_profiles(&self) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(self.drive_c().join("users")) else {
+            return Vec::new();
+        };
+        let mut profiles: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .filter(|e| !e.file_name().eq_ignore_ascii_case("Public"))
+            .map(|e| e.path())
+            .collect();
+        profiles.sort();
+        profiles
+    }
+
+    pub fn public_dir(&self) -> PathBuf {
+        self.drive_c().join("users").join("Public")
+    }
+}
+
+/// The prefix `path` lives in, if it is under a `drive_c` directory.
+pub fn prefix_containing(path: &Path) -> Option<WinePrefix> {
+    let drive_c = path.ancestors().find(|dir| {
+        dir.file_name()
+            .is_some_and(|n| n.eq_ignore_ascii_case("drive_c"))
+    })?;
+    let prefix = drive_c.parent()?.to_path_buf();
+    let app_id = (prefix.file_name()? == "pfx")
+        .then(|| prefix.parent()?.file_name()?.to_str().map(str::to_string))
+        .flatten();
+    let kind = if app_id.is_some() {
+        PrefixKind::Proton
+    } else {
+        PrefixKind::Wine
+    };
+    WinePrefix::new(prefix, kind, app_id)
+}
+
+/// Proton prefixes in every Steam library.
+fn proton_prefixes() -> Vec<WinePrefix> {
+    let mut prefixes = Vec::new();
+    for root in crate::steam_discovery::locate_steam() {
+        for library in crate::steam_discovery::library_folders(&root) {
+            let Ok(entries) = std::fs::read_dir(library.join("steamapps").join("compatdata"))
+            else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let id = entry.file_name().to_string_lossy().into_owned();
+                if let Some(prefix) =
+                    WinePrefix::new(entry.path().join("pfx"), PrefixKind::Proton, Some(id))
+                {
+                    prefixes.push(prefix);
+                }
+            }
+        }
+    }
+    prefixes
+}
+
+/// Prefixes a game's Windows paths may map into, most likely first. Empty on Windows.
+pub fn candidate_prefixes(app_id: Option<&str>, game_dir: Option<&Path>) -> Vec<WinePrefix> {
+    if cfg!(windows) {
+        return Vec::new();
+    }
+
+    let mut prefixes: Vec<WinePrefix> = game_dir.and_then(prefix_containing).into_iter().collect();
+    let (own, others): (Vec<_>, Vec<_>) = proton_prefixes()
+        .into_iter()
+        .partition(|p| app_id.is_some() && p.app_id.as_deref() == app_id);
+    prefixes.extend(own);
+    if let Some(path) = std::env::var_os("WINEPREFIX") {
+        prefixes.extend(WinePrefix::new(PathBuf::from(path), PrefixKind::Wine, None));
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        prefixes.extend(WinePrefix::new(
+            PathBuf::from(home).join(".wine"),
+            PrefixKind::Wine,
+            None,
+        ));
+    }
+    prefixes.extend(others);
+
+    let mut seen = std::collections::HashSet::new();
+    prefixes.retain(|p| seen.insert(p.path.clone()));
+    prefixes
+}
+
+/// A Windows location a save path starts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowsFolder {
+    /// `AppData/Roaming`.
+    RoamingAppData,
+    /// `AppData/Local`.
+    LocalAppData,
+    Documents,
+    /// `%PUBLIC%` (`C:\Users\Public`).
+    Public,
+    /// The user profile itself (`%USERPROFILE%`).
+    Profile,
+    /// `%ProgramData%` (`C:\ProgramData`).
+    ProgramData,
+}
+
+/// Where `folder` is inside `prefix`, in every user profile. Older Wine versions
+/// use the XP-era names, so both layouts are listed.
+pub fn folder_in_prefix(prefix: &WinePrefix, folder: WindowsFolder) -> Vec<PathBuf> {
+    match folder {
+        WindowsFolder::Public => return vec![prefix.public_dir()],
+        WindowsFolder::ProgramData => return vec![prefix.drive_c().join("ProgramData")],
+        _ => {}
+    }
+    let mut dirs = Vec::new();
+    for profile in prefix.user_profiles() {
+        match folder {
+            WindowsFolder::RoamingAppData => {
+                dirs.push(profile.join("AppData").join("Roaming"));
+                dirs.push(profile.join("Application Data"));
+            }
+            WindowsFolder::LocalAppData => {
+                dirs.push(profile.join("AppData").join("Local"));
+                dirs.push(profile.join("Local Settings").join("Application Data"));
+            }
+            WindowsFolder::Documents => {
+                dirs.push(profile.join("Documents"));
+                dirs.push(profile.join("My Documents"));
+            }
+            WindowsFolder::Profile => dirs.push(profile),
+            WindowsFolder::Public | WindowsFolder::ProgramData => {}
+        }
+    }
+    dirs
+}
+
+/// Splits a Windows path such as `%APPDATA%\GSE Saves\480`,
+/// `C:\Users\Bob\Documents\x` or `AppData/Roaming/x` (relative to the profile) into
+/// its base folder and the remaining components. Other drive paths have no folder.
+fn split_windows_path(path: &str) -> (Option<WindowsFolder>, Vec<String>) {
+    let parts: Vec<&str> = path.split(['\\', '/']).filter(|p| !p.is_empty()).collect();
+    let lower: Vec<String> = parts.iter().map(|p| p.to_lowercase()).collect();
+    let rest = |n: usize| {
+        parts[n.min(parts.len())..]
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    };
+
+    let from_profile = |start: usize| -> (Option<WindowsFolder>, Vec<String>) {
+        match lower.get(start).map(String::as_str) {
+            Some("appdata") => match lower.get(start + 1).map(String::as_str) {
+                Some("roaming") => (Some(WindowsFolder::RoamingAppData), rest(start + 2)),
+                Some("local") => (Some(WindowsFolder::LocalAppData), rest(start + 2)),
+                _ => (Some(WindowsFolder::Profile), rest(start)),
+            },
+            Some("documents") | Some("my documents") => {
+                (Some(WindowsFolder::Documents), rest(start + 1))
+            }
+            _ => (Some(WindowsFolder::Profile), rest(start)),
+        }
+    };
+
+    match lower.first().map(String::as_str) {
+        Some("%appdata%") => (Some(WindowsFolder::RoamingAppData), rest(1)),
+        Some("%localappdata%") => (Some(WindowsFolder::LocalAppData), rest(1)),
+        Some("%public%") => (Some(WindowsFolder::Public), rest(1)),
+        Some("%programdata%") => (Some(WindowsFolder::ProgramData), rest(1)),
+        Some("%userprofile%") => from_profile(1),
+        Some(drive) if drive.len() == 2 && drive.ends_with(':') => {
+            if drive == "c:" && lower.get(1).map(String::as_str) == Some("programdata") {
+                return (Some(WindowsFolder::ProgramData), rest(2));
+            }
+            if lower.get(1).map(String::as_str) != Some("users") || parts.len() < 3 {
+                return (None, rest(0));
+            }
+            if lower[2] == "public" {
+                (Some(WindowsFolder::Public), rest(3))
+            } else {
+                from_profile(3)
+            }
+        }
+        _ => from_profile(0),
+    }
+}
+
+/// Host paths a Windows save location may correspond to, existing ones first.
+pub fn map_windows_path(path: &str, prefixes: &[WinePrefix]) -> Vec<PathBuf> {
+    let (folder, rest) = split_windows_path(path);
+    let mut mapped = Vec::new();
+    for prefix in prefixes {
+        let bases = match folder {
+            Some(folder) => folder_in_prefix(prefix, folder),
+            // Plain drive path: only C: maps into the prefix.
+            None => match rest.first() {
+                Some(drive) if drive.eq_ignore_ascii_case("c:") => vec![prefix.drive_c()],
+                _ => Vec::new(),
+            },
+        };
+        let skip = usize::from(folder.is_none());
+        for base in bases {
+            let mut target = base;
+            target.extend(rest.iter().skip(skip));
+            mapped.push(target);
+        }
+    }
+    mapped.sort_by_key(|p| !p.exists());
+    mapped
+}
+
+/// Maps a Windows save location (`%APPDATA%\GSE Saves\480`, `C:\Users\Public\...`,
+/// `Documents/...`) to the matching paths in the game's Wine or Proton prefixes.
+/// On Windows, environment variables are expanded and the path is returned as is.
+#[tauri::command]
+pub fn resolve_wine_paths(
+    path: String,
+    app_id: Option<String>,
+    game_dir: Option<String>,
+) -> Result<Vec<String>, String> {
+    if cfg!(windows) {
+        let (folder, rest) = split_windows_path(&path);
+        let base = match folder {
+            Some(WindowsFolder::RoamingAppData) => std::env::var_os("APPDATA").map(PathBuf::from),
+            Some(WindowsFolder::LocalAppData) => {
+                std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+            }
+            Some(WindowsFolder::Public) => std::env::var_os("PUBLIC").map(PathBuf::from),
+            Some(WindowsFolder::Documents) => {
+                std::env::var_os("USERPROFILE").map(|p| PathBuf::from(p).join("Documents"))
+            }
+            Some(WindowsFolder::Profile) => std::env::var_os("USERPROFILE").map(PathBuf::from),
+            Some(WindowsFolder::ProgramData) => std::env::var_os("ProgramData").map(PathBuf::from),
+            None => return Ok(vec![path]),
+        };
+        let mut target = base.ok_or_else(|| format!("Failed to resolve {}", path))?;
+        target.extend(rest);
+        return Ok(vec![target.to_string_lossy().into_owned()]);
+    }
+
+    let prefixes = candidate_prefixes(app_id.as_deref(), game_dir.as_deref().map(Path::new));
+    if prefixes.is_empty() {
+        return Err("No Wine or Proton prefix found".to_string());
+    }
+    Ok(map_windows_path(&path, &prefixes)
+        .into_iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn split_windows_path_finds_the_base_folder() {
+        use WindowsFolder::*;
+        let cases: &[(&str, Option<WindowsFolder>, &[&str])] = &[
+            (
+                r"%APPDATA%\GSE Saves\480",
+                Some(RoamingAppData),
+                &["GSE Saves", "480"],
+            ),
+            (r"%LocalAppData%\Game", Some(LocalAppData), &["Game"]),
+            (
+                r"%USERPROFILE%\Saved Games\X",
+                Some(Profile),
+                &["Saved Games", "X"],
+            ),
+            (r"%USERPROFILE%\AppData\Local\X", Some(LocalAppData), &["X"]),
+            (
+                r"C:\Users\Bob\Documents\My Games\X",
+                Some(Documents),
+                &["My Games", "X"],
+            ),
+            (r"c:\users\bob\My Documents\X", Some(Documents), &["X"]),
+            (
+                r"C:\Users\Bob\AppData\Roaming\X",
+                Some(RoamingAppData),
+                &["X"],
+            ),
+            (
+                r"C:\Users\Public\Documents\X",
+                Some(Public),
+                &["Documents", "X"],
+            ),
+            (
+                r"C:\ProgramData\Steam\X",
+                Some(ProgramData),
+                &["Steam", "X"],
+            ),
+            (r"%PROGRAMDATA%\X", Some(ProgramData), &["X"]),
+            (
+                "AppData/Roaming/Godot/app_userdata",
+                Some(RoamingAppData),
+                &["Godot", "app_userdata"],
+            ),
+            ("Documents/X", Some(Documents), &["X"]),
+            (r"D:\Games\Save", None, &["D:", "Games", "Save"]),
+            (r"C:\Games\Save", None, &["C:", "Games", "Save"]),
+        ];
+        for (path, folder, rest) in cases {
+            assert_eq!(split_windows_path(path), (*folder, parts(rest)), "{}", path);
+        }
+    }
+
+    #[test]
+    fn map_windows_path_maps_into_each_prefix_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("compatdata").join("480").join("pfx");
+        let users = root.join("drive_c").join("users");
+        let roaming = users.join("steamuser").join("AppData").join("Roaming");
+        std::fs::create_dir_all(roaming.join("GSE Saves")).unwrap();
+        std::fs::create_dir_all(users.join("Public")).unwrap();
+
+        let prefix = prefix_containing(&roaming).unwrap();
+        assert_eq!(prefix.kind, PrefixKind::Proton);
+        assert_eq!(prefix.app_id.as_deref(), Some("480"));
+        let prefixes = [prefix];
+
+        let mapped = map_windows_path(r"%APPDATA%\GSE Saves", &prefixes);
+        assert_eq!(
+            mapped,
+            [
+                roaming.join("GSE Saves"),
+                users
+                    .join("steamuser")
+                    .join("Application Data")
+                    .join("GSE Saves"),
+            ]
+        );
+        assert_eq!(
+            map_windows_path(r"C:\Users\Public\Documents\X", &prefixes),
+            [users.join("Public").join("Documents").join("X")]
+        );
+        assert_eq!(
+            map_windows_path(r"C:\ProgramData\X", &prefixes),
+            [root.join("drive_c").join("ProgramData").join("X")]
+        );
+        assert_eq!(
+            map_windows_path(r"C:\Games\Save", &prefixes),
+            [root.join("drive_c").join("Games").join("Save")]
+        );
+        assert!(map_windows_path(r"D:\Games\Save", &prefixes).is_empty());
+    }
+}
@@ -11,6 +11,7 @@ import useInitialWorkflow from "./workflow/initial_workflow";
 import GameDetails from "./components/main/Game/game-details";
 import { GamingLoader } from "./components/main/shared/add-game-loading";
 import ConfirmationDialog from "./components/main/shared/confirmation-dialog";
+import LauncherGamesDialog from "./components/main/shared/launcher-games-dialog";
 import CheckConnection from "./components/main/shared/check-connection";
 import useTrackingWorkflow from "./workflow/tracking-workflow";
 import useSilentPercentageRefreshWorkflow from "./workflow/silent-percentage-refresh-workflow";
@@ -74,6 +75,7 @@ function App() {
             <DockContainer />
           </div>
           <ConfirmationDialog />
+          <LauncherGamesDialog />
           <CheckConnection />
         </div>
       </ScrollToTopWrapper>
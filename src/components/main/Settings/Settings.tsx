@@ -10,11 +10,10 @@ import {
   Camera,
   Play,
   Download,
+  Import,
 } from "lucide-react";
 import { useState, useRef, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { convertFileSrc } from "@tauri-apps/api/core";
-import { resolveResource } from "@tauri-apps/api/path";
 import {
   Dialog,
   DialogContent,
@@ -32,6 +31,9 @@ import { WallpaperSelector } from "../Game/sections/WallpaperSelector";
 import { CoverSelector } from "../Game/sections/CoverSelector";
 import useMyGamesStore from "@/store/my-games-store";
 import useUpdateGameWorkflow from "@/workflow/update-game-workflow";
+import useLauncherImportWorkflow, {
+  ImportSource,
+} from "@/workflow/launcher-import-workflow";
 
 function SettingsDialog() {
   const [showToken, setShowToken] = useState(false);
@@ -41,6 +43,9 @@ function SettingsDialog() {
   const [tempName, setTempName] = useState("");
   const [wallpaperDialogOpen, setWallpaperDialogOpen] = useState(false);
   const [coverDialogOpen, setCoverDialogOpen] = useState(false);
+  const [importingFrom, setImportingFrom] = useState<ImportSource | null>(
+    null
+  );
   const [selectedGame, setSelectedGame] = useState<{
     appId: string;
     name: string;
@@ -51,6 +56,7 @@ function SettingsDialog() {
   const { open, toggle } = settingsModalStore();
   const { games } = useMyGamesStore();
   const { setGameHeaderImage, setGameLibraryCover } = useUpdateGameWorkflow();
+  const { previewImport, applyImport } = useLauncherImportWorkflow();
   const {
     getProfile,
     updateName,
@@ -64,6 +70,27 @@ function SettingsDialog() {
     { name: "Xbox 360", value: "xbox-360.mp3" },
     { name: "Xbox Rare Achievement", value: "xbox-rare.mp3" },
   ];
+  const importSources: {
+    name: string;
+    value: ImportSource;
+    filters: { name: string; extensions: string[] }[];
+  }[] = [
+    {
+      name: "Playnite",
+      value: "playnite",
+      filters: [{ name: "Playnite library", extensions: ["db", "json"] }],
+    },
+    {
+      name: "Steam",
+      value: "steam",
+      filters: [{ name: "localconfig.vdf", extensions: ["vdf"] }],
+    },
+    {
+      name: "GOG Galaxy",
+      value: "gog_galaxy",
+      filters: [{ name: "galaxy-2.0.db", extensions: ["db"] }],
+    },
+  ];
   useEffect(() => {
     window.scrollTo({ top: 0, behavior: "smooth" });
     if (open && dialogRef.current) {
@@ -124,26 +151,8 @@ function SettingsDialog() {
 
   const playNotificationSound = async (soundFile: string) => {
     try {
-      let audioSrc: string;
-
-      // Check if we're in a Tauri environment
-      const isTauri = typeof (window as any).__TAURI__ !== "undefined";
-
-      if (isTauri) {
-        try {
-          // First try to resolve as a bundled resource
-          const resourcePath = await resolveResource(soundFile);
-          audioSrc = convertFileSrc(resourcePath);
-        } catch (resourceError) {
-          // Fallback to direct file conversion if resource resolution fails
-          audioSrc = convertFileSrc(soundFile);
-        }
-      } else {
-        // For development mode, use direct path
-        audioSrc = `/${soundFile}`;
-      }
-      const audio = new Audio(audioSrc);
-      await audio.play();
+      // Preview through the backend player so it honours the volume settings
+      await invoke("preview_sound", { sound: soundFile });
     } catch (error) {
       toast.error("Failed to play notification sound", {
         style: {
@@ -203,6 +212,41 @@ function SettingsDialog() {
     }
   };
 
+  const handleLauncherImport = async (source: (typeof importSources)[0]) => {
+    const selected = await openDialog({
+      multiple: false,
+      filters: source.filters,
+    });
+    if (!selected) {
+      return;
+    }
+
+    setImportingFrom(source.value);
+    try {
+      const preview = await previewImport(source.value, selected);
+      if (preview.matched === 0) {
+        toast.error(`No ${source.name} games match your library`, {
+          style: { background: "rgb(185 28 28)" },
+        });
+        return;
+      }
+      const report = await applyImport(source.value, selected);
+      toast.success(`Imported from ${source.name}`, {
+        style: { background: "rgb(21 128 61)" },
+        description: `${report.games_updated} games updated, ${report.unlocks_added} unlocks and ${Math.round(
+          report.playtime_seconds_added / 60
+        )} minutes of playtime added.`,
+      });
+    } catch (error) {
+      toast.error(`Failed to import from ${source.name}`, {
+        style: { background: "rgb(185 28 28)" },
+        description: String(error),
+      });
+    } finally {
+      setImportingFrom(null);
+    }
+  };
+
   const handleWallpaperSelected = async (imagePath: string) => {
     if (selectedGame) {
       await setGameHeaderImage(selectedGame.appId, imagePath);
@@ -417,6 +461,38 @@ function SettingsDialog() {
 
               <Separator />
 
+              {/* Launcher Import Section */}
+              <div className='space-y-6'>
+                <div className='flex items-center gap-3'>
+                  <Import className='h-6 w-6 text-primary' />
+                  <h2 className='text-xl font-semibold'>
+                    Import from Other Launchers
+                  </h2>
+                </div>
+                <p className='text-muted-foreground'>
+                  Adds playtime and unlocks recorded by another launcher to the
+                  games already in your library.
+                </p>
+                <div className='grid grid-cols-1 md:grid-cols-3 gap-3'>
+                  {importSources.map((source) => (
+                    <Button
+                      key={source.value}
+                      onClick={() => handleLauncherImport(source)}
+                      disabled={importingFrom !== null}
+                      variant='outline'
+                      className='flex items-center gap-2'
+                    >
+                      <Import className='h-4 w-4' />
+                      {importingFrom === source.value
+                        ? "Importing..."
+                        : source.name}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              <Separator />
+
               {/* Steam API Section */}
               <div className='space-y-6'>
                 <h2 className='text-xl font-semibold'>Steam Integration</h2>
@@ -472,13 +548,15 @@ function SettingsDialog() {
                     onClick={async () => {
                       try {
                         await invoke("toast_notification", {
-                          iconPath: "",
-                          gameName: "UnlockIt Settings",
-                          achievementName: "Test Notification Working!",
-                          soundPath: getProfile().notificationSound,
-                          hero: "",
-                          progress: null,
-                          isRare: false,
+                          notification: {
+                            icon_path: "",
+                            game_name: "UnlockIt Settings",
+                            achievement_name: "Test Notification Working!",
+                            sound_path: getProfile().notificationSound,
+                            hero: "",
+                            progress: null,
+                            is_rare: false,
+                          },
                         });
                         toast.success("Test notification sent!");
                       } catch (error) {
@@ -1,13 +1,21 @@
 import Dock from "@/animation-ui/Dock/Dock";
 import settingsModalStore from "@/store/settings-modal-state";
+import useUIStateStore from "@/store/ui-state-store";
 import useAddGameWorkflow from "@/workflow/add-game-workflow";
-import { Gamepad2Icon, HomeIcon, PlusIcon, SettingsIcon } from "lucide-react";
+import {
+  Gamepad2Icon,
+  HomeIcon,
+  LibraryIcon,
+  PlusIcon,
+  SettingsIcon,
+} from "lucide-react";
 import { useNavigate } from "react-router-dom";
 
 function DockContainer() {
   const navigate = useNavigate();
   const { toggle } = settingsModalStore();
   const { getGamePath } = useAddGameWorkflow();
+  const { setLauncherGamesOpen } = useUIStateStore();
   const items = [
     {
       icon: <HomeIcon size={18} />,
@@ -29,6 +37,11 @@ function DockContainer() {
       label: "Add Game",
       onClick: async () => await getGamePath(),
     },
+    {
+      icon: <LibraryIcon size={18} />,
+      label: "Add from Launcher",
+      onClick: () => setLauncherGamesOpen(true),
+    },
   ];
   return (
     <Dock items={items} panelHeight={68} baseItemSize={50} magnification={70} />
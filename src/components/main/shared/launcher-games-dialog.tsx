@@ -0,0 +1,89 @@
+import { Button } from "@/components/ui/button";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import useUIStateStore from "@/store/ui-state-store";
+import { LauncherGame } from "@/types/launchers";
+import useAddGameWorkflow from "@/workflow/add-game-workflow";
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+
+function LauncherGamesDialog() {
+  const { isLauncherGamesOpen, setLauncherGamesOpen } = useUIStateStore();
+  const { addLauncherGame, discoverLauncherGames } = useAddGameWorkflow();
+  const [games, setGames] = useState<LauncherGame[]>([]);
+  const [scanning, setScanning] = useState(false);
+
+  useEffect(() => {
+    if (!isLauncherGamesOpen) {
+      return;
+    }
+    setScanning(true);
+    discoverLauncherGames()
+      .then(setGames)
+      .catch((error) => {
+        toast.error("Failed to scan launchers", {
+          style: { background: "rgb(185 28 28)" },
+          description: String(error),
+        });
+      })
+      .finally(() => setScanning(false));
+  }, [isLauncherGamesOpen]);
+
+  const handleAdd = async (game: LauncherGame) => {
+    setLauncherGamesOpen(false);
+    await addLauncherGame(game);
+  };
+
+  return (
+    <Dialog open={isLauncherGamesOpen} onOpenChange={setLauncherGamesOpen}>
+      <DialogContent>
+        <DialogHeader>
+          <DialogTitle>Games from Heroic, Lutris and Bottles</DialogTitle>
+          <DialogDescription>
+            Their save files are tracked as soon as the game is added.
+          </DialogDescription>
+        </DialogHeader>
+
+        {scanning ? (
+          <p className='text-sm text-muted-foreground'>Scanning launchers...</p>
+        ) : games.length === 0 ? (
+          <p className='text-sm text-muted-foreground'>No games found.</p>
+        ) : (
+          <ScrollArea className='max-h-96'>
+            <div className='flex flex-col gap-2 pr-3'>
+              {games.map((game) => (
+                <div
+                  key={`${game.launcher}_${game.id}`}
+                  className='flex items-center justify-between gap-3'
+                >
+                  <div className='min-w-0'>
+                    <p className='truncate font-medium'>{game.name}</p>
+                    <p className='truncate text-xs text-muted-foreground'>
+                      {game.launcher}
+                      {game.emulators[0] && ` · ${game.emulators[0].emulator}`}
+                      {game.app_id && ` · ${game.app_id}`}
+                    </p>
+                  </div>
+                  <Button
+                    size='sm'
+                    disabled={game.in_library || !game.exe_path}
+                    onClick={() => handleAdd(game)}
+                  >
+                    {game.in_library ? "Added" : "Add"}
+                  </Button>
+                </div>
+              ))}
+            </div>
+          </ScrollArea>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
+export default LauncherGamesDialog;
@@ -0,0 +1,224 @@
+import { GameStoreData } from "@/types/Game";
+import { Achievement, SteamSchemaResponse } from "@/types/achievements";
+import useAchievementsStore from "@/store/achievements-store";
+import { invoke } from "@tauri-apps/api/core";
+import { load } from "@tauri-apps/plugin-store";
+
+/**
+ * Mirrors the frontend stores into the backend library database
+ * (`UnlockIt/library.db`). The file watcher, notifications, exports and
+ * importers all read games and achievement schemas from there. Unlocks the
+ * backend records on its own (launcher imports) are pulled back with
+ * `refreshUnlocksFromDb`, and relocks it detects are applied with
+ * `relockInStores`.
+ */
+
+interface AchievementState {
+  unlocked: boolean;
+  unlock_time: number | null;
+}
+
+interface GameAchievementState {
+  app_id: string;
+  achievements: Record<string, AchievementState>;
+}
+
+function toGameRecord(game: GameStoreData) {
+  return {
+    app_id: String(game.appId),
+    name: game.name,
+    exe_path: game.exePath || null,
+    install_dir: game.dir || null,
+    header_image: game.header_image || null,
+    cover: game.library_cover || game.igdb_cover || null,
+    status: game.status || null,
+    data: game,
+  };
+}
+
+function toAchievementRecord(achievement: Achievement) {
+  const percent = parseFloat(achievement.percent ?? "");
+  return {
+    api_name: achievement.name,
+    display_name: achievement.displayName || null,
+    description: achievement.description || null,
+    icon: achievement.icon || null,
+    icon_gray: achievement.icongray || null,
+    hidden: Boolean(achievement.hidden),
+    global_percent: isNaN(percent) ? null : percent,
+  };
+}
+
+/** `achievedAt` in Unix seconds; older entries were written in milliseconds. */
+function unlockTime(achievedAt?: string): number | null {
+  const time = parseInt(achievedAt || "0");
+  if (!time || time <= 0) {
+    return null;
+  }
+  return time > 1e12 ? Math.floor(time / 1000) : time;
+}
+
+export async function syncGameToDb(game: GameStoreData) {
+  await invoke("upsert_game", { game: toGameRecord(game) });
+}
+
+export async function removeGameFromDb(appId: string) {
+  await invoke("delete_game", { appId });
+}
+
+/**
+ * Stores the achievement schema and every unlock it records. Locked entries
+ * are not sent, so an unlock the backend already knows about is never undone.
+ */
+export async function syncAchievementsToDb(
+  appId: number | string,
+  schema: SteamSchemaResponse
+) {
+  const achievements = schema?.game?.availableGameStats?.achievements ?? [];
+  if (achievements.length === 0) {
+    return;
+  }
+  await invoke("upsert_achievements", {
+    appId: String(appId),
+    achievements: achievements.map(toAchievementRecord),
+  });
+
+  const unlocked = achievements
+    .map((achievement) => ({
+      api_name: achievement.name,
+      unlock_time: unlockTime(achievement.achievedAt),
+    }))
+    .filter((achievement) => achievement.unlock_time !== null)
+    .map((achievement) => ({ ...achievement, unlocked: true }));
+  if (unlocked.length > 0) {
+    await invoke("store_achievements_by_appid", {
+      appId: String(appId),
+      achievements: unlocked,
+    });
+  }
+}
+
+/** Marks every achievement of a game as locked again. */
+export async function resetUnlocksInDb(
+  appId: number | string,
+  schema: SteamSchemaResponse
+) {
+  const achievements = schema?.game?.availableGameStats?.achievements ?? [];
+  await invoke("store_achievements_by_appid", {
+    appId: String(appId),
+    achievements: achievements.map((achievement) => ({
+      api_name: achievement.name,
+      unlocked: false,
+    })),
+  });
+}
+
+// Every hook instance of the initial workflow loads the stores; one sync per launch is enough.
+let librarySynced = false;
+
+/** Brings the database up to date with the stores loaded at startup. */
+export async function syncLibraryToDb(
+  games: GameStoreData[],
+  schemas: SteamSchemaResponse[]
+) {
+  if (librarySynced) {
+    return;
+  }
+  librarySynced = true;
+  for (const game of games) {
+    await syncGameToDb(game).catch(() => {});
+  }
+  for (const schema of schemas) {
+    if (schema?.gameId) {
+      await syncAchievementsToDb(schema.gameId, schema).catch(() => {});
+    }
+  }
+}
+
+/**
+ * Rewrites the game's achievements with `update`, in the zustand store and in
+ * `achievements.json`. Nothing is written when no achievement changed.
+ */
+async function updateStoredAchievements(
+  appId: number | string,
+  update: (achievement: Achievement) => Achievement
+) {
+  const schema = useAchievementsStore
+    .getState()
+    .achievements.find((ach) => Number(ach.gameId) === Number(appId));
+  const achievements = schema?.game?.availableGameStats?.achievements;
+  if (!schema || !achievements) {
+    return;
+  }
+  const updated = achievements.map(update);
+  if (updated.every((achievement, i) => achievement === achievements[i])) {
+    return;
+  }
+
+  const game = {
+    ...schema.game,
+    availableGameStats: {
+      ...schema.game.availableGameStats,
+      achievements: updated,
+    },
+  };
+  useAchievementsStore.getState().updateAchievement(Number(appId), { game });
+  const store = await load("achievements.json");
+  await store.set(`achievements_${appId}`, { ...schema, game });
+  await store.save();
+}
+
+/**
+ * Marks `unlocks` as unlocked with the time the backend read from the save.
+ * An earlier time already in the store is kept.
+ */
+export async function applyUnlocksToStores(
+  appId: number | string,
+  unlocks: { api_name: string; unlock_time: number | null }[]
+) {
+  const times = new Map(
+    unlocks.map((unlock) => [unlock.api_name, unlock.unlock_time])
+  );
+  await updateStoredAchievements(appId, (achievement) => {
+    if (!times.has(achievement.name)) {
+      return achievement;
+    }
+    const current = unlockTime(achievement.achievedAt);
+    const time = times.get(achievement.name) ?? null;
+    if (current !== null && (time === null || current <= time)) {
+      return achievement;
+    }
+    return {
+      ...achievement,
+      defaultvalue: 1,
+      achievedAt: String(time ?? Math.floor(Date.now() / 1000)),
+    };
+  });
+}
+
+/** Copies unlocks recorded in the database into the game's achievements. */
+export async function refreshUnlocksFromDb(appId: number | string) {
+  const state = await invoke<GameAchievementState>("get_achievements_state", {
+    appId: String(appId),
+  });
+  await applyUnlocksToStores(
+    appId,
+    Object.entries(state.achievements)
+      .filter(([, entry]) => entry.unlocked)
+      .map(([api_name, entry]) => ({
+        api_name,
+        unlock_time: entry.unlock_time,
+      }))
+  );
+}
+
+/** Locks `names` again after their save file lost them. */
+export async function relockInStores(appId: number | string, names: string[]) {
+  const relocked = new Set(names);
+  await updateStoredAchievements(appId, (achievement) =>
+    relocked.has(achievement.name) &&
+    unlockTime(achievement.achievedAt) !== null
+      ? { ...achievement, defaultvalue: 0, achievedAt: "0" }
+      : achievement
+  );
+}
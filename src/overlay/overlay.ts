@@ -0,0 +1,71 @@
+import { emit, listen } from "@tauri-apps/api/event";
+import "./overlay.css";
+
+// Mirrors `OverlayPayload` in src-tauri/src/notifications/overlay.rs
+interface OverlayToast {
+  game_name: string;
+  achievement_name: string;
+  headline: string;
+  progress: string | null;
+  progress_percent: number | null;
+  rarity: "Common" | "Uncommon" | "Rare" | "Epic" | "Legendary" | "Mythic";
+  icon: string | null;
+  hero: string | null;
+  animation: string;
+  duration_ms: number;
+}
+
+const root = document.getElementById("overlay-root") as HTMLElement;
+
+function renderToast(toast: OverlayToast) {
+  const card = document.createElement("div");
+  card.className = `toast rarity-${toast.rarity.toLowerCase()} anim-${toast.animation}`;
+  if (toast.hero) {
+    card.style.backgroundImage = `url(${toast.hero})`;
+  }
+
+  if (toast.icon) {
+    const icon = document.createElement("img");
+    icon.className = "toast-icon";
+    icon.src = toast.icon;
+    card.appendChild(icon);
+  }
+
+  const text = document.createElement("div");
+  text.className = "toast-text";
+
+  const title = document.createElement("div");
+  title.className = "toast-title";
+  title.textContent = toast.game_name;
+  text.appendChild(title);
+
+  const headline = document.createElement("div");
+  headline.className = "toast-headline";
+  headline.textContent = toast.headline;
+  text.appendChild(headline);
+
+  if (toast.progress_percent !== null) {
+    const bar = document.createElement("div");
+    bar.className = "toast-progress";
+    const fill = document.createElement("div");
+    fill.style.width = `${toast.progress_percent}%`;
+    bar.appendChild(fill);
+    text.appendChild(bar);
+  } else if (toast.progress) {
+    const progress = document.createElement("div");
+    progress.className = "toast-headline";
+    progress.textContent = toast.progress;
+    text.appendChild(progress);
+  }
+
+  card.appendChild(text);
+  root.replaceChildren(card);
+
+  window.setTimeout(() => card.classList.add("leaving"), toast.duration_ms);
+}
+
+// Only announce readiness once the listener is registered, or the first toast
+// sent in response could arrive before anyone is listening.
+listen<OverlayToast>("overlay-toast", (event) =>
+  renderToast(event.payload)
+).then(() => emit("overlay-ready"));
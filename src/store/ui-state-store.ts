@@ -17,6 +17,8 @@ interface State {
     action: string,
     onConfirm?: () => void
   ) => void;
+  isLauncherGamesOpen: boolean;
+  setLauncherGamesOpen: (open: boolean) => void;
 }
 const useUIStateStore = create<State>((set) => ({
   isAddGameLoading: false,
@@ -59,6 +61,8 @@ const useUIStateStore = create<State>((set) => ({
       },
     }));
   },
+  isLauncherGamesOpen: false,
+  setLauncherGamesOpen: (open) => set({ isLauncherGamesOpen: open }),
 }));
 
 export default useUIStateStore;
@@ -0,0 +1,24 @@
+export interface EmulatorDetection {
+  emulator: string;
+  app_id: string | null;
+  score: number;
+  config_dir: string | null;
+  evidence: string[];
+  save_dir: string | null;
+  watch_files: string[];
+  saves_found: boolean;
+}
+
+export interface LauncherGame {
+  launcher: "heroic" | "lutris" | "bottles";
+  id: string;
+  name: string;
+  exe_path: string | null;
+  install_dir: string | null;
+  wine_prefix: string | null;
+  runner: string | null;
+  emulators: EmulatorDetection[];
+  save_files: string[];
+  app_id: string | null;
+  in_library: boolean;
+}
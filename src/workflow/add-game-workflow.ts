@@ -18,6 +18,9 @@ import useHowLongToBeatWorkflow from "./how-long-to-beat-workflow";
 import useParsingWorkflow from "./parser/parse-workflow";
 import useAutoGameStatusWorkflow from "./auto-game-status-workflow";
 import useUpdateGameWorkflow from "./update-game-workflow";
+import { syncAchievementsToDb, syncGameToDb } from "@/lib/library-db";
+import { EmulatorDetection, LauncherGame } from "@/types/launchers";
+import sharedParsingWorkflow from "./parser/shared-parse-workflow";
 // import { igdbClient } from "@/lib/igdb-client"; // Temporarily disabled
 
 const useAddGameWorkflow = () => {
@@ -35,6 +38,7 @@ const useAddGameWorkflow = () => {
   const { executeHowLongToBeatWorkflow } = useHowLongToBeatWorkflow();
   const { checkAndUpdateGameStatus } = useAutoGameStatusWorkflow();
   const { setGameExePath } = useUpdateGameWorkflow();
+  const { saveToTrackList } = sharedParsingWorkflow();
 
   // Helper function to fetch IGDB cover art
   /* TEMPORARILY DISABLED - Uncomment when needed
@@ -133,12 +137,17 @@ const useAddGameWorkflow = () => {
       // Update in-memory stores
       addGame(gameData);
       addAchievement(achievementData);
+
+      // The database is brought up to date again on the next launch if this fails
+      await syncGameToDb(gameData).catch(() => {});
+      await syncAchievementsToDb(gameData.appId, achievementData).catch(
+        () => {}
+      );
     } catch (error) {
       throw new Error("Failed to save game data to storage");
     }
   }
   async function getGamePath() {
-    let isLoading = false;
     try {
       setAddGameLoadingProgress(5);
 
@@ -173,6 +182,57 @@ const useAddGameWorkflow = () => {
         return false;
       }
 
+      return await addGameFromPath(gamePath);
+    } catch (error) {
+      toast.error("Failed to add game", {
+        style: { background: "rgb(185 28 28)" },
+        description:
+          error instanceof Error ? error.message : "Unknown error occurred",
+      });
+      return false;
+    }
+  }
+
+  /**
+   * Adds a game found by a launcher scan. Its appid and save files come from
+   * the emulator detection, so nothing has to be picked by hand.
+   */
+  async function addLauncherGame(game: LauncherGame) {
+    if (!game.exe_path) {
+      toast.error("Could not find the game executable", {
+        style: { background: "rgb(185 28 28)" },
+        description: `${game.launcher} does not list an executable for ${game.name}.`,
+      });
+      return false;
+    }
+    if (!getSteamApiKey()) {
+      toast.error("Please Make Sure to include Your API Key", {
+        style: { background: "rgb(185 28 28)" },
+      });
+      return false;
+    }
+    return await addGameFromPath(game.exe_path, {
+      appId: game.app_id,
+      saveFiles: game.save_files,
+    });
+  }
+
+  async function discoverLauncherGames() {
+    return await invoke<LauncherGame[]>("discover_launcher_games");
+  }
+
+  /**
+   * Adds the game at `gamePath`. `appId` and `saveFiles` skip detection when
+   * the caller already knows them; otherwise they come from the emulator
+   * config files next to the exe. The save files are handed to the file
+   * watcher right away.
+   */
+  async function addGameFromPath(
+    gamePath: string,
+    known: { appId?: string | null; saveFiles?: string[] } = {}
+  ) {
+    let isLoading = false;
+    try {
       let { name, dir } = getGameNameAndDir(gamePath);
       // Start loading state
       isLoading = true;
@@ -180,23 +240,33 @@ const useAddGameWorkflow = () => {
       setAddGameLoading(true);
       setAddGameLoadingProgress(15);
 
+      const detection = known.saveFiles?.length
+        ? null
+        : await detectEmulator(gamePath);
+
       // Extract AppID with timeout and fallback
-      const appId = await Promise.race([
-        Promise.all([
-          extractAppIdFromSteamEmuIni(dir).catch(() => null),
-          extractRealAppIdFromOnlineFixIni(dir).catch(() => null),
-          readGoldbergAppId(gamePath).catch(() => null), // Add Goldberg support
-          extractAppIdFromTenokeConfig(dir).catch(() => null), // Add TENOKE support
-        ]).then(
-          ([steamEmuId, onlineFixId, goldbergId, tenokeId]) =>
-            steamEmuId || onlineFixId || goldbergId || tenokeId
-        ),
-        new Promise<null>((_, reject) =>
-          setTimeout(() => reject(new Error("AppID extraction timeout")), 5000)
-        ),
-      ]).catch(() => {
-        return null;
-      });
+      const appId =
+        known.appId ||
+        (await Promise.race([
+          Promise.all([
+            extractAppIdFromSteamEmuIni(dir).catch(() => null),
+            extractRealAppIdFromOnlineFixIni(dir).catch(() => null),
+            readGoldbergAppId(gamePath).catch(() => null), // Add Goldberg support
+            extractAppIdFromTenokeConfig(dir).catch(() => null), // Add TENOKE support
+          ]).then(
+            ([steamEmuId, onlineFixId, goldbergId, tenokeId]) =>
+              steamEmuId || onlineFixId || goldbergId || tenokeId
+          ),
+          new Promise<null>((_, reject) =>
+            setTimeout(
+              () => reject(new Error("AppID extraction timeout")),
+              5000
+            )
+          ),
+        ]).catch(() => {
+          return null;
+        })) ||
+        detection?.app_id;
       if (!appId) {
         toast.error("Could not detect game AppID", {
           style: { background: "rgb(185 28 28)" },
@@ -209,8 +279,12 @@ const useAddGameWorkflow = () => {
       // Check if game already exists by appId
       const { games } = useMyGamesStore.getState();
       const existingGame = games.find((g) => String(g.appId) === String(appId));
+      const saveFiles = known.saveFiles?.length
+        ? known.saveFiles
+        : detection?.watch_files ?? [];
 
       if (existingGame) {
+        await trackSaveFiles(existingGame.appId, saveFiles);
         // Game already exists, just update the exe path if different
         if (existingGame.exePath !== gamePath || existingGame.dir !== dir) {
           await setGameExePath(String(appId), gamePath, dir);
@@ -374,6 +448,7 @@ const useAddGameWorkflow = () => {
 
       // Save data with atomic operations
       await saveGameDataAtomically(gameData, achievements);
+      await trackSaveFiles(metadata.steam_appid, saveFiles);
 
       setAddGameLoadingProgress(100);
       await new Promise((resolve) => setTimeout(resolve, 800)); // Reduced delay
@@ -493,6 +568,25 @@ const useAddGameWorkflow = () => {
       return null;
     }
   }
+  async function trackSaveFiles(appId: number, files: string[]) {
+    for (const file of files) {
+      await saveToTrackList(Number(appId), file).catch(() => {});
+    }
+  }
+
+  // Best emulator detection for the exe's directory, whose save files the
+  // watcher tracks even before the parsers have found them.
+  async function detectEmulator(gamePath: string) {
+    const gameDir = gamePath.substring(
+      0,
+      Math.max(gamePath.lastIndexOf("/"), gamePath.lastIndexOf("\\"))
+    );
+    const detections = await invoke<EmulatorDetection[]>("detect_emulator", {
+      gameDir,
+    }).catch(() => []);
+    return detections[0] ?? null;
+  }
+
   function getGameNameAndDir(path: string) {
     const normalizedPath = path.replace(/\\/g, "/");
     const dir = normalizedPath.substring(0, normalizedPath.lastIndexOf("/"));
@@ -502,7 +596,7 @@ const useAddGameWorkflow = () => {
     const name = fileWithExt.replace(/\.[^/.]+$/, ""); // Remove extension
     return { dir: dir.replace(/\//g, "\\"), name };
   }
-  return { getGamePath };
+  return { getGamePath, addLauncherGame, discoverLauncherGames };
 };
 
 export default useAddGameWorkflow;
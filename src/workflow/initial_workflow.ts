@@ -3,6 +3,7 @@ import useMyGamesStore from "@/store/my-games-store";
 import useHowLongToBeatStore from "@/store/how-long-to-beat-store";
 import { SteamSchemaResponse } from "@/types/achievements";
 import { GameStoreData } from "@/types/Game";
+import { syncLibraryToDb } from "@/lib/library-db";
 import { HowLongToBeatGame } from "@/types/howLongToBeat";
 import { load, Store } from "@tauri-apps/plugin-store";
 import { useEffect, useState } from "react";
@@ -64,6 +65,10 @@ const useInitialWorkflow = () => {
       const trackedList: { appid: number; filePath: string }[] =
         (await tracked_files_store.get("trackedAchievementsFiles")) || [];
       setTrackedAchievementsFiles(trackedList);
+      syncLibraryToDb(
+        Object.values(obj) as GameStoreData[],
+        Object.values(objAchievements) as SteamSchemaResponse[]
+      );
     })();
   }, [
     addGame,
@@ -0,0 +1,49 @@
+import { invoke } from "@tauri-apps/api/core";
+import { refreshUnlocksFromDb } from "@/lib/library-db";
+
+export type ImportSource = "playnite" | "steam" | "gog_galaxy";
+
+export interface ImportPreview {
+  source: ImportSource;
+  path: string;
+  matched: number;
+  unmatched: number;
+}
+
+export interface ImportReport {
+  games_updated: number;
+  playtime_seconds_added: number;
+  unlocks_added: number;
+  skipped: number;
+  app_ids: string[];
+}
+
+const useLauncherImportWorkflow = () => {
+  const previewImport = async (source: ImportSource, path: string) => {
+    return await invoke<ImportPreview>("preview_import", { source, path });
+  };
+
+  /**
+   * Merges playtime and unlocks from another launcher, then copies the new
+   * unlocks into the achievement stores so the library shows them right away.
+   */
+  const applyImport = async (
+    source: ImportSource,
+    path: string,
+    appIds?: string[]
+  ) => {
+    const report = await invoke<ImportReport>("apply_import", {
+      source,
+      path,
+      appIds: appIds ?? null,
+    });
+    for (const appId of report.app_ids) {
+      await refreshUnlocksFromDb(appId).catch(() => {});
+    }
+    return report;
+  };
+
+  return { previewImport, applyImport };
+};
+
+export default useLauncherImportWorkflow;
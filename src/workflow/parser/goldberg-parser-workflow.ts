@@ -1,4 +1,5 @@
 import { path } from "@tauri-apps/api";
+import { invoke } from "@tauri-apps/api/core";
 import { readFile, exists } from "@tauri-apps/plugin-fs";
 import sharedParsingWorkflow from "./shared-parse-workflow";
 
@@ -153,21 +154,26 @@ const useGoldbergParserWorkflow = ({
 
       for (const fileInfo of goldbergFiles) {
         try {
-          const fileContent = new TextDecoder().decode(
-            await readFile(fileInfo.filePath)
-          );
-
           // Determine parsing method based on file extension
           let parsedData: { name: string; achievedAt: number }[] = [];
           const fileName = fileInfo.filePath.toLowerCase();
 
-          if (fileName.endsWith(".json")) {
-            parsedData = parseGoldbergJson(fileContent);
-          } else if (fileName.endsWith(".ini")) {
-            parsedData = parseGoldbergIni(fileContent);
-          } else if (fileName.endsWith(".bin")) {
-            // For now, skip binary files - would need special handling
-            continue;
+          if (
+            fileName.endsWith(".bin") ||
+            fileName.endsWith(".dat") ||
+            fileName.endsWith(".cfg")
+          ) {
+            // Binary and CFG saves are decoded by the backend
+            parsedData = await decodeSaveFile(fileInfo.filePath, app_id);
+          } else {
+            const fileContent = new TextDecoder().decode(
+              await readFile(fileInfo.filePath)
+            );
+            if (fileName.endsWith(".json")) {
+              parsedData = parseGoldbergJson(fileContent);
+            } else if (fileName.endsWith(".ini")) {
+              parsedData = parseGoldbergIni(fileContent);
+            }
           }
           allAchievements.push(...parsedData);
           processedFiles.push(fileInfo.filePath);
@@ -198,6 +204,30 @@ const useGoldbergParserWorkflow = ({
     }
   }
 
+  // Decode stats.bin, Achievements.Bin, achieve.dat and CreamAPI.Achievements.cfg
+  async function decodeSaveFile(
+    filePath: string,
+    app_id: number
+  ): Promise<{ name: string; achievedAt: number }[]> {
+    const decoded = await invoke<{
+      unlocks: Record<
+        string,
+        { unlocked: boolean; unlock_time: number | null }
+      >;
+      stats: Record<string, number>;
+    } | null>("decode_save_file", {
+      path: filePath,
+      appId: app_id.toString(),
+    });
+    if (!decoded) return [];
+    return Object.entries(decoded.unlocks)
+      .filter(([, unlock]) => unlock.unlocked)
+      .map(([name, unlock]) => ({
+        name,
+        achievedAt: unlock.unlock_time ?? Math.floor(Date.now() / 1000),
+      }));
+  }
+
   // Parse Goldberg JSON achievement files
   function parseGoldbergJson(
     content: string
@@ -10,6 +10,7 @@ import useRuneParserWorkflow from "./rune-parser.workflow";
 import useCodexParserWorkflow from "./codex-parsing-workflow";
 import useOnlineFixParserWorkflow from "./online-fix-parser-workflow";
 import useTenokeParserWorkflow from "./tenoke-parser-workflow";
+import { syncAchievementsToDb } from "@/lib/library-db";
 
 const useParsingWorkflow = ({
   appid,
@@ -141,6 +142,7 @@ const useParsingWorkflow = ({
 
     // Update the store once with all changes
     updateAchievement(app_id, { ...updated });
+    await syncAchievementsToDb(app_id, updated).catch(() => {});
 
     return updated;
   }
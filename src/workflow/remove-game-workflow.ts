@@ -1,5 +1,6 @@
 import useMyGamesStore from "@/store/my-games-store";
 import { load } from "@tauri-apps/plugin-store";
+import { removeGameFromDb } from "@/lib/library-db";
 import { toast } from "sonner";
 
 const useRemoveGameWorkflow = () => {
@@ -11,6 +12,7 @@ const useRemoveGameWorkflow = () => {
       const store = await load("my-games.json");
       await store.delete(`game_${appId}`);
       await store.save();
+      await removeGameFromDb(appId).catch(() => {});
       toast.success("Game removed successfully", {
         description: "The game has been removed from your library.",
         style: {
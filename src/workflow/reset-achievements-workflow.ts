@@ -1,6 +1,7 @@
 import useAchievementsStore from "@/store/achievements-store";
 import { writeFile } from "@tauri-apps/plugin-fs";
 import useInitialWorkflow from "./initial_workflow";
+import { resetUnlocksInDb } from "@/lib/library-db";
 
 const useResetAchievementsWorkflow = (appid: number) => {
   const {
@@ -33,6 +34,7 @@ const useResetAchievementsWorkflow = (appid: number) => {
         updatedAchievementData
       );
       await achievementsStore.save();
+      await resetUnlocksInDb(appid, updatedAchievementData).catch(() => {});
     }
   };
 
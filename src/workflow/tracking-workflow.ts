@@ -1,58 +1,31 @@
 import useAchievementsStore from "@/store/achievements-store";
-import { invoke, convertFileSrc } from "@tauri-apps/api/core";
-import { resolveResource } from "@tauri-apps/api/path";
+import { invoke } from "@tauri-apps/api/core";
 import { useEffect, useRef } from "react";
 import { listen } from "@tauri-apps/api/event";
 import useMyGamesStore from "@/store/my-games-store";
 import useParsingWorkflow from "./parser/parse-workflow";
 import { toast } from "sonner";
 import useProfileStore from "@/store/profile-store";
+import useScoringSystemWorkflow from "./scoring-system-worfklow";
+import { Achievement } from "@/types/achievements";
+import { applyUnlocksToStores, relockInStores } from "@/lib/library-db";
 
 const useTrackingWorkflow = () => {
   const { trackAchievementsFiles, getTrackedAchievementsFiles } =
     useAchievementsStore();
 
-  // Helper function to play notification sound with proper path resolution
-  const playNotificationSound = async (soundFile: string) => {
-    try {
-      let audioSrc: string;
-
-      // Check if we're in a Tauri environment
-      const isTauri = typeof (window as any).__TAURI__ !== "undefined";
-
-      if (isTauri) {
-        try {
-          // First try to resolve as a bundled resource
-          const resourcePath = await resolveResource(soundFile);
-          audioSrc = convertFileSrc(resourcePath);
-        } catch (resourceError) {
-          // Fallback to direct file conversion if resource resolution fails
-          audioSrc = convertFileSrc(soundFile);
-        }
-      } else {
-        // For development mode, use direct path
-        audioSrc = `/${soundFile}`;
-      }
-
-      const audio = new Audio(audioSrc);
-      audio.volume = 1;
-      await audio.play();
-    } catch (error) {
-      // Failed to play notification sound
-    }
-  };
   const { getGameById } = useMyGamesStore();
   const { parseAchievements } = useParsingWorkflow({
     exePath: "",
     appid: 0,
   });
   const { getProfile } = useProfileStore();
+  const { calculateAchievementTier } = useScoringSystemWorkflow();
   const isWatcherSetup = useRef(false);
   const currentPaths = useRef<string>("");
   const eventListenerSetup = useRef(false);
   const processedAchievements = useRef<Set<string>>(new Set()); // Track processed achievements
   const lastProcessedTime = useRef<number>(0); // Debounce mechanism
-  const isProcessingNotification = useRef<boolean>(false); // Prevent concurrent notifications
 
   useEffect(() => {
     const getPaths = trackAchievementsFiles.map((item) => item.filePath);
@@ -64,7 +37,13 @@ const useTrackingWorkflow = () => {
       currentPaths.current = pathsString;
       isWatcherSetup.current = true;
 
-      invoke("track_files", { paths: Array.from(new Set(getPaths)) })
+      const appIds = Object.fromEntries(
+        trackAchievementsFiles.map((item) => [
+          item.filePath,
+          String(item.appid),
+        ])
+      );
+      invoke("track_files", { paths: Array.from(new Set(getPaths)), appIds })
         .then(() => {
           // track_files invoked successfully
         })
@@ -95,7 +74,11 @@ const useTrackingWorkflow = () => {
         path: string;
         kind: string;
         added_lines: string[];
-        content: string;
+        removed_lines: string[];
+        unlocked: { api_name: string; unlock_time: number | null }[];
+        size: number;
+        hash: string;
+        binary: boolean;
       };
 
       const game = getGameBasedOnPath(payload.path);
@@ -145,208 +128,33 @@ const useTrackingWorkflow = () => {
           }
 
           if (achievementNames.size > 0) {
-            // Prevent concurrent notification processing
-            if (isProcessingNotification.current) {
-              return;
-            }
-
-            // Update the last processed time
             lastProcessedTime.current = now;
-            isProcessingNotification.current = true;
-
-            parseAchievements(appId, exePath)
-              .then(async () => {
-                await new Promise((resolve) => setTimeout(resolve, 100));
-                const { achievements: updatedAchievements } =
-                  useAchievementsStore.getState();
-                const currentAchievements = updatedAchievements.find(
-                  (ach) => Number(ach.gameId) === Number(appId)
-                );
-
-                if (
-                  currentAchievements?.game?.availableGameStats?.achievements
-                ) {
-                  const allAchievements =
-                    currentAchievements.game.availableGameStats.achievements;
-                  const unlockedAchievements = Array.from(achievementNames)
-                    .map((achievementName) => {
-                      return allAchievements.find(
-                        (ach) =>
-                          ach.name === achievementName ||
-                          ach.displayName === achievementName
-                      );
-                    })
-                    .filter(Boolean) // Remove undefined entries
-                    .filter(
-                      (ach) =>
-                        ach?.achievedAt &&
-                        ach.achievedAt !== "0" &&
-                        ach.achievedAt !== ""
-                    ); // Only include actually unlocked achievements
-                  if (unlockedAchievements.length > 0) {
-                    // Show web toast notification ONCE for all achievements
-                    toast(
-                      `${unlockedAchievements.length} new achievement${
-                        unlockedAchievements.length > 1 ? "s" : ""
-                      } unlocked!`,
-                      {
-                        duration: 3000,
-                        style: {
-                          backgroundColor: "#a21caf", // tailwindcss purple-500
-                        },
-                      }
-                    );
-
-                    // Play sound ONCE for all achievements
-                    try {
-                      const soundPath = getProfile().notificationSound;
-                      if (soundPath) {
-                        await playNotificationSound(soundPath);
-                      }
-
-                      // Show ONE native notification for all achievements
-                      const firstAchievement = unlockedAchievements[0];
-                      const achievementTitle =
-                        unlockedAchievements.length === 1
-                          ? firstAchievement?.displayName ||
-                            firstAchievement?.name ||
-                            "Achievement Unlocked"
-                          : `${unlockedAchievements.length} Achievements Unlocked!`;
-                      await invoke("toast_notification", {
-                        iconPath:
-                          firstAchievement?.icon || game.header_image || "",
-                        gameName: game.name,
-                        achievementName: achievementTitle,
-                        soundPath: soundPath || null,
-                        hero: game.header_image || "",
-                        progress: null,
-                        isRare: false,
-                      });
-                    } catch (error) {
-                    } finally {
-                      // Reset processing flag
-                      isProcessingNotification.current = false;
-                    }
-                  } else {
-                    isProcessingNotification.current = false;
-                  }
-                } else {
-                  isProcessingNotification.current = false;
-                }
-              })
-              .catch(() => {
-                isProcessingNotification.current = false; // Reset flag on error
-              });
-          } else {
-          }
-        } else {
-          // No new lines added, but check if content changed (for JSON modifications like GSE Saves)
-          if (payload.content && payload.content.length > 0) {
-            // NEW: Try JSON state change detection FIRST (10-second window, immediate detection)
-            const handledByJsonDetection = detectJsonAchievementStateChanges(
-              payload.content,
+            handleAchievementNotifications(
+              achievementNames,
               appId,
               exePath,
               game
             );
-
-            if (handledByJsonDetection) {
+          }
+        } else {
+          // No new lines added (JSON saves like GSE Saves, binary saves): use the unlocks the backend parsed
+          if (payload.size > 0) {
+            if (payload.unlocked && payload.unlocked.length > 0) {
+              applyUnlocksToStores(appId, payload.unlocked)
+                .catch(() => {})
+                .then(() =>
+                  handleAchievementNotifications(
+                    payload.unlocked.map((unlock) => unlock.api_name),
+                    appId,
+                    exePath,
+                    game
+                  )
+                );
               return; // Exit early - no need for the 30-second fallback
             }
             // ORIGINAL FALLBACK CODE (30-second window) - only runs if JSON detection found nothing
-            // Prevent concurrent notification processing
-            if (isProcessingNotification.current) {
-              return;
-            }
-
-            // Update the last processed time
             lastProcessedTime.current = now;
-            isProcessingNotification.current = true;
-
-            // Re-parse achievements from full content to detect changes
-            parseAchievements(appId, exePath)
-              .then(async () => {
-                await new Promise((resolve) => setTimeout(resolve, 100));
-                const { achievements: updatedAchievements } =
-                  useAchievementsStore.getState();
-                const currentAchievements = updatedAchievements.find(
-                  (ach) => Number(ach.gameId) === Number(appId)
-                );
-
-                if (
-                  currentAchievements?.game?.availableGameStats?.achievements
-                ) {
-                  const allAchievements =
-                    currentAchievements.game.availableGameStats.achievements;
-
-                  // Find recently unlocked achievements (unlocked in the last 30 seconds)
-                  const now = Math.floor(Date.now() / 1000);
-                  const recentlyUnlockedAchievements = allAchievements.filter(
-                    (ach) => {
-                      const achievedAt = parseInt(ach.achievedAt || "0");
-                      return (
-                        achievedAt > 0 &&
-                        achievedAt !== 0 &&
-                        ach.achievedAt !== "" &&
-                        now - achievedAt <= 30 // Unlocked within last 30 seconds
-                      );
-                    }
-                  );
-                  if (recentlyUnlockedAchievements.length > 0) {
-                    // Show web toast notification ONCE for all achievements
-                    toast(
-                      `${recentlyUnlockedAchievements.length} new achievement${
-                        recentlyUnlockedAchievements.length > 1 ? "s" : ""
-                      } unlocked!`,
-                      {
-                        duration: 3000,
-                        style: {
-                          backgroundColor: "#a21caf", // tailwindcss purple-500
-                        },
-                      }
-                    );
-
-                    // Play sound ONCE for all achievements
-                    try {
-                      const soundPath = getProfile().notificationSound;
-                      if (soundPath) {
-                        await playNotificationSound(soundPath);
-                      }
-
-                      // Show ONE native notification for all achievements
-                      const firstAchievement = recentlyUnlockedAchievements[0];
-                      const achievementTitle =
-                        recentlyUnlockedAchievements.length === 1
-                          ? firstAchievement?.displayName ||
-                            firstAchievement?.name ||
-                            "Achievement Unlocked"
-                          : `${recentlyUnlockedAchievements.length} Achievements Unlocked!`;
-                      await invoke("toast_notification", {
-                        iconPath:
-                          firstAchievement?.icon || game.header_image || "",
-                        gameName: game.name,
-                        achievementName: achievementTitle,
-                        soundPath: soundPath || null,
-                        hero: game.header_image || "",
-                        progress: null,
-                        isRare: false,
-                      });
-                    } catch (error) {
-                    } finally {
-                      // Reset processing flag
-                      isProcessingNotification.current = false;
-                    }
-                  } else {
-                    isProcessingNotification.current = false;
-                  }
-                } else {
-                  isProcessingNotification.current = false;
-                }
-              })
-              .catch(() => {
-                isProcessingNotification.current = false; // Reset flag on error
-              });
-          } else {
+            notifyRecentUnlocks(appId, exePath, game);
           }
         }
       }
@@ -357,13 +165,65 @@ const useTrackingWorkflow = () => {
       unlisten.then((fn) => fn());
     };
   }, []); // Empty dependency array - setup listener only once
+
+  // Unlocks found when a file starts being watched happened while the app was
+  // closed: refresh the games quietly instead of notifying.
+  useEffect(() => {
+    const unlisten = listen("achievements-backfilled", (event) => {
+      const payload = event.payload as {
+        games: {
+          app_id: string;
+          paths: string[];
+          achievements: { api_name: string; unlock_time: number | null }[];
+        }[];
+      };
+      payload.games.forEach(({ app_id, achievements }) => {
+        const game = getGameById(String(app_id));
+        if (game) {
+          applyUnlocksToStores(game.appId, achievements)
+            .then(() => parseAchievements(game.appId, game.exePath))
+            .catch(() => {});
+        }
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // A save that lost unlocks, or was deleted or cut short: the backend has
+  // already relocked them in the library, mirror that and refresh the game.
+  useEffect(() => {
+    const unlistenRelocked = listen("achievement-relocked", (event) => {
+      const payload = event.payload as {
+        path: string;
+        app_id: string | null;
+        achievements: string[];
+      };
+      refreshRelockedGame(payload.path, payload.app_id, payload.achievements);
+    });
+    const unlistenReset = listen("tracked-file-reset", (event) => {
+      const payload = event.payload as {
+        path: string;
+        app_id: string | null;
+        reason: string;
+        relocked: string[];
+      };
+      refreshRelockedGame(payload.path, payload.app_id, payload.relocked);
+    });
+
+    return () => {
+      unlistenRelocked.then((fn) => fn());
+      unlistenReset.then((fn) => fn());
+    };
+  }, []);
   useEffect(() => {
     return () => {
       isWatcherSetup.current = false;
       currentPaths.current = "";
       eventListenerSetup.current = false;
       processedAchievements.current.clear(); // Clear processed achievements on cleanup
-      isProcessingNotification.current = false; // Reset processing flag
     };
   }, []);
 
@@ -436,102 +296,9 @@ const useTrackingWorkflow = () => {
     }
   }
 
-  /**
-   * Detects JSON-based achievement state changes (earned: false → true)
-   * Specifically handles Goldberg GSE Saves format where no new lines are added
-   * This runs BEFORE the 30-second fallback to provide immediate detection
-   */
-  function detectJsonAchievementStateChanges(
-    content: string,
-    appId: number,
-    exePath: string,
-    game: any
-  ): boolean {
-    try {
-      // Only process JSON files
-      if (!content.trim().startsWith("{") && !content.trim().startsWith("[")) {
-        return false;
-      }
-      // Parse the JSON content
-      const data = JSON.parse(content);
-      const newlyUnlockedAchievements: string[] = [];
-
-      // Handle GSE Saves format (object with achievement names as keys)
-      if (
-        typeof data === "object" &&
-        !Array.isArray(data) &&
-        !data.achievements
-      ) {
-        for (const [achievementName, achData] of Object.entries(data)) {
-          const achievement = achData as any;
-
-          // Check if earned is explicitly true and has a recent timestamp
-          if (achievement.earned === true && achievement.earned_time) {
-            const earnedTime = Number(achievement.earned_time);
-            const now = Math.floor(Date.now() / 1000);
-            const timeDiff = now - earnedTime;
-
-            // Only detect achievements earned in the last 10 seconds
-            // This is tighter than the 30-second fallback window
-            if (timeDiff <= 10) {
-              newlyUnlockedAchievements.push(achievementName);
-            }
-          }
-        }
-      }
-
-      // Handle standard Goldberg JSON format (object with achievements property)
-      if (data.achievements && typeof data.achievements === "object") {
-        for (const [achievementName, achData] of Object.entries(
-          data.achievements
-        )) {
-          const achievement = achData as any;
-
-          if (
-            achievement.earned ||
-            achievement.unlocked ||
-            achievement.achieved
-          ) {
-            const unlockTime =
-              achievement.unlock_time ||
-              achievement.unlocktime ||
-              achievement.time ||
-              0;
-
-            if (unlockTime > 0) {
-              const now = Math.floor(Date.now() / 1000);
-              const timeDiff = now - Number(unlockTime);
-
-              if (timeDiff <= 10) {
-                newlyUnlockedAchievements.push(achievementName);
-              }
-            }
-          }
-        }
-      }
-
-      // If we found newly unlocked achievements, trigger notifications
-      if (newlyUnlockedAchievements.length > 0) {
-        // Use the same notification pipeline as the main workflow
-        handleAchievementNotifications(
-          newlyUnlockedAchievements,
-          appId,
-          exePath,
-          game
-        );
-
-        return true; // Signal that we handled this
-      }
-
-      return false; // No new achievements detected
-    } catch (error) {
-      return false;
-    }
-  }
-
   /**
    * Centralized achievement notification handler
-   * Used by both added_lines detection and JSON state change detection
+   * Used by both added_lines detection and backend-parsed unlocks
    */
   async function handleAchievementNotifications(
     achievementNames: string[] | Set<string>,
@@ -543,22 +310,7 @@ const useTrackingWorkflow = () => {
       ? achievementNames
       : Array.from(achievementNames);
     try {
-      // Re-parse achievements to get latest state
-      await parseAchievements(appId, exePath);
-      await new Promise((resolve) => setTimeout(resolve, 100));
-
-      const { achievements: updatedAchievements } =
-        useAchievementsStore.getState();
-      const currentAchievements = updatedAchievements.find(
-        (ach) => Number(ach.gameId) === Number(appId)
-      );
-
-      if (!currentAchievements?.game?.availableGameStats?.achievements) {
-        return;
-      }
-
-      const allAchievements =
-        currentAchievements.game.availableGameStats.achievements;
+      const allAchievements = await reparseAchievements(appId, exePath);
 
       // Find matching unlocked achievements
       const unlockedAchievements = achievementNamesArray
@@ -569,51 +321,125 @@ const useTrackingWorkflow = () => {
               ach.displayName === achievementName
           );
         })
-        .filter(Boolean)
+        .filter((ach): ach is Achievement => Boolean(ach))
         .filter(
           (ach) =>
-            ach?.achievedAt && ach.achievedAt !== "0" && ach.achievedAt !== ""
-        );
-      if (unlockedAchievements.length > 0) {
-        // Show web toast notification
-        toast(
-          `${unlockedAchievements.length} new achievement${
-            unlockedAchievements.length > 1 ? "s" : ""
-          } unlocked!`,
-          {
-            duration: 3000,
-            style: {
-              backgroundColor: "#a21caf",
-            },
-          }
+            ach.achievedAt && ach.achievedAt !== "0" && ach.achievedAt !== ""
         );
+      await showUnlockNotifications(unlockedAchievements, appId, game);
+    } catch (error) {}
+  }
 
-        // Play sound
-        const soundPath = getProfile().notificationSound;
-        if (soundPath) {
-          await playNotificationSound(soundPath);
-        }
+  /**
+   * Fallback for saves whose changes could not be attributed to achievements:
+   * notifies for everything unlocked in the last 30 seconds
+   */
+  async function notifyRecentUnlocks(
+    appId: number,
+    exePath: string,
+    game: any
+  ) {
+    try {
+      const allAchievements = await reparseAchievements(appId, exePath);
+      const now = Math.floor(Date.now() / 1000);
+      const recentlyUnlockedAchievements = allAchievements.filter((ach) => {
+        const achievedAt = parseInt(ach.achievedAt || "0");
+        return achievedAt > 0 && now - achievedAt <= 30;
+      });
+      await showUnlockNotifications(recentlyUnlockedAchievements, appId, game);
+    } catch (error) {}
+  }
+
+  /**
+   * Re-parses the game's save files and returns its achievements with the
+   * latest unlock state
+   */
+  async function reparseAchievements(
+    appId: number,
+    exePath: string
+  ): Promise<Achievement[]> {
+    await parseAchievements(appId, exePath);
+    await new Promise((resolve) => setTimeout(resolve, 100));
+
+    const { achievements: updatedAchievements } =
+      useAchievementsStore.getState();
+    const currentAchievements = updatedAchievements.find(
+      (ach) => Number(ach.gameId) === Number(appId)
+    );
+    return currentAchievements?.game?.availableGameStats?.achievements ?? [];
+  }
+
+  /**
+   * Shows one in-app toast for the batch and queues one native notification
+   * per achievement. The backend queue orders, batches and de-duplicates them
+   * by appId/achievementId, so nothing is aggregated here.
+   */
+  async function showUnlockNotifications(
+    unlockedAchievements: Achievement[],
+    appId: number,
+    game: any
+  ) {
+    if (unlockedAchievements.length === 0) {
+      return;
+    }
 
-        // Show native notification
-        const firstAchievement = unlockedAchievements[0];
-        const achievementTitle =
-          unlockedAchievements.length === 1
-            ? firstAchievement?.displayName ||
-              firstAchievement?.name ||
-              "Achievement Unlocked"
-            : `${unlockedAchievements.length} Achievements Unlocked!`;
-
-        await invoke("toast_notification", {
-          iconPath: firstAchievement?.icon || game.header_image || "",
-          gameName: game.name,
-          achievementName: achievementTitle,
-          soundPath: soundPath || null,
+    // Show web toast notification
+    toast(
+      `${unlockedAchievements.length} new achievement${
+        unlockedAchievements.length > 1 ? "s" : ""
+      } unlocked!`,
+      {
+        duration: 3000,
+        style: {
+          backgroundColor: "#a21caf",
+        },
+      }
+    );
+
+    // The backend plays the sound when each toast is shown
+    const soundPath = getProfile().notificationSound;
+
+    // Show native notifications
+    for (const achievement of unlockedAchievements) {
+      await invoke("toast_notification", {
+        notification: {
+          icon_path: achievement.icon || game.header_image || "",
+          game_name: game.name,
+          achievement_name: achievement.displayName || achievement.name,
+          sound_path: soundPath || null,
           hero: game.header_image || "",
           progress: null,
-          isRare: false,
-        });
-      } else {
-      }
+          is_rare: false,
+          rarity: rarityOf(achievement),
+          app_id: String(appId),
+          achievement_id: achievement.name,
+        },
+      }).catch(() => {});
+    }
+  }
+
+  // Rarity tier sent with toasts; null lets the backend fall back to Common
+  // when the global unlock percentage has not been fetched yet.
+  function rarityOf(achievement?: Achievement) {
+    const percentage = parseFloat(achievement?.percent ?? "");
+    return isNaN(percentage) ? null : calculateAchievementTier(percentage);
+  }
+
+  async function refreshRelockedGame(
+    path: string,
+    appId: string | null,
+    relocked: string[]
+  ) {
+    const game = appId ? getGameById(String(appId)) : getGameBasedOnPath(path);
+    if (!game) {
+      return;
+    }
+    for (const name of relocked) {
+      processedAchievements.current.delete(`${game.appId}_${name}`);
+    }
+    try {
+      await relockInStores(game.appId, relocked);
+      await parseAchievements(game.appId, game.exePath);
     } catch (error) {}
   }
 
@@ -1,6 +1,7 @@
 import { GameStoreData } from "@/types/Game";
 import useMyGamesStore from "@/store/my-games-store";
 import { load } from "@tauri-apps/plugin-store";
+import { syncGameToDb } from "@/lib/library-db";
 
 const useUpdateGameWorkflow = () => {
   const {
@@ -26,6 +27,7 @@ const useUpdateGameWorkflow = () => {
         const updatedGame = { ...gameData, status };
         await store.set(gameKey, updatedGame);
         await store.save();
+        await syncGameToDb(updatedGame);
       }
     } catch (error) {}
   };
@@ -41,6 +43,7 @@ const useUpdateGameWorkflow = () => {
         const updatedGame = { ...gameData, my_rating: rating };
         await store.set(gameKey, updatedGame);
         await store.save();
+        await syncGameToDb(updatedGame);
       }
     } catch (error) {}
   };
@@ -56,6 +59,7 @@ const useUpdateGameWorkflow = () => {
         const updatedGame = { ...gameData, playtime };
         await store.set(gameKey, updatedGame);
         await store.save();
+        await syncGameToDb(updatedGame);
       }
     } catch (error) {}
   };
@@ -71,6 +75,7 @@ const useUpdateGameWorkflow = () => {
         const updatedGame = { ...gameData, header_image: headerImage };
         await store.set(gameKey, updatedGame);
         await store.save();
+        await syncGameToDb(updatedGame);
       }
     } catch (error) {
       console.error("Failed to save header image:", error);
@@ -88,6 +93,7 @@ const useUpdateGameWorkflow = () => {
         const updatedGame = { ...gameData, library_cover: libraryCover };
         await store.set(gameKey, updatedGame);
         await store.save();
+        await syncGameToDb(updatedGame);
       }
     } catch (error) {
       console.error("Failed to save library cover:", error);
@@ -109,6 +115,7 @@ const useUpdateGameWorkflow = () => {
         const updatedGame = { ...gameData, exePath, dir };
         await store.set(gameKey, updatedGame);
         await store.save();
+        await syncGameToDb(updatedGame);
       }
     } catch (error) {
       console.error("Failed to save exe path:", error);